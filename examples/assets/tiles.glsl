@@ -0,0 +1,26 @@
+#type vertex
+#version 330 core
+layout (location = 0) in vec2 aPos;
+layout (location = 1) in vec2 aOffset;
+layout (location = 2) in vec3 aColor;
+
+uniform mat4 uProjection;
+
+out vec3 ourColor;
+
+void main()
+{
+    gl_Position = uProjection * vec4(aPos + aOffset, 0.0, 1.0);
+    ourColor = aColor;
+}
+
+#type fragment
+#version 330 core
+out vec4 FragColor;
+
+in vec3 ourColor;
+
+void main()
+{
+    FragColor = vec4(ourColor, 1.0);
+}
@@ -0,0 +1,49 @@
+//! Replays a trace captured with `gl::trace` in a headless context and saves
+//! the framebuffer after every draw call as `frame<f>_draw<d>.png`.
+//!
+//! cargo run --example replay_trace --features gl-trace,png -- <trace> [output directory]
+
+use crean::graphics::gl::trace::{Trace, Value};
+use crean::graphics::HeadlessContext;
+use std::error::Error;
+use std::path::PathBuf;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let trace_path = args
+        .next()
+        .ok_or("usage: replay_trace <trace> [output directory]")?;
+    let output = PathBuf::from(args.next().unwrap_or_else(|| ".".to_owned()));
+    std::fs::create_dir_all(&output)?;
+
+    let trace = Trace::load(trace_path)?;
+
+    // Size the context after the first viewport, which is the window's.
+    let (width, height) = trace
+        .calls
+        .iter()
+        .find(|call| call.function == "Viewport")
+        .and_then(|call| match call.args[..] {
+            [_, _, Value::Int(width), Value::Int(height)] => Some((width as u32, height as u32)),
+            _ => None,
+        })
+        .unwrap_or((512, 512));
+    let _context = HeadlessContext::new(width, height)?;
+
+    let mut draws = 0;
+    trace.replay(|draw| {
+        let path = output.join(format!("frame{}_draw{}.png", draw.frame, draw.draw));
+        if let Err(error) = draw.read_framebuffer().save_png(&path) {
+            eprintln!("failed to save {}: {}", path.display(), error);
+        }
+        draws += 1;
+    })?;
+
+    println!(
+        "replayed {} frames, saved {} draws to {}",
+        trace.frames.len(),
+        draws,
+        output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crean::app::{AppConfig, AppContext, Application};
+use crean::graphics::{gl, Buffer, Event, PrimitiveMode, ShaderProgram, VertexArray};
+use crean::input::{ButtonAction, Key};
+use crean::maths::Matrix4;
+use std::error::Error;
+
+const SHADER_SOURCE: &str = include_str!("assets/tiles.glsl");
+
+const COLUMNS: usize = 100;
+const ROWS: usize = 100;
+
+struct Tiles {
+    vertex_array: VertexArray,
+    shader_program: ShaderProgram,
+}
+
+impl Application for Tiles {
+    fn init(context: &mut AppContext) -> Result<Self, Box<dyn Error>> {
+        context.window().set_clear_color(0.1, 0.1, 0.1, 1.0);
+
+        // A single hexagon shared by every tile.
+        let vertices: [f32; 14] = [
+            0.0, 0.0, // center
+            0.0, -0.5, // bottom
+            0.43, -0.25, // bottom right
+            0.43, 0.25, // top right
+            0.0, 0.5, // top
+            -0.43, 0.25, // top left
+            -0.43, -0.25, // bottom left
+        ];
+        let indices = [0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 5, 0, 5, 6, 0, 6, 1];
+
+        // Every tile only differs in its offset and color.
+        let mut offsets = Vec::with_capacity(COLUMNS * ROWS * 2);
+        let mut colors = Vec::with_capacity(COLUMNS * ROWS * 3);
+        for row in 0..ROWS {
+            for column in 0..COLUMNS {
+                let shift = if row % 2 == 0 { 0.0 } else { 0.43 };
+                offsets.push(column as f32 * 0.86 + shift);
+                offsets.push(row as f32 * 0.75);
+
+                colors.push(column as f32 / COLUMNS as f32);
+                colors.push(row as f32 / ROWS as f32);
+                colors.push(0.6);
+            }
+        }
+
+        let mut vertex_array = VertexArray::new();
+        vertex_array.put_indices_buffer(Buffer::indices(&indices));
+        vertex_array.put_array_buffer(0, Buffer::array(&vertices, 2));
+        vertex_array.put_instance_buffer(1, Buffer::array(&offsets, 2));
+        vertex_array.put_instance_buffer(2, Buffer::array(&colors, 3));
+
+        let mut shader_program = ShaderProgram::from_multi_shader(SHADER_SOURCE)?;
+        shader_program.bind();
+        shader_program.define_uniform("uProjection")?;
+        let projection_matrix = Matrix4::orthographic(
+            -1.0,
+            COLUMNS as f32 * 0.86 + 0.5,
+            -1.0,
+            ROWS as f32 * 0.75 + 0.5,
+            -1.0,
+            1.0,
+        );
+        shader_program.upload_uniform("uProjection", &projection_matrix)?;
+        shader_program.unbind();
+
+        Ok(Tiles {
+            vertex_array,
+            shader_program,
+        })
+    }
+
+    fn render(&mut self, _context: &mut AppContext, _alpha: f32) {
+        if let Err(error) = gl::check_error() {
+            println!("GL Error: {}", error);
+        }
+
+        // Draw every tile in a single draw call.
+        self.shader_program.bind();
+        self.vertex_array
+            .draw_instanced(PrimitiveMode::Triangles, COLUMNS * ROWS);
+        self.shader_program.unbind();
+    }
+
+    fn on_event(&mut self, context: &mut AppContext, event: &Event) {
+        if let Event::Key {
+            key: Key::Escape,
+            action: ButtonAction::Press,
+            ..
+        } = event
+        {
+            context.quit();
+        }
+    }
+}
+
+fn main() {
+    crean::app::run::<Tiles>(AppConfig::new(512, 512, "Tiles")).unwrap();
+}
@@ -0,0 +1,161 @@
+use crate::graphics::{Event, EventLoop, Window, WindowBuilder, WindowError};
+use crate::input::Input;
+use crate::time::{FrameStats, GameLoop};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("failed to create the application window")]
+    Window(#[from] WindowError),
+    #[error("application failed to initialize: {0}")]
+    Init(Box<dyn Error>),
+}
+
+/// Settings for `run`.
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub window: WindowBuilder,
+    /// How many times per second `Application::fixed_update` is called.
+    pub updates_per_second: u32,
+}
+
+impl AppConfig {
+    pub fn new<T>(width: u32, height: u32, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        AppConfig::from_window(WindowBuilder::new(width, height, title))
+    }
+
+    pub fn from_window(window: WindowBuilder) -> Self {
+        AppConfig {
+            window,
+            updates_per_second: 60,
+        }
+    }
+
+    pub fn updates_per_second(mut self, updates_per_second: u32) -> Self {
+        self.updates_per_second = updates_per_second;
+        self
+    }
+}
+
+/// The window, input and timing state handed to every `Application` hook.
+pub struct AppContext {
+    window: Window,
+    event_loop: EventLoop,
+    game_loop: GameLoop,
+}
+
+impl AppContext {
+    #[inline]
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    #[inline]
+    pub fn window_mut(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    #[inline]
+    pub fn input(&self) -> &Input {
+        self.event_loop.input()
+    }
+
+    #[inline]
+    pub fn event_loop_mut(&mut self) -> &mut EventLoop {
+        &mut self.event_loop
+    }
+
+    #[inline]
+    pub fn frame_stats(&self) -> &FrameStats {
+        self.game_loop.stats()
+    }
+
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.game_loop.frame_count()
+    }
+
+    /// Stops the application after the current frame.
+    pub fn quit(&mut self) {
+        self.window.set_should_close(true);
+    }
+}
+
+/// A game or tool driven by `run`. Only `init` is required; every other hook
+/// does nothing by default.
+///
+/// Each frame, `run` processes events (calling `on_resize` and `on_event`),
+/// then calls `fixed_update` as many times as the fixed timestep requires,
+/// then `update` and `render`, and finally presents the frame.
+pub trait Application: Sized {
+    /// Called once the window and OpenGL context exist, to create the
+    /// application's resources.
+    fn init(context: &mut AppContext) -> Result<Self, Box<dyn Error>>;
+
+    /// Called once per frame with the frame's delta time in seconds.
+    fn update(&mut self, _context: &mut AppContext, _dt: f32) {}
+
+    /// Called at the fixed rate set in `AppConfig`, with the fixed delta time
+    /// in seconds.
+    fn fixed_update(&mut self, _context: &mut AppContext, _dt: f32) {}
+
+    /// Called once per frame after updating. `alpha` is how far the frame is
+    /// between the last fixed update and the next, for interpolation.
+    fn render(&mut self, _context: &mut AppContext, _alpha: f32) {}
+
+    fn on_event(&mut self, _context: &mut AppContext, _event: &Event) {}
+
+    /// Called when the framebuffer is resized, after the window's viewport
+    /// has been updated.
+    fn on_resize(&mut self, _context: &mut AppContext, _width: u32, _height: u32) {}
+
+    /// Called once after the last frame, while the context is still alive.
+    fn shutdown(&mut self, _context: &mut AppContext) {}
+}
+
+/// Creates the window described by `config` and runs `A` until the window is
+/// closed or `AppContext::quit` is called.
+///
+/// ```ignore
+/// fn main() -> Result<(), AppError> {
+///     crean::app::run::<Game>(AppConfig::new(1280, 720, "Game"))
+/// }
+/// ```
+pub fn run<A: Application>(config: AppConfig) -> Result<(), AppError> {
+    let (window, event_loop) = config.window.build()?;
+    let mut context = AppContext {
+        window,
+        event_loop,
+        game_loop: GameLoop::new(config.updates_per_second),
+    };
+
+    let mut app = A::init(&mut context).map_err(AppError::Init)?;
+
+    while !context.window.should_close() {
+        let tick = context.game_loop.tick();
+
+        context.event_loop.process_events(&mut context.window);
+        let events = context.event_loop.events().to_vec();
+        for event in &events {
+            if let Event::Resized { width, height } = *event {
+                app.on_resize(&mut context, width, height);
+            }
+            app.on_event(&mut context, event);
+        }
+
+        for _ in 0..tick.fixed_steps {
+            app.fixed_update(&mut context, tick.fixed_delta_seconds());
+        }
+        app.update(&mut context, tick.delta_seconds());
+        app.render(&mut context, tick.alpha);
+
+        context.window.update();
+    }
+
+    app.shutdown(&mut context);
+    Ok(())
+}
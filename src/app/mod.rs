@@ -0,0 +1,2 @@
+mod application;
+pub use self::application::*;
@@ -0,0 +1,119 @@
+use std::fmt;
+
+/// A handle to an entity living in a `World`.
+///
+/// The generation is bumped every time an index is reused, so a handle to a
+/// despawned entity never aliases the entity that replaced it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Entities {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl Entities {
+    pub fn alloc(&mut self) -> Entity {
+        self.len += 1;
+
+        if let Some(index) = self.free.pop() {
+            self.alive[index as usize] = true;
+            Entity {
+                index,
+                generation: self.generations[index as usize],
+            }
+        } else {
+            let index = self.generations.len() as u32;
+            self.generations.push(0);
+            self.alive.push(true);
+            Entity {
+                index,
+                generation: 0,
+            }
+        }
+    }
+
+    pub fn free(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let index = entity.index as usize;
+        self.alive[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(entity.index);
+        self.len -= 1;
+
+        true
+    }
+
+    #[inline]
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.index as usize;
+        index < self.alive.len()
+            && self.alive[index]
+            && self.generations[index] == entity.generation
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.alive
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| **alive)
+            .map(move |(index, _)| Entity {
+                index: index as u32,
+                generation: self.generations[index],
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freed_indices_are_reused_with_a_new_generation() {
+        let mut entities = Entities::default();
+        let first = entities.alloc();
+        let second = entities.alloc();
+        assert!(entities.free(first));
+        assert!(!entities.free(first));
+
+        let reused = entities.alloc();
+        assert_eq!(reused.index(), first.index());
+        assert_eq!(reused.generation(), first.generation() + 1);
+        assert!(!entities.is_alive(first));
+        assert!(entities.is_alive(reused));
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities.iter().collect::<Vec<_>>(), [reused, second]);
+    }
+}
@@ -0,0 +1,14 @@
+mod entity;
+pub use self::entity::*;
+
+mod query;
+pub use self::query::*;
+
+mod schedule;
+pub use self::schedule::*;
+
+mod storage;
+pub use self::storage::*;
+
+mod world;
+pub use self::world::*;
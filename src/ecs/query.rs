@@ -0,0 +1,92 @@
+use crate::ecs::{Entity, SparseSet, World};
+use std::cell::{Ref, RefMut};
+
+/// A set of component types that can be iterated with `World::query`.
+///
+/// Implemented for `&T`, `&mut T` and tuples of up to six of those.
+pub trait Query {
+    /// The storage borrows held for the duration of the query.
+    type Borrow<'w>;
+    /// The components handed to the query callback for one entity.
+    type Item<'b>;
+
+    /// Returns None if any of the queried component types has no storage.
+    fn borrow(world: &World) -> Option<Self::Borrow<'_>>;
+
+    /// The candidate entities to fetch, taken from the smallest storage.
+    fn entities<'a>(borrow: &'a Self::Borrow<'_>) -> &'a [Entity];
+
+    fn fetch<'b>(borrow: &'b mut Self::Borrow<'_>, entity: Entity) -> Option<Self::Item<'b>>;
+}
+
+impl<T: 'static> Query for &T {
+    type Borrow<'w> = Ref<'w, SparseSet<T>>;
+    type Item<'b> = &'b T;
+
+    fn borrow(world: &World) -> Option<Self::Borrow<'_>> {
+        world.storage::<T>()
+    }
+
+    fn entities<'a>(borrow: &'a Self::Borrow<'_>) -> &'a [Entity] {
+        borrow.entities()
+    }
+
+    fn fetch<'b>(borrow: &'b mut Self::Borrow<'_>, entity: Entity) -> Option<Self::Item<'b>> {
+        borrow.get(entity)
+    }
+}
+
+impl<T: 'static> Query for &mut T {
+    type Borrow<'w> = RefMut<'w, SparseSet<T>>;
+    type Item<'b> = &'b mut T;
+
+    fn borrow(world: &World) -> Option<Self::Borrow<'_>> {
+        world.storage_mut::<T>()
+    }
+
+    fn entities<'a>(borrow: &'a Self::Borrow<'_>) -> &'a [Entity] {
+        borrow.entities()
+    }
+
+    fn fetch<'b>(borrow: &'b mut Self::Borrow<'_>, entity: Entity) -> Option<Self::Item<'b>> {
+        borrow.get_mut(entity)
+    }
+}
+
+macro_rules! tuple_query {
+    ($($name:ident)*) => {
+        #[allow(non_snake_case)]
+        impl<$($name: Query),*> Query for ($($name,)*) {
+            type Borrow<'w> = ($($name::Borrow<'w>,)*);
+            type Item<'b> = ($($name::Item<'b>,)*);
+
+            fn borrow(world: &World) -> Option<Self::Borrow<'_>> {
+                Some(($($name::borrow(world)?,)*))
+            }
+
+            fn entities<'a>(borrow: &'a Self::Borrow<'_>) -> &'a [Entity] {
+                let ($($name,)*) = borrow;
+                let mut smallest: Option<&'a [Entity]> = None;
+                $(
+                    let entities = $name::entities($name);
+                    if smallest.map_or(true, |smallest| entities.len() < smallest.len()) {
+                        smallest = Some(entities);
+                    }
+                )*
+                smallest.unwrap_or(&[])
+            }
+
+            fn fetch<'b>(borrow: &'b mut Self::Borrow<'_>, entity: Entity) -> Option<Self::Item<'b>> {
+                let ($($name,)*) = borrow;
+                Some(($($name::fetch($name, entity)?,)*))
+            }
+        }
+    };
+}
+
+tuple_query!(A);
+tuple_query!(A B);
+tuple_query!(A B C);
+tuple_query!(A B C D);
+tuple_query!(A B C D E);
+tuple_query!(A B C D E F);
@@ -0,0 +1,95 @@
+use crate::ecs::World;
+
+/// The stages a `Schedule` runs its systems in, in order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Input,
+    Update,
+    Render,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 3] = [Stage::Input, Stage::Update, Stage::Render];
+
+    fn index(&self) -> usize {
+        match *self {
+            Stage::Input => 0,
+            Stage::Update => 1,
+            Stage::Render => 2,
+        }
+    }
+}
+
+pub trait System {
+    fn run(&mut self, world: &mut World);
+}
+
+impl<F> System for F
+where
+    F: FnMut(&mut World),
+{
+    fn run(&mut self, world: &mut World) {
+        self(world);
+    }
+}
+
+/// Runs systems stage by stage. Within a stage, systems run in the order they
+/// were added.
+#[derive(Default)]
+pub struct Schedule {
+    stages: [Vec<Box<dyn System>>; 3],
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Schedule::default()
+    }
+
+    pub fn add_system<S>(&mut self, stage: Stage, system: S) -> &mut Self
+    where
+        S: System + 'static,
+    {
+        self.stages[stage.index()].push(Box::new(system));
+        self
+    }
+
+    pub fn run(&mut self, world: &mut World) {
+        for stage in Stage::ALL.iter() {
+            self.run_stage(*stage, world);
+        }
+    }
+
+    pub fn run_stage(&mut self, stage: Stage, world: &mut World) {
+        for system in self.stages[stage.index()].iter_mut() {
+            system.run(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(name: &'static str) -> impl FnMut(&mut World) {
+        move |world: &mut World| world.resource_mut::<Vec<&str>>().unwrap().push(name)
+    }
+
+    #[test]
+    fn systems_run_by_stage_then_in_insertion_order() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&str>::new());
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::Render, log("render"))
+            .add_system(Stage::Update, log("update 1"))
+            .add_system(Stage::Input, log("input"))
+            .add_system(Stage::Update, log("update 2"));
+        schedule.run(&mut world);
+
+        assert_eq!(
+            *world.resource::<Vec<&str>>().unwrap(),
+            ["input", "update 1", "update 2", "render"]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use crate::ecs::Entity;
+use std::any::Any;
+use std::cell::RefCell;
+
+/// Typed component storage. Components are kept densely packed for
+/// iteration, with a sparse index from entity to dense slot for lookups.
+#[derive(Debug)]
+pub struct SparseSet<T> {
+    sparse: Vec<Option<usize>>,
+    dense: Vec<Entity>,
+    data: Vec<T>,
+}
+
+impl<T> SparseSet<T> {
+    pub fn new() -> Self {
+        SparseSet {
+            sparse: Vec::new(),
+            dense: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    /// Inserts `value` for `entity`, returning the previous component if the
+    /// entity already had one.
+    pub fn insert(&mut self, entity: Entity, value: T) -> Option<T> {
+        let index = entity.index() as usize;
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, None);
+        }
+
+        if let Some(slot) = self.sparse[index] {
+            self.dense[slot] = entity;
+            Some(std::mem::replace(&mut self.data[slot], value))
+        } else {
+            self.sparse[index] = Some(self.dense.len());
+            self.dense.push(entity);
+            self.data.push(value);
+            None
+        }
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let slot = self.slot(entity)?;
+        self.sparse[entity.index() as usize] = None;
+
+        let last = self.dense.len() - 1;
+        if slot != last {
+            let moved = self.dense[last];
+            self.sparse[moved.index() as usize] = Some(slot);
+        }
+
+        self.dense.swap_remove(slot);
+        Some(self.data.swap_remove(slot))
+    }
+
+    #[inline]
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.slot(entity).map(|slot| &self.data[slot])
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        self.slot(entity).map(move |slot| &mut self.data[slot])
+    }
+
+    #[inline]
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.slot(entity).is_some()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    /// The entities that have a component in this storage, in dense order.
+    #[inline]
+    pub fn entities(&self) -> &[Entity] {
+        &self.dense
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.dense.iter().copied().zip(self.data.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.dense.iter().copied().zip(self.data.iter_mut())
+    }
+
+    #[inline]
+    fn slot(&self, entity: Entity) -> Option<usize> {
+        let slot = (*self.sparse.get(entity.index() as usize)?)?;
+        if self.dense[slot] == entity {
+            Some(slot)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        SparseSet::new()
+    }
+}
+
+/// Type-erased access to a `RefCell<SparseSet<T>>` so a `World` can hold the
+/// storages of every component type in one map.
+pub(crate) trait ComponentStorage {
+    fn remove_entity(&mut self, entity: Entity);
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: 'static> ComponentStorage for RefCell<SparseSet<T>> {
+    fn remove_entity(&mut self, entity: Entity) {
+        self.get_mut().remove(entity);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Entities;
+
+    #[test]
+    fn remove_moves_the_last_component_into_the_gap() {
+        let mut entities = Entities::default();
+        let [a, b, c] = [entities.alloc(), entities.alloc(), entities.alloc()];
+        let mut set = SparseSet::new();
+        set.insert(a, 'a');
+        set.insert(b, 'b');
+        set.insert(c, 'c');
+
+        assert_eq!(set.remove(a), Some('a'));
+        assert_eq!(set.remove(a), None);
+        assert_eq!(set.entities(), [c, b]);
+        assert_eq!(set.get(c), Some(&'c'));
+        assert_eq!(set.get(b), Some(&'b'));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn stale_entities_miss_their_reused_slot() {
+        let mut entities = Entities::default();
+        let stale = entities.alloc();
+        entities.free(stale);
+        let reused = entities.alloc();
+
+        let mut set = SparseSet::new();
+        set.insert(reused, 1);
+        assert!(!set.contains(stale));
+        assert_eq!(set.remove(stale), None);
+        assert_eq!(set.insert(reused, 2), Some(1));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::ecs::{ComponentStorage, Entities, Entity, Query, SparseSet};
+use std::any::{Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EcsError {
+    #[error("entity {0} does not exist or has been despawned")]
+    NoSuchEntity(Entity),
+}
+
+/// Owns every entity, component and resource of a game.
+///
+/// Component storages and resources are wrapped in a `RefCell`, which lets
+/// systems borrow several of them at once through a shared `&World`.
+/// Conflicting borrows, such as querying `(&mut T, &T)`, panic.
+#[derive(Default)]
+pub struct World {
+    entities: Entities,
+    storages: HashMap<TypeId, Box<dyn ComponentStorage>>,
+    resources: HashMap<TypeId, RefCell<Box<dyn Any>>>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        World::default()
+    }
+
+    pub fn spawn(&mut self) -> Entity {
+        self.entities.alloc()
+    }
+
+    /// Removes `entity` and all of its components.
+    ///
+    /// Returns false if the entity was already despawned.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.entities.free(entity) {
+            return false;
+        }
+
+        for storage in self.storages.values_mut() {
+            storage.remove_entity(entity);
+        }
+        true
+    }
+
+    #[inline]
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    /// The number of living entities.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.len() == 0
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter()
+    }
+
+    /// Adds `component` to `entity`, returning the component it replaced.
+    ///
+    /// Returns Err if the entity has been despawned.
+    pub fn insert<T: 'static>(
+        &mut self,
+        entity: Entity,
+        component: T,
+    ) -> Result<Option<T>, EcsError> {
+        if !self.entities.is_alive(entity) {
+            return Err(EcsError::NoSuchEntity(entity));
+        }
+
+        let storage = self
+            .storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(RefCell::new(SparseSet::<T>::new())));
+
+        let storage = storage
+            .as_any()
+            .downcast_ref::<RefCell<SparseSet<T>>>()
+            .expect("component storage registered under the wrong type");
+
+        Ok(storage.borrow_mut().insert(entity, component))
+    }
+
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+        self.storage_mut::<T>()?.remove(entity)
+    }
+
+    pub fn has<T: 'static>(&self, entity: Entity) -> bool {
+        self.storage::<T>()
+            .map(|storage| storage.contains(entity))
+            .unwrap_or(false)
+    }
+
+    pub fn get<T: 'static>(&self, entity: Entity) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.storage::<T>()?, |storage| storage.get(entity)).ok()
+    }
+
+    pub fn get_mut<T: 'static>(&self, entity: Entity) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.storage_mut::<T>()?, |storage| storage.get_mut(entity)).ok()
+    }
+
+    /// Borrows the storage of component `T`, or None if no entity has ever
+    /// had a `T` inserted.
+    pub fn storage<T: 'static>(&self) -> Option<Ref<'_, SparseSet<T>>> {
+        self.storage_cell::<T>().map(RefCell::borrow)
+    }
+
+    pub fn storage_mut<T: 'static>(&self) -> Option<RefMut<'_, SparseSet<T>>> {
+        self.storage_cell::<T>().map(RefCell::borrow_mut)
+    }
+
+    fn storage_cell<T: 'static>(&self) -> Option<&RefCell<SparseSet<T>>> {
+        self.storages
+            .get(&TypeId::of::<T>())?
+            .as_any()
+            .downcast_ref::<RefCell<SparseSet<T>>>()
+    }
+
+    /// Calls `f` for every entity that has all the components in `Q`.
+    ///
+    /// ```ignore
+    /// world.query::<(&mut Transform, &Velocity)>(|_, (transform, velocity)| {
+    ///     transform.translation += velocity.0;
+    /// });
+    /// ```
+    pub fn query<Q: Query>(&self, mut f: impl for<'b> FnMut(Entity, Q::Item<'b>)) {
+        let mut borrow = match Q::borrow(self) {
+            Some(borrow) => borrow,
+            None => return,
+        };
+
+        // The queried storages can't change while borrowed, so the dense
+        // entities stay put while they are fetched from.
+        for index in 0..Q::entities(&borrow).len() {
+            let entity = Q::entities(&borrow)[index];
+            if let Some(item) = Q::fetch(&mut borrow, entity) {
+                f(entity, item);
+            }
+        }
+    }
+
+    /// Inserts a global resource, returning the resource of the same type it
+    /// replaced.
+    pub fn insert_resource<R: 'static>(&mut self, resource: R) -> Option<R> {
+        self.resources
+            .insert(TypeId::of::<R>(), RefCell::new(Box::new(resource)))
+            .and_then(|old| old.into_inner().downcast::<R>().ok())
+            .map(|old| *old)
+    }
+
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        self.resources
+            .remove(&TypeId::of::<R>())
+            .and_then(|old| old.into_inner().downcast::<R>().ok())
+            .map(|old| *old)
+    }
+
+    pub fn has_resource<R: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    pub fn resource<R: 'static>(&self) -> Option<Ref<'_, R>> {
+        let cell = self.resources.get(&TypeId::of::<R>())?;
+        Ref::filter_map(cell.borrow(), |resource| resource.downcast_ref::<R>()).ok()
+    }
+
+    pub fn resource_mut<R: 'static>(&self) -> Option<RefMut<'_, R>> {
+        let cell = self.resources.get(&TypeId::of::<R>())?;
+        RefMut::filter_map(cell.borrow_mut(), |resource| resource.downcast_mut::<R>()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    #[test]
+    fn query_visits_entities_with_every_component() {
+        let mut world = World::new();
+        let moving = world.spawn();
+        world.insert(moving, Position(0)).unwrap();
+        world.insert(moving, Velocity(2)).unwrap();
+        let still = world.spawn();
+        world.insert(still, Position(5)).unwrap();
+
+        world.query::<(&mut Position, &Velocity)>(|_, (position, velocity)| {
+            position.0 += velocity.0;
+        });
+        assert_eq!(*world.get::<Position>(moving).unwrap(), Position(2));
+        assert_eq!(*world.get::<Position>(still).unwrap(), Position(5));
+
+        let mut visited = Vec::new();
+        world.query::<&Position>(|entity, _| visited.push(entity));
+        assert_eq!(visited, [moving, still]);
+    }
+
+    #[test]
+    fn despawning_removes_components() {
+        let mut world = World::new();
+        let entity = world.spawn();
+        world.insert(entity, Position(1)).unwrap();
+
+        assert!(world.despawn(entity));
+        assert!(!world.despawn(entity));
+        assert!(!world.has::<Position>(entity));
+        assert!(world.insert(entity, Position(2)).is_err());
+        assert!(world.is_empty());
+    }
+
+    #[test]
+    fn resources_are_replaced_by_type() {
+        let mut world = World::new();
+        assert_eq!(world.insert_resource(1u32), None);
+        assert_eq!(world.insert_resource(2u32), Some(1));
+        *world.resource_mut::<u32>().unwrap() += 1;
+        assert_eq!(world.remove_resource::<u32>(), Some(3));
+        assert!(!world.has_resource::<u32>());
+    }
+}
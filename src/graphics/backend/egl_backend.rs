@@ -0,0 +1,254 @@
+use crate::graphics::{
+    CursorMode, Event, FullscreenMode, GlProfile, Image, StandardCursor, WindowBackend,
+    WindowBuilder, WindowError,
+};
+use crate::input::GamepadSource;
+use crate::maths::Vector2;
+use khronos_egl as egl;
+use std::any::Any;
+use std::os::raw::c_void;
+use std::ptr;
+
+// From EGL_MESA_platform_surfaceless, which the bindings don't define.
+const PLATFORM_SURFACELESS_MESA: egl::Enum = 0x31DD;
+
+/// An OpenGL context on a pbuffer of Mesa's surfaceless EGL platform, which
+/// renders offscreen without any windowing system or display connection.
+pub(crate) struct EglContext {
+    egl: egl::DynamicInstance<egl::EGL1_5>,
+    display: egl::Display,
+    surface: egl::Surface,
+    context: egl::Context,
+
+    width: u32,
+    height: u32,
+}
+
+impl EglContext {
+    /// Creates a context with a default framebuffer of the builder's size,
+    /// without making it current.
+    pub(crate) fn new(builder: &WindowBuilder) -> Result<Self, WindowError> {
+        let egl = unsafe { egl::DynamicInstance::<egl::EGL1_5>::load_required() }
+            .map_err(|error| WindowError::Init(format!("failed to load libEGL: {}", error)))?;
+
+        let display = egl
+            .get_platform_display(
+                PLATFORM_SURFACELESS_MESA,
+                ptr::null_mut(),
+                &[egl::ATTRIB_NONE],
+            )
+            .map_err(|error| init_error("no surfaceless EGL display", error))?;
+        egl.initialize(display)
+            .map_err(|error| init_error("failed to initialize EGL", error))?;
+        egl.bind_api(egl::OPENGL_API)
+            .map_err(|error| init_error("EGL does not support OpenGL", error))?;
+
+        let mut config_attributes = vec![
+            egl::SURFACE_TYPE,
+            egl::PBUFFER_BIT,
+            egl::RENDERABLE_TYPE,
+            egl::OPENGL_BIT,
+            egl::RED_SIZE,
+            8,
+            egl::GREEN_SIZE,
+            8,
+            egl::BLUE_SIZE,
+            8,
+            egl::ALPHA_SIZE,
+            8,
+        ];
+        if let Some(bits) = builder.depth_bits {
+            config_attributes.extend_from_slice(&[egl::DEPTH_SIZE, bits as egl::Int]);
+        }
+        if let Some(bits) = builder.stencil_bits {
+            config_attributes.extend_from_slice(&[egl::STENCIL_SIZE, bits as egl::Int]);
+        }
+        if let Some(samples) = builder.samples {
+            config_attributes.extend_from_slice(&[egl::SAMPLES, samples as egl::Int]);
+        }
+        config_attributes.push(egl::NONE);
+
+        let config = egl
+            .choose_first_config(display, &config_attributes)
+            .map_err(|error| context_error("failed to choose an EGL config", error))?
+            .ok_or_else(|| {
+                WindowError::ContextCreation("no EGL config matches the options".to_owned())
+            })?;
+
+        let surface = egl
+            .create_pbuffer_surface(
+                display,
+                config,
+                &[
+                    egl::WIDTH,
+                    builder.width as egl::Int,
+                    egl::HEIGHT,
+                    builder.height as egl::Int,
+                    egl::NONE,
+                ],
+            )
+            .map_err(|error| context_error("failed to create a pbuffer", error))?;
+
+        let (major, minor) = builder.gl_version;
+        let mut context_attributes = vec![
+            egl::CONTEXT_MAJOR_VERSION,
+            major as egl::Int,
+            egl::CONTEXT_MINOR_VERSION,
+            minor as egl::Int,
+        ];
+        match builder.gl_profile {
+            GlProfile::Core => context_attributes.extend_from_slice(&[
+                egl::CONTEXT_OPENGL_PROFILE_MASK,
+                egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
+            ]),
+            GlProfile::Compatibility => context_attributes.extend_from_slice(&[
+                egl::CONTEXT_OPENGL_PROFILE_MASK,
+                egl::CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT,
+            ]),
+            GlProfile::Any => {}
+        }
+        if builder.forward_compatible {
+            context_attributes.extend_from_slice(&[
+                egl::CONTEXT_OPENGL_FORWARD_COMPATIBLE,
+                egl::TRUE as egl::Int,
+            ]);
+        }
+        if builder.debug_context {
+            context_attributes
+                .extend_from_slice(&[egl::CONTEXT_OPENGL_DEBUG, egl::TRUE as egl::Int]);
+        }
+        context_attributes.push(egl::NONE);
+
+        let context = match egl.create_context(display, config, None, &context_attributes) {
+            Ok(context) => context,
+            Err(error) => {
+                let _ = egl.destroy_surface(display, surface);
+                return Err(WindowError::UnsupportedGlVersion {
+                    major,
+                    minor,
+                    description: error.to_string(),
+                });
+            }
+        };
+
+        Ok(EglContext {
+            egl,
+            display,
+            surface,
+            context,
+            width: builder.width,
+            height: builder.height,
+        })
+    }
+}
+
+fn init_error(what: &str, error: egl::Error) -> WindowError {
+    WindowError::Init(format!("{}: {}", what, error))
+}
+
+fn context_error(what: &str, error: egl::Error) -> WindowError {
+    WindowError::ContextCreation(format!("{}: {}", what, error))
+}
+
+impl WindowBackend for EglContext {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn make_current(&mut self) {
+        if let Err(error) = self.egl.make_current(
+            self.display,
+            Some(self.surface),
+            Some(self.surface),
+            Some(self.context),
+        ) {
+            log::error!("failed to make the OpenGL context current: {}", error);
+        }
+    }
+
+    fn get_proc_address(&mut self, symbol: &str) -> *const c_void {
+        self.egl
+            .get_proc_address(symbol)
+            .map_or(ptr::null(), |function| function as *const c_void)
+    }
+
+    fn swap_buffers(&mut self) {}
+
+    fn set_swap_interval(&mut self, _interval: u32) {}
+
+    fn poll_events(&mut self, _events: &mut Vec<Event>) {}
+
+    fn gamepad_source(&self) -> Option<Box<dyn GamepadSource>> {
+        None
+    }
+
+    fn framebuffer_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn logical_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn content_scale(&self) -> Vector2 {
+        Vector2::new(1.0, 1.0)
+    }
+
+    fn set_title(&mut self, _title: &str) {}
+
+    fn set_fullscreen(&mut self, _mode: FullscreenMode) -> bool {
+        false
+    }
+
+    fn minimize(&mut self) {}
+
+    fn maximize(&mut self) {}
+
+    fn restore(&mut self) {}
+
+    fn is_minimized(&self) -> bool {
+        false
+    }
+
+    fn is_maximized(&self) -> bool {
+        false
+    }
+
+    fn cursor_mode(&self) -> CursorMode {
+        CursorMode::Normal
+    }
+
+    fn set_cursor_mode(&mut self, _mode: CursorMode) {}
+
+    fn set_standard_cursor(&mut self, _cursor: StandardCursor) {}
+
+    fn set_cursor_image(&mut self, _image: &Image, _hotspot_x: u32, _hotspot_y: u32) {}
+
+    fn reset_cursor(&mut self) {}
+
+    fn set_icon(&mut self, _images: &[Image]) {}
+
+    fn clipboard(&self) -> Option<String> {
+        None
+    }
+
+    fn set_clipboard(&mut self, _text: &str) {}
+
+    fn should_close(&self) -> bool {
+        false
+    }
+
+    fn set_should_close(&mut self, _should_close: bool) {}
+}
+
+impl Drop for EglContext {
+    fn drop(&mut self) {
+        // The display is shared by every context in the process, so it is
+        // left initialized rather than terminated.
+        if self.egl.get_current_context() == Some(self.context) {
+            let _ = self.egl.make_current(self.display, None, None, None);
+        }
+        let _ = self.egl.destroy_context(self.display, self.context);
+        let _ = self.egl.destroy_surface(self.display, self.surface);
+    }
+}
@@ -0,0 +1,391 @@
+use crate::graphics::{
+    CursorMode, Event, FullscreenMode, Image, MonitorSelection, PlatformBackend, StandardCursor,
+    WindowBackend, WindowBuilder, WindowError,
+};
+use crate::input::{GamepadSource, GlfwGamepadSource};
+use crate::maths::Vector2;
+use glfw::{Context, WindowEvent, WindowHint, WindowMode};
+use std::any::Any;
+use std::cell::RefCell;
+use std::os::raw::c_void;
+use std::sync::mpsc::Receiver;
+
+thread_local! {
+    static LAST_GLFW_ERROR: RefCell<Option<(glfw::Error, String)>> = const { RefCell::new(None) };
+}
+
+/// Installed as GLFW's error callback. Errors are logged, and the latest one
+/// is kept so a failed call can be turned into a `WindowError`.
+fn report_glfw_error(error: glfw::Error, description: String, _: &()) {
+    log::error!("GLFW error ({}): {}", error, description);
+    LAST_GLFW_ERROR.with(|last| *last.borrow_mut() = Some((error, description)));
+}
+
+fn take_glfw_error() -> Option<(glfw::Error, String)> {
+    LAST_GLFW_ERROR.with(|last| last.borrow_mut().take())
+}
+
+pub(crate) struct GlfwPlatform {
+    glfw: glfw::Glfw,
+}
+
+impl GlfwPlatform {
+    pub(crate) fn new() -> Result<Self, WindowError> {
+        let callback = glfw::Callback {
+            f: report_glfw_error as fn(glfw::Error, String, &()),
+            data: (),
+        };
+        let glfw = glfw::init(Some(callback)).map_err(|error| {
+            let description = take_glfw_error().map_or_else(|| error.to_string(), |(_, d)| d);
+            WindowError::Init(description)
+        })?;
+
+        Ok(GlfwPlatform { glfw })
+    }
+}
+
+impl PlatformBackend for GlfwPlatform {
+    fn create_window(
+        &mut self,
+        builder: &WindowBuilder,
+        share: Option<&dyn WindowBackend>,
+    ) -> Result<Box<dyn WindowBackend>, WindowError> {
+        let share = match share {
+            Some(share) => Some(
+                &share
+                    .as_any()
+                    .downcast_ref::<GlfwWindow>()
+                    .ok_or_else(|| {
+                        WindowError::ContextCreation(
+                            "cannot share objects with a context from another backend".to_owned(),
+                        )
+                    })?
+                    .window,
+            ),
+            None => None,
+        };
+
+        let glfw = &mut self.glfw;
+        // Hints persist between windows, so start from the defaults.
+        glfw.default_window_hints();
+        glfw.window_hint(WindowHint::ContextVersion(
+            builder.gl_version.0,
+            builder.gl_version.1,
+        ));
+        glfw.window_hint(WindowHint::OpenGlProfile(builder.gl_profile.to_glfw()));
+        glfw.window_hint(WindowHint::OpenGlForwardCompat(builder.forward_compatible));
+        glfw.window_hint(WindowHint::OpenGlDebugContext(builder.debug_context));
+        glfw.window_hint(WindowHint::ContextCreationApi(
+            builder.context_api.to_glfw(),
+        ));
+        glfw.window_hint(WindowHint::Samples(builder.samples));
+        glfw.window_hint(WindowHint::DepthBits(builder.depth_bits));
+        glfw.window_hint(WindowHint::StencilBits(builder.stencil_bits));
+        glfw.window_hint(WindowHint::SRgbCapable(builder.srgb));
+        glfw.window_hint(WindowHint::Resizable(builder.resizable));
+        glfw.window_hint(WindowHint::Decorated(builder.decorated));
+        glfw.window_hint(WindowHint::TransparentFramebuffer(builder.transparent));
+        glfw.window_hint(WindowHint::Visible(builder.visible));
+
+        take_glfw_error();
+
+        let (width, height, title) = (builder.width, builder.height, &builder.title);
+        let create_window = |glfw: &glfw::Glfw, mode: WindowMode<'_>| match share {
+            Some(share) => share.create_shared(width, height, title, mode),
+            None => glfw.create_window(width, height, title, mode),
+        };
+        let created = match builder.fullscreen {
+            None => create_window(glfw, WindowMode::Windowed),
+            Some(MonitorSelection::Primary) => glfw.with_primary_monitor(|glfw, monitor| {
+                create_window(
+                    glfw,
+                    monitor.map_or(WindowMode::Windowed, WindowMode::FullScreen),
+                )
+            }),
+            Some(MonitorSelection::Index(index)) => {
+                glfw.with_connected_monitors(|glfw, monitors| {
+                    let mode = monitors
+                        .get(index)
+                        .map_or(WindowMode::Windowed, WindowMode::FullScreen);
+                    create_window(glfw, mode)
+                })
+            }
+        };
+        let (major, minor) = builder.gl_version;
+        let (mut window, receiver) = created.ok_or_else(|| match take_glfw_error() {
+            Some((glfw::Error::VersionUnavailable, description)) => {
+                WindowError::UnsupportedGlVersion {
+                    major,
+                    minor,
+                    description,
+                }
+            }
+            Some((_, description)) => WindowError::ContextCreation(description),
+            None => WindowError::ContextCreation("unknown error".to_owned()),
+        })?;
+
+        if let (Some((x, y)), None) = (builder.position, builder.fullscreen) {
+            window.set_pos(x, y);
+        }
+
+        window.set_key_polling(true);
+        window.set_char_polling(true);
+        window.set_mouse_button_polling(true);
+        window.set_cursor_pos_polling(true);
+        window.set_cursor_enter_polling(true);
+        window.set_scroll_polling(true);
+        window.set_framebuffer_size_polling(true);
+        window.set_focus_polling(true);
+        window.set_close_polling(true);
+        window.set_drag_and_drop_polling(true);
+        window.set_content_scale_polling(true);
+
+        Ok(Box::new(GlfwWindow {
+            glfw: self.glfw.clone(),
+            window,
+            receiver,
+            fullscreen: builder.fullscreen.is_some(),
+            windowed_rect: None,
+            windowed_decorated: builder.decorated,
+        }))
+    }
+
+    fn detach_current_context(&mut self) {
+        self.glfw.make_context_current(None);
+    }
+
+    #[inline]
+    fn poll_events(&mut self) {
+        self.glfw.poll_events();
+    }
+}
+
+pub(crate) struct GlfwWindow {
+    glfw: glfw::Glfw,
+    window: glfw::Window,
+    receiver: Receiver<(f64, WindowEvent)>,
+
+    fullscreen: bool,
+    // Position and size to go back to when leaving fullscreen.
+    windowed_rect: Option<(i32, i32, u32, u32)>,
+    // Whether the window was decorated before borderless fullscreen removed
+    // the decorations.
+    windowed_decorated: bool,
+}
+
+impl WindowBackend for GlfwWindow {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn make_current(&mut self) {
+        self.window.make_current();
+    }
+
+    fn get_proc_address(&mut self, symbol: &str) -> *const c_void {
+        self.window.get_proc_address(symbol)
+    }
+
+    #[inline]
+    fn swap_buffers(&mut self) {
+        self.window.swap_buffers();
+    }
+
+    fn set_swap_interval(&mut self, interval: u32) {
+        self.glfw.set_swap_interval(if interval == 0 {
+            glfw::SwapInterval::None
+        } else {
+            glfw::SwapInterval::Sync(interval)
+        });
+    }
+
+    fn poll_events(&mut self, events: &mut Vec<Event>) {
+        self.glfw.poll_events();
+        for (_, event) in glfw::flush_messages(&self.receiver) {
+            if let Some(event) = Event::from_glfw(event) {
+                events.push(event);
+            }
+        }
+    }
+
+    fn gamepad_source(&self) -> Option<Box<dyn GamepadSource>> {
+        Some(Box::new(GlfwGamepadSource::new(self.glfw.clone())))
+    }
+
+    fn framebuffer_size(&self) -> (u32, u32) {
+        let (width, height) = self.window.get_framebuffer_size();
+        (width.max(0) as u32, height.max(0) as u32)
+    }
+
+    fn logical_size(&self) -> (u32, u32) {
+        let (width, height) = self.window.get_size();
+        (width.max(0) as u32, height.max(0) as u32)
+    }
+
+    fn content_scale(&self) -> Vector2 {
+        let (x, y) = self.window.get_content_scale();
+        Vector2::new(x, y)
+    }
+
+    #[inline]
+    fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    fn set_fullscreen(&mut self, mode: FullscreenMode) -> bool {
+        if !self.fullscreen && mode != FullscreenMode::Windowed {
+            let (x, y) = self.window.get_pos();
+            let (width, height) = self.logical_size();
+            self.windowed_rect = Some((x, y, width, height));
+            self.windowed_decorated = self.window.is_decorated();
+        }
+
+        let window = &mut self.window;
+        let applied = match mode {
+            FullscreenMode::Windowed => {
+                let (x, y, width, height) = self.windowed_rect.take().unwrap_or_else(|| {
+                    let (x, y) = window.get_pos();
+                    let (width, height) = window.get_size();
+                    (x, y, width.max(0) as u32, height.max(0) as u32)
+                });
+                window.set_decorated(self.windowed_decorated);
+                window.set_monitor(WindowMode::Windowed, x, y, width, height, None);
+                true
+            }
+            FullscreenMode::Exclusive(selection) => {
+                with_monitor(&mut self.glfw, selection, |monitor| {
+                    match monitor.and_then(|m| m.get_video_mode().map(|mode| (m, mode))) {
+                        Some((monitor, video_mode)) => {
+                            window.set_monitor(
+                                WindowMode::FullScreen(monitor),
+                                0,
+                                0,
+                                video_mode.width,
+                                video_mode.height,
+                                Some(video_mode.refresh_rate),
+                            );
+                            true
+                        }
+                        None => false,
+                    }
+                })
+            }
+            FullscreenMode::Borderless(selection) => {
+                with_monitor(&mut self.glfw, selection, |monitor| {
+                    match monitor.and_then(|m| m.get_video_mode().map(|mode| (m, mode))) {
+                        Some((monitor, video_mode)) => {
+                            let (x, y) = monitor.get_pos();
+                            window.set_decorated(false);
+                            window.set_monitor(
+                                WindowMode::Windowed,
+                                x,
+                                y,
+                                video_mode.width,
+                                video_mode.height,
+                                None,
+                            );
+                            true
+                        }
+                        None => false,
+                    }
+                })
+            }
+        };
+
+        if applied {
+            self.fullscreen = mode != FullscreenMode::Windowed;
+        }
+        applied
+    }
+
+    #[inline]
+    fn minimize(&mut self) {
+        self.window.iconify();
+    }
+
+    #[inline]
+    fn maximize(&mut self) {
+        self.window.maximize();
+    }
+
+    #[inline]
+    fn restore(&mut self) {
+        self.window.restore();
+    }
+
+    #[inline]
+    fn is_minimized(&self) -> bool {
+        self.window.is_iconified()
+    }
+
+    #[inline]
+    fn is_maximized(&self) -> bool {
+        self.window.is_maximized()
+    }
+
+    fn cursor_mode(&self) -> CursorMode {
+        match self.window.get_cursor_mode() {
+            glfw::CursorMode::Normal => CursorMode::Normal,
+            glfw::CursorMode::Hidden => CursorMode::Hidden,
+            glfw::CursorMode::Disabled => CursorMode::Captured,
+        }
+    }
+
+    fn set_cursor_mode(&mut self, mode: CursorMode) {
+        self.window.set_cursor_mode(mode.to_glfw());
+        if self.glfw.supports_raw_motion() {
+            self.window
+                .set_raw_mouse_motion(mode == CursorMode::Captured);
+        }
+    }
+
+    fn set_standard_cursor(&mut self, cursor: StandardCursor) {
+        let cursor = glfw::Cursor::standard(cursor.to_glfw());
+        self.window.set_cursor(Some(cursor));
+    }
+
+    fn set_cursor_image(&mut self, image: &Image, hotspot_x: u32, hotspot_y: u32) {
+        let cursor = glfw::Cursor::create_from_pixels(image.to_glfw(), hotspot_x, hotspot_y);
+        self.window.set_cursor(Some(cursor));
+    }
+
+    fn reset_cursor(&mut self) {
+        self.window.set_cursor(None);
+    }
+
+    fn set_icon(&mut self, images: &[Image]) {
+        self.window
+            .set_icon_from_pixels(images.iter().map(Image::to_glfw).collect());
+    }
+
+    fn clipboard(&self) -> Option<String> {
+        self.window.get_clipboard_string()
+    }
+
+    fn set_clipboard(&mut self, text: &str) {
+        self.window.set_clipboard_string(text);
+    }
+
+    #[inline]
+    fn should_close(&self) -> bool {
+        self.window.should_close()
+    }
+
+    #[inline]
+    fn set_should_close(&mut self, should_close: bool) {
+        self.window.set_should_close(should_close);
+    }
+}
+
+fn with_monitor<T, F>(glfw: &mut glfw::Glfw, selection: MonitorSelection, mut f: F) -> T
+where
+    F: FnMut(Option<&glfw::Monitor>) -> T,
+{
+    match selection {
+        MonitorSelection::Primary => glfw.with_primary_monitor_mut(|_, monitor| f(monitor)),
+        MonitorSelection::Index(index) => {
+            glfw.with_connected_monitors_mut(|_, monitors| f(monitors.get(index)))
+        }
+    }
+}
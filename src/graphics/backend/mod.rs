@@ -0,0 +1,20 @@
+#[cfg(not(any(feature = "glfw", feature = "winit")))]
+compile_error!("crean needs a windowing backend, enable the `glfw` or `winit` feature");
+
+#[cfg(feature = "glfw")]
+mod glfw_backend;
+#[cfg(feature = "glfw")]
+pub(crate) use self::glfw_backend::*;
+
+#[cfg(feature = "egl")]
+mod egl_backend;
+#[cfg(feature = "egl")]
+pub(crate) use self::egl_backend::*;
+
+mod window_backend;
+pub use self::window_backend::*;
+
+#[cfg(feature = "winit")]
+mod winit_backend;
+#[cfg(feature = "winit")]
+pub(crate) use self::winit_backend::*;
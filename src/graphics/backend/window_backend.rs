@@ -0,0 +1,92 @@
+use crate::graphics::{
+    CursorMode, Event, FullscreenMode, Image, StandardCursor, WindowBuilder, WindowError,
+};
+use crate::input::GamepadSource;
+use crate::maths::Vector2;
+use std::any::Any;
+use std::os::raw::c_void;
+
+/// The windowing libraries a `Platform` can be built on. Each one is compiled
+/// in by the cargo feature of the same name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// GLFW through the `glfw` crate, which builds the C library with CMake.
+    /// Enabled by default.
+    #[cfg(feature = "glfw")]
+    Glfw,
+    /// winit and glutin, written in pure Rust. Gamepads, custom cursor images
+    /// and the clipboard are not available with this backend.
+    #[cfg(feature = "winit")]
+    Winit,
+}
+
+#[cfg(feature = "glfw")]
+const DEFAULT_BACKEND: Backend = Backend::Glfw;
+#[cfg(all(feature = "winit", not(feature = "glfw")))]
+const DEFAULT_BACKEND: Backend = Backend::Winit;
+
+impl Default for Backend {
+    /// GLFW if it is enabled, winit otherwise.
+    fn default() -> Self {
+        DEFAULT_BACKEND
+    }
+}
+
+/// Creates windows and runs the event loop of one windowing library.
+pub(crate) trait PlatformBackend {
+    /// Creates a window and its OpenGL context without making the context
+    /// current. With `share`, the context shares objects with `share`'s
+    /// context, which must come from the same backend.
+    fn create_window(
+        &mut self,
+        builder: &WindowBuilder,
+        share: Option<&dyn WindowBackend>,
+    ) -> Result<Box<dyn WindowBackend>, WindowError>;
+
+    fn detach_current_context(&mut self);
+
+    fn poll_events(&mut self);
+}
+
+/// A window and its OpenGL context, as `Window` and `HeadlessContext` need
+/// them from a windowing library.
+pub(crate) trait WindowBackend {
+    fn as_any(&self) -> &dyn Any;
+
+    fn make_current(&mut self);
+    fn get_proc_address(&mut self, symbol: &str) -> *const c_void;
+    fn swap_buffers(&mut self);
+    /// Applies to the current context.
+    fn set_swap_interval(&mut self, interval: u32);
+
+    /// Polls the platform and appends the events received by this window
+    /// since the last call.
+    fn poll_events(&mut self, events: &mut Vec<Event>);
+    fn gamepad_source(&self) -> Option<Box<dyn GamepadSource>>;
+
+    fn framebuffer_size(&self) -> (u32, u32);
+    fn logical_size(&self) -> (u32, u32);
+    fn content_scale(&self) -> Vector2;
+
+    fn set_title(&mut self, title: &str);
+    /// Returns false if the selected monitor is not connected.
+    fn set_fullscreen(&mut self, mode: FullscreenMode) -> bool;
+    fn minimize(&mut self);
+    fn maximize(&mut self);
+    fn restore(&mut self);
+    fn is_minimized(&self) -> bool;
+    fn is_maximized(&self) -> bool;
+
+    fn cursor_mode(&self) -> CursorMode;
+    fn set_cursor_mode(&mut self, mode: CursorMode);
+    fn set_standard_cursor(&mut self, cursor: StandardCursor);
+    fn set_cursor_image(&mut self, image: &Image, hotspot_x: u32, hotspot_y: u32);
+    fn reset_cursor(&mut self);
+    fn set_icon(&mut self, images: &[Image]);
+
+    fn clipboard(&self) -> Option<String>;
+    fn set_clipboard(&mut self, text: &str);
+
+    fn should_close(&self) -> bool;
+    fn set_should_close(&mut self, should_close: bool);
+}
@@ -0,0 +1,737 @@
+use crate::graphics::{
+    ContextApi, CursorMode, Event, FullscreenMode, GlProfile, Image, MonitorSelection,
+    PlatformBackend, StandardCursor, WindowBackend, WindowBuilder, WindowError,
+};
+use crate::input::{ButtonAction, GamepadSource, Key, Modifiers, MouseButton};
+use crate::maths::Vector2;
+use glutin::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
+use glutin::event::{
+    ElementState, Event as WinitEvent, ModifiersState, MouseScrollDelta, VirtualKeyCode,
+    WindowEvent,
+};
+use glutin::event_loop::{ControlFlow, EventLoop};
+use glutin::monitor::{MonitorHandle, VideoMode};
+use glutin::platform::run_return::EventLoopExtRunReturn;
+use glutin::window::{CursorIcon, Fullscreen, Icon, WindowId};
+use glutin::{Api, ContextBuilder, CreationError, GlRequest, PossiblyCurrent, WindowedContext};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::os::raw::c_void;
+use std::rc::{Rc, Weak};
+
+// How many pixels of touchpad scrolling count as one line of mouse wheel
+// scrolling, which is the unit `Event::Scroll` uses.
+const PIXELS_PER_LINE: f64 = 20.0;
+
+thread_local! {
+    // winit allows a single event loop per process, so every platform on the
+    // main thread shares it.
+    static SHARED: RefCell<Weak<Shared>> = const { RefCell::new(Weak::new()) };
+}
+
+struct Shared {
+    event_loop: RefCell<EventLoop<()>>,
+    windows: RefCell<HashMap<WindowId, WindowState>>,
+}
+
+impl Shared {
+    /// Runs the event loop until the platform's queue is empty, converting
+    /// events into each window's state as they arrive.
+    fn pump(&self) {
+        let mut windows = self.windows.borrow_mut();
+        self.event_loop
+            .borrow_mut()
+            .run_return(|event, _, control_flow| {
+                *control_flow = ControlFlow::Poll;
+                match event {
+                    WinitEvent::WindowEvent { window_id, event } => {
+                        if let Some(state) = windows.get_mut(&window_id) {
+                            state.handle(event);
+                        }
+                    }
+                    WinitEvent::MainEventsCleared => *control_flow = ControlFlow::Exit,
+                    _ => {}
+                }
+            });
+    }
+}
+
+/// Everything winit reports one event at a time that GLFW tracks for us.
+struct WindowState {
+    events: Vec<Event>,
+    modifiers: Modifiers,
+    keys_down: HashSet<Key>,
+    scale_factor: f64,
+    should_close: bool,
+}
+
+impl WindowState {
+    fn new(scale_factor: f64) -> Self {
+        WindowState {
+            events: Vec::new(),
+            modifiers: Modifiers::empty(),
+            keys_down: HashSet::new(),
+            scale_factor,
+            should_close: false,
+        }
+    }
+
+    fn handle(&mut self, event: WindowEvent<'_>) {
+        let event = match event {
+            WindowEvent::KeyboardInput { input, .. } => {
+                let key = input.virtual_keycode.map_or(Key::Unknown, key_from_winit);
+                // winit doesn't flag repeats, so a press of a held key is one.
+                let action = match input.state {
+                    ElementState::Pressed => {
+                        if self.keys_down.insert(key) {
+                            ButtonAction::Press
+                        } else {
+                            ButtonAction::Repeat
+                        }
+                    }
+                    ElementState::Released => {
+                        self.keys_down.remove(&key);
+                        ButtonAction::Release
+                    }
+                };
+                Event::Key {
+                    key,
+                    action,
+                    modifiers: self.modifiers,
+                }
+            }
+            WindowEvent::ModifiersChanged(state) => {
+                self.modifiers = modifiers_from_winit(state);
+                return;
+            }
+            // GLFW doesn't report control characters such as backspace.
+            WindowEvent::ReceivedCharacter(character) if !character.is_control() => {
+                Event::Char(character)
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let button = match mouse_button_from_winit(button) {
+                    Some(button) => button,
+                    None => return,
+                };
+                Event::MouseButton {
+                    button,
+                    action: match state {
+                        ElementState::Pressed => ButtonAction::Press,
+                        ElementState::Released => ButtonAction::Release,
+                    },
+                    modifiers: self.modifiers,
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = position.to_logical::<f64>(self.scale_factor);
+                Event::CursorMoved(Vector2::new(position.x as f32, position.y as f32))
+            }
+            WindowEvent::CursorEntered { .. } => Event::CursorEntered(true),
+            WindowEvent::CursorLeft { .. } => Event::CursorEntered(false),
+            WindowEvent::MouseWheel { delta, .. } => match delta {
+                MouseScrollDelta::LineDelta(x, y) => Event::Scroll(Vector2::new(x, y)),
+                MouseScrollDelta::PixelDelta(offset) => {
+                    let offset = offset.to_logical::<f64>(self.scale_factor);
+                    Event::Scroll(Vector2::new(
+                        (offset.x / PIXELS_PER_LINE) as f32,
+                        (offset.y / PIXELS_PER_LINE) as f32,
+                    ))
+                }
+            },
+            WindowEvent::Resized(size) => Event::Resized {
+                width: size.width,
+                height: size.height,
+            },
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                self.scale_factor = scale_factor;
+                let scale = scale_factor as f32;
+                self.events
+                    .push(Event::ContentScaleChanged(Vector2::new(scale, scale)));
+                Event::Resized {
+                    width: new_inner_size.width,
+                    height: new_inner_size.height,
+                }
+            }
+            WindowEvent::Focused(focused) => Event::Focused(focused),
+            WindowEvent::CloseRequested => {
+                self.should_close = true;
+                Event::CloseRequested
+            }
+            WindowEvent::DroppedFile(path) => Event::FilesDropped(vec![path]),
+            _ => return,
+        };
+        self.events.push(event);
+    }
+}
+
+pub(crate) struct WinitPlatform {
+    shared: Rc<Shared>,
+}
+
+impl WinitPlatform {
+    /// Returns Err if winit cannot connect to the windowing system, or if
+    /// the event loop is created off the main thread where the platform
+    /// requires it.
+    pub(crate) fn new() -> Result<Self, WindowError> {
+        SHARED.with(|shared| {
+            let mut shared = shared.borrow_mut();
+            if let Some(existing) = shared.upgrade() {
+                return Ok(WinitPlatform { shared: existing });
+            }
+
+            // winit only has fallible constructors for some of its platforms,
+            // and panics on the others.
+            let event_loop = std::panic::catch_unwind(EventLoop::new).map_err(|panic| {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "winit failed to create its event loop".to_owned());
+                WindowError::Init(message)
+            })?;
+
+            let new = Rc::new(Shared {
+                event_loop: RefCell::new(event_loop),
+                windows: RefCell::new(HashMap::new()),
+            });
+            *shared = Rc::downgrade(&new);
+            Ok(WinitPlatform { shared: new })
+        })
+    }
+}
+
+impl PlatformBackend for WinitPlatform {
+    fn create_window(
+        &mut self,
+        builder: &WindowBuilder,
+        share: Option<&dyn WindowBackend>,
+    ) -> Result<Box<dyn WindowBackend>, WindowError> {
+        let share =
+            match share {
+                Some(share) => Some(share.as_any().downcast_ref::<WinitWindow>().ok_or_else(
+                    || {
+                        WindowError::ContextCreation(
+                            "cannot share objects with a context from another backend".to_owned(),
+                        )
+                    },
+                )?),
+                None => None,
+            };
+
+        if builder.context_api != ContextApi::Native {
+            log::warn!("the winit backend always uses the platform's native context API");
+        }
+
+        let event_loop = self.shared.event_loop.borrow();
+
+        let mut window_builder = glutin::window::WindowBuilder::new()
+            .with_title(builder.title.as_str())
+            .with_inner_size(LogicalSize::new(builder.width, builder.height))
+            .with_resizable(builder.resizable)
+            .with_decorations(builder.decorated)
+            .with_transparent(builder.transparent)
+            .with_visible(builder.visible);
+        match builder.fullscreen {
+            Some(selection) => {
+                let monitor = select_monitor(
+                    selection,
+                    event_loop.primary_monitor(),
+                    event_loop.available_monitors(),
+                );
+                window_builder = window_builder.with_fullscreen(
+                    monitor
+                        .and_then(|monitor| exclusive_video_mode(&monitor))
+                        .map(Fullscreen::Exclusive),
+                );
+            }
+            None => {
+                if let Some((x, y)) = builder.position {
+                    window_builder = window_builder.with_position(LogicalPosition::new(x, y));
+                }
+            }
+        }
+
+        let (major, minor) = builder.gl_version;
+        let mut context_builder = ContextBuilder::new()
+            .with_gl(GlRequest::Specific(Api::OpenGl, (major as u8, minor as u8)))
+            .with_gl_debug_flag(builder.debug_context)
+            .with_srgb(builder.srgb)
+            .with_vsync(builder.swap_interval > 0);
+        match builder.gl_profile {
+            GlProfile::Core => {
+                context_builder = context_builder.with_gl_profile(glutin::GlProfile::Core)
+            }
+            GlProfile::Compatibility => {
+                context_builder = context_builder.with_gl_profile(glutin::GlProfile::Compatibility)
+            }
+            GlProfile::Any => {}
+        }
+        if let Some(samples) = builder.samples {
+            context_builder = context_builder.with_multisampling(samples as u16);
+        }
+        if let Some(bits) = builder.depth_bits {
+            context_builder = context_builder.with_depth_buffer(bits as u8);
+        }
+        if let Some(bits) = builder.stencil_bits {
+            context_builder = context_builder.with_stencil_buffer(bits as u8);
+        }
+
+        let created = match share {
+            Some(share) => context_builder
+                .with_shared_lists(share.context().context())
+                .build_windowed(window_builder, &event_loop),
+            None => context_builder.build_windowed(window_builder, &event_loop),
+        };
+        let context = created.map_err(|error| match error {
+            CreationError::OpenGlVersionNotSupported => WindowError::UnsupportedGlVersion {
+                major,
+                minor,
+                description: error.to_string(),
+            },
+            error => WindowError::ContextCreation(error.to_string()),
+        })?;
+        // Like GLFW, creating a context doesn't change which one is current.
+        // `ContextId` tracks that and makes it current before it is used.
+        let context = unsafe { context.treat_as_current() };
+
+        let id = context.window().id();
+        let scale_factor = context.window().scale_factor();
+        self.shared
+            .windows
+            .borrow_mut()
+            .insert(id, WindowState::new(scale_factor));
+
+        Ok(Box::new(WinitWindow {
+            shared: Rc::clone(&self.shared),
+            id,
+            context: Some(context),
+            vsync: builder.swap_interval > 0,
+            cursor_mode: CursorMode::Normal,
+            minimized: false,
+        }))
+    }
+
+    fn detach_current_context(&mut self) {
+        log::warn!("the winit backend cannot detach the current context");
+    }
+
+    #[inline]
+    fn poll_events(&mut self) {
+        self.shared.pump();
+    }
+}
+
+pub(crate) struct WinitWindow {
+    shared: Rc<Shared>,
+    id: WindowId,
+    // Only None while the context is being made current, which consumes it.
+    context: Option<WindowedContext<PossiblyCurrent>>,
+
+    vsync: bool,
+    cursor_mode: CursorMode,
+    // winit can minimize a window but not tell whether it is.
+    minimized: bool,
+}
+
+impl WinitWindow {
+    #[inline]
+    fn context(&self) -> &WindowedContext<PossiblyCurrent> {
+        self.context.as_ref().unwrap()
+    }
+
+    #[inline]
+    fn window(&self) -> &glutin::window::Window {
+        self.context().window()
+    }
+
+    fn with_state<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&mut WindowState) -> T,
+    {
+        let mut windows = self.shared.windows.borrow_mut();
+        f(windows.get_mut(&self.id).unwrap())
+    }
+}
+
+impl WindowBackend for WinitWindow {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn make_current(&mut self) {
+        let context = self.context.take().unwrap();
+        self.context = Some(match unsafe { context.make_current() } {
+            Ok(context) => context,
+            Err((context, error)) => {
+                log::error!("failed to make the OpenGL context current: {}", error);
+                context
+            }
+        });
+    }
+
+    fn get_proc_address(&mut self, symbol: &str) -> *const c_void {
+        self.context().get_proc_address(symbol)
+    }
+
+    fn swap_buffers(&mut self) {
+        if let Err(error) = self.context().swap_buffers() {
+            log::error!("failed to swap buffers: {}", error);
+        }
+    }
+
+    fn set_swap_interval(&mut self, interval: u32) {
+        if (interval > 0) != self.vsync {
+            log::warn!("the winit backend can only set vsync when a window is created");
+        }
+    }
+
+    fn poll_events(&mut self, events: &mut Vec<Event>) {
+        self.shared.pump();
+
+        let received = self.with_state(|state| std::mem::take(&mut state.events));
+        for event in &received {
+            if let Event::Resized { width, height } = *event {
+                self.context().resize(PhysicalSize::new(width, height));
+            }
+        }
+        events.extend(received);
+    }
+
+    fn gamepad_source(&self) -> Option<Box<dyn GamepadSource>> {
+        None
+    }
+
+    fn framebuffer_size(&self) -> (u32, u32) {
+        let size = self.window().inner_size();
+        (size.width, size.height)
+    }
+
+    fn logical_size(&self) -> (u32, u32) {
+        let window = self.window();
+        let size: LogicalSize<u32> = window.inner_size().to_logical(window.scale_factor());
+        (size.width, size.height)
+    }
+
+    fn content_scale(&self) -> Vector2 {
+        let scale = self.window().scale_factor() as f32;
+        Vector2::new(scale, scale)
+    }
+
+    #[inline]
+    fn set_title(&mut self, title: &str) {
+        self.window().set_title(title);
+    }
+
+    fn set_fullscreen(&mut self, mode: FullscreenMode) -> bool {
+        let window = self.window();
+        let fullscreen = match mode {
+            FullscreenMode::Windowed => None,
+            FullscreenMode::Exclusive(selection) => {
+                let monitor = select_monitor(
+                    selection,
+                    window.primary_monitor(),
+                    window.available_monitors(),
+                );
+                match monitor.and_then(|monitor| exclusive_video_mode(&monitor)) {
+                    Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                    None => return false,
+                }
+            }
+            FullscreenMode::Borderless(selection) => {
+                let monitor = select_monitor(
+                    selection,
+                    window.primary_monitor(),
+                    window.available_monitors(),
+                );
+                match monitor {
+                    Some(monitor) => Some(Fullscreen::Borderless(Some(monitor))),
+                    None => return false,
+                }
+            }
+        };
+
+        window.set_fullscreen(fullscreen);
+        true
+    }
+
+    fn minimize(&mut self) {
+        self.window().set_minimized(true);
+        self.minimized = true;
+    }
+
+    #[inline]
+    fn maximize(&mut self) {
+        self.window().set_maximized(true);
+    }
+
+    fn restore(&mut self) {
+        let window = self.window();
+        window.set_minimized(false);
+        window.set_maximized(false);
+        self.minimized = false;
+    }
+
+    #[inline]
+    fn is_minimized(&self) -> bool {
+        self.minimized
+    }
+
+    #[inline]
+    fn is_maximized(&self) -> bool {
+        self.window().is_maximized()
+    }
+
+    #[inline]
+    fn cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    fn set_cursor_mode(&mut self, mode: CursorMode) {
+        let window = self.window();
+        window.set_cursor_visible(mode == CursorMode::Normal);
+        if let Err(error) = window.set_cursor_grab(mode == CursorMode::Captured) {
+            log::warn!("failed to capture the cursor: {}", error);
+        }
+        self.cursor_mode = mode;
+    }
+
+    fn set_standard_cursor(&mut self, cursor: StandardCursor) {
+        self.window().set_cursor_icon(match cursor {
+            StandardCursor::Arrow => CursorIcon::Default,
+            StandardCursor::IBeam => CursorIcon::Text,
+            StandardCursor::Crosshair => CursorIcon::Crosshair,
+            StandardCursor::Hand => CursorIcon::Hand,
+            StandardCursor::HorizontalResize => CursorIcon::EwResize,
+            StandardCursor::VerticalResize => CursorIcon::NsResize,
+        });
+    }
+
+    fn set_cursor_image(&mut self, _: &Image, _: u32, _: u32) {
+        log::warn!("the winit backend does not support custom cursor images");
+    }
+
+    fn reset_cursor(&mut self) {
+        self.window().set_cursor_icon(CursorIcon::Default);
+    }
+
+    /// winit takes a single icon, so the largest image is used.
+    fn set_icon(&mut self, images: &[Image]) {
+        let icon = match images
+            .iter()
+            .max_by_key(|image| image.width() * image.height())
+        {
+            Some(image) => {
+                match Icon::from_rgba(image.pixels().to_vec(), image.width(), image.height()) {
+                    Ok(icon) => Some(icon),
+                    Err(error) => {
+                        log::error!("failed to set the window icon: {}", error);
+                        return;
+                    }
+                }
+            }
+            None => None,
+        };
+        self.window().set_window_icon(icon);
+    }
+
+    fn clipboard(&self) -> Option<String> {
+        log::warn!("the winit backend does not support the clipboard");
+        None
+    }
+
+    fn set_clipboard(&mut self, _: &str) {
+        log::warn!("the winit backend does not support the clipboard");
+    }
+
+    fn should_close(&self) -> bool {
+        self.with_state(|state| state.should_close)
+    }
+
+    fn set_should_close(&mut self, should_close: bool) {
+        self.with_state(|state| state.should_close = should_close);
+    }
+}
+
+impl Drop for WinitWindow {
+    fn drop(&mut self) {
+        self.shared.windows.borrow_mut().remove(&self.id);
+    }
+}
+
+fn select_monitor<I>(
+    selection: MonitorSelection,
+    primary: Option<MonitorHandle>,
+    mut available: I,
+) -> Option<MonitorHandle>
+where
+    I: Iterator<Item = MonitorHandle>,
+{
+    match selection {
+        MonitorSelection::Primary => primary,
+        MonitorSelection::Index(index) => available.nth(index),
+    }
+}
+
+/// winit can't report a monitor's current video mode, so use the best one
+/// at its current resolution.
+fn exclusive_video_mode(monitor: &MonitorHandle) -> Option<VideoMode> {
+    let size = monitor.size();
+    monitor
+        .video_modes()
+        .filter(|mode| mode.size() == size)
+        .max_by_key(|mode| (mode.refresh_rate(), mode.bit_depth()))
+}
+
+fn modifiers_from_winit(state: ModifiersState) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, state.shift());
+    modifiers.set(Modifiers::CONTROL, state.ctrl());
+    modifiers.set(Modifiers::ALT, state.alt());
+    modifiers.set(Modifiers::SUPER, state.logo());
+    modifiers
+}
+
+/// Extra buttons are numbered differently per platform; the back and forward
+/// buttons are 8 and 9 on X11 and 1 and 2 on Windows.
+fn mouse_button_from_winit(button: glutin::event::MouseButton) -> Option<MouseButton> {
+    use glutin::event::MouseButton as Button;
+
+    match button {
+        Button::Left => Some(MouseButton::Left),
+        Button::Right => Some(MouseButton::Right),
+        Button::Middle => Some(MouseButton::Middle),
+        Button::Other(1) | Button::Other(8) => Some(MouseButton::Button4),
+        Button::Other(2) | Button::Other(9) => Some(MouseButton::Button5),
+        Button::Other(_) => None,
+    }
+}
+
+fn key_from_winit(key: VirtualKeyCode) -> Key {
+    use VirtualKeyCode as Vk;
+
+    match key {
+        Vk::Space => Key::Space,
+        Vk::Apostrophe => Key::Apostrophe,
+        Vk::Comma => Key::Comma,
+        Vk::Minus => Key::Minus,
+        Vk::Period => Key::Period,
+        Vk::Slash => Key::Slash,
+        Vk::Key0 => Key::Num0,
+        Vk::Key1 => Key::Num1,
+        Vk::Key2 => Key::Num2,
+        Vk::Key3 => Key::Num3,
+        Vk::Key4 => Key::Num4,
+        Vk::Key5 => Key::Num5,
+        Vk::Key6 => Key::Num6,
+        Vk::Key7 => Key::Num7,
+        Vk::Key8 => Key::Num8,
+        Vk::Key9 => Key::Num9,
+        Vk::Semicolon => Key::Semicolon,
+        Vk::Equals => Key::Equal,
+        Vk::A => Key::A,
+        Vk::B => Key::B,
+        Vk::C => Key::C,
+        Vk::D => Key::D,
+        Vk::E => Key::E,
+        Vk::F => Key::F,
+        Vk::G => Key::G,
+        Vk::H => Key::H,
+        Vk::I => Key::I,
+        Vk::J => Key::J,
+        Vk::K => Key::K,
+        Vk::L => Key::L,
+        Vk::M => Key::M,
+        Vk::N => Key::N,
+        Vk::O => Key::O,
+        Vk::P => Key::P,
+        Vk::Q => Key::Q,
+        Vk::R => Key::R,
+        Vk::S => Key::S,
+        Vk::T => Key::T,
+        Vk::U => Key::U,
+        Vk::V => Key::V,
+        Vk::W => Key::W,
+        Vk::X => Key::X,
+        Vk::Y => Key::Y,
+        Vk::Z => Key::Z,
+        Vk::LBracket => Key::LeftBracket,
+        Vk::Backslash => Key::Backslash,
+        Vk::RBracket => Key::RightBracket,
+        Vk::Grave => Key::GraveAccent,
+        Vk::OEM102 => Key::World1,
+        Vk::Escape => Key::Escape,
+        Vk::Return => Key::Enter,
+        Vk::Tab => Key::Tab,
+        Vk::Back => Key::Backspace,
+        Vk::Insert => Key::Insert,
+        Vk::Delete => Key::Delete,
+        Vk::Right => Key::Right,
+        Vk::Left => Key::Left,
+        Vk::Down => Key::Down,
+        Vk::Up => Key::Up,
+        Vk::PageUp => Key::PageUp,
+        Vk::PageDown => Key::PageDown,
+        Vk::Home => Key::Home,
+        Vk::End => Key::End,
+        Vk::Capital => Key::CapsLock,
+        Vk::Scroll => Key::ScrollLock,
+        Vk::Numlock => Key::NumLock,
+        Vk::Snapshot => Key::PrintScreen,
+        Vk::Pause => Key::Pause,
+        Vk::F1 => Key::F1,
+        Vk::F2 => Key::F2,
+        Vk::F3 => Key::F3,
+        Vk::F4 => Key::F4,
+        Vk::F5 => Key::F5,
+        Vk::F6 => Key::F6,
+        Vk::F7 => Key::F7,
+        Vk::F8 => Key::F8,
+        Vk::F9 => Key::F9,
+        Vk::F10 => Key::F10,
+        Vk::F11 => Key::F11,
+        Vk::F12 => Key::F12,
+        Vk::F13 => Key::F13,
+        Vk::F14 => Key::F14,
+        Vk::F15 => Key::F15,
+        Vk::F16 => Key::F16,
+        Vk::F17 => Key::F17,
+        Vk::F18 => Key::F18,
+        Vk::F19 => Key::F19,
+        Vk::F20 => Key::F20,
+        Vk::F21 => Key::F21,
+        Vk::F22 => Key::F22,
+        Vk::F23 => Key::F23,
+        Vk::F24 => Key::F24,
+        Vk::Numpad0 => Key::Kp0,
+        Vk::Numpad1 => Key::Kp1,
+        Vk::Numpad2 => Key::Kp2,
+        Vk::Numpad3 => Key::Kp3,
+        Vk::Numpad4 => Key::Kp4,
+        Vk::Numpad5 => Key::Kp5,
+        Vk::Numpad6 => Key::Kp6,
+        Vk::Numpad7 => Key::Kp7,
+        Vk::Numpad8 => Key::Kp8,
+        Vk::Numpad9 => Key::Kp9,
+        Vk::NumpadDecimal => Key::KpDecimal,
+        Vk::NumpadDivide => Key::KpDivide,
+        Vk::NumpadMultiply => Key::KpMultiply,
+        Vk::NumpadSubtract => Key::KpSubtract,
+        Vk::NumpadAdd => Key::KpAdd,
+        Vk::NumpadEnter => Key::KpEnter,
+        Vk::NumpadEquals => Key::KpEqual,
+        Vk::LShift => Key::LeftShift,
+        Vk::LControl => Key::LeftControl,
+        Vk::LAlt => Key::LeftAlt,
+        Vk::LWin => Key::LeftSuper,
+        Vk::RShift => Key::RightShift,
+        Vk::RControl => Key::RightControl,
+        Vk::RAlt => Key::RightAlt,
+        Vk::RWin => Key::RightSuper,
+        Vk::Apps => Key::Menu,
+        _ => Key::Unknown,
+    }
+}
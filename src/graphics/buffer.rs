@@ -1,39 +1,288 @@
 use crate::graphics::gl;
-pub use crate::graphics::gl::BufferType;
+pub use crate::graphics::gl::{BufferType, BufferUsage, IndexType, MapAccess};
+use std::cell::Cell;
+use std::ops::{Deref, DerefMut, Range};
+use thiserror::Error;
 
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BufferError {
+    #[error("elements {start}..{end} are out of bounds of a buffer with a capacity of {capacity}")]
+    OutOfBounds {
+        start: usize,
+        end: usize,
+        capacity: usize,
+    },
+    #[error("the buffer is mapped")]
+    Mapped,
+    #[error("the buffer could not be mapped")]
+    MapFailed,
+}
+
+/// A type of the elements in a `Buffer`. It is implemented for the plain data
+/// types buffers hold, for which any bytes read back from OpenGL are a valid
+/// value, and can't be implemented outside of this crate.
+pub trait BufferElement: Copy + sealed::Sealed {}
+
+impl BufferElement for u8 {}
+impl BufferElement for u16 {}
+impl BufferElement for u32 {}
+impl BufferElement for f32 {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for f32 {}
+}
+
+/// A buffer of vertex data or indices.
+///
+/// Its data store holds `capacity()` elements, of which the first `len()` are
+/// drawn. Updating the buffer within its capacity reuses the store, so
+/// buffers that are updated often should be created with room to grow and a
+/// `BufferUsage` other than `Static`.
 #[derive(Debug)]
 pub struct Buffer {
-    len: usize,
+    len: Cell<usize>,
+    capacity: Cell<usize>,
     size: usize,
 
     buffer_id: gl::BufferId,
     buffer_type: BufferType,
+    index_type: Option<IndexType>,
+    usage: BufferUsage,
+    /// The size of an element in bytes.
+    element_size: usize,
+    mapped: Cell<bool>,
 }
 
 impl Buffer {
     pub fn indices(data: &[u32]) -> Self {
-        Buffer::from_array(BufferType::ElementArrayBuffer, data, 0)
+        Buffer::from_indices(IndexType::UnsignedInt, data)
+    }
+
+    pub fn indices_u16(data: &[u16]) -> Self {
+        Buffer::from_indices(IndexType::UnsignedShort, data)
+    }
+
+    pub fn indices_u8(data: &[u8]) -> Self {
+        Buffer::from_indices(IndexType::UnsignedByte, data)
     }
 
     pub fn array(data: &[f32], size: usize) -> Self {
-        Buffer::from_array(BufferType::ArrayBuffer, data, size)
+        Buffer::array_with_usage(data, size, BufferUsage::Static)
+    }
+
+    pub fn array_with_usage(data: &[f32], size: usize, usage: BufferUsage) -> Self {
+        let buffer = Buffer::new(BufferType::ArrayBuffer, None, size, 4, usage);
+        buffer.fill(data);
+        buffer
+    }
+
+    /// An array buffer with room for `capacity` floats, `size` per vertex,
+    /// and nothing in it yet.
+    pub fn empty_array(capacity: usize, size: usize, usage: BufferUsage) -> Self {
+        let buffer = Buffer::new(BufferType::ArrayBuffer, None, size, 4, usage);
+        buffer.allocate(capacity);
+        buffer
+    }
+
+    /// An indices buffer with room for `capacity` indices of `index_type`,
+    /// and nothing in it yet.
+    pub fn empty_indices(index_type: IndexType, capacity: usize, usage: BufferUsage) -> Self {
+        let buffer = Buffer::new(
+            BufferType::ElementArrayBuffer,
+            Some(index_type),
+            0,
+            index_type.size(),
+            usage,
+        );
+        buffer.allocate(capacity);
+        buffer
     }
 
-    fn from_array<T>(buffer_type: BufferType, data: &[T], size: usize) -> Self {
+    fn from_indices<T>(index_type: IndexType, data: &[T]) -> Self {
+        let buffer = Buffer::new(
+            BufferType::ElementArrayBuffer,
+            Some(index_type),
+            0,
+            std::mem::size_of::<T>(),
+            BufferUsage::Static,
+        );
+        buffer.fill(data);
+        buffer
+    }
+
+    /// Creates and binds a buffer without a data store.
+    fn new(
+        buffer_type: BufferType,
+        index_type: Option<IndexType>,
+        size: usize,
+        element_size: usize,
+        usage: BufferUsage,
+    ) -> Self {
         let buffer = Buffer {
-            len: data.len(),
+            len: Cell::new(0),
+            capacity: Cell::new(0),
             size,
             buffer_id: gl::create_buffer(),
             buffer_type,
+            index_type,
+            usage,
+            element_size,
+            mapped: Cell::new(false),
         };
-
-        buffer.bind();
-        gl::create_static_buffer_data(buffer.buffer_type, &data);
         buffer.bind();
-
         buffer
     }
 
+    /// Replaces the data store of the bound buffer with one holding exactly
+    /// `data`.
+    fn fill<T>(&self, data: &[T]) {
+        gl::buffer_data(self.buffer_type, data, self.usage);
+        self.capacity.set(data.len());
+        self.len.set(data.len());
+    }
+
+    /// Replaces the data store of the bound buffer with an uninitialized one
+    /// of `capacity` elements.
+    fn allocate(&self, capacity: usize) {
+        gl::allocate_buffer_data(self.buffer_type, capacity * self.element_size, self.usage);
+        self.capacity.set(capacity);
+        self.len.set(0);
+    }
+
+    /// Replaces the elements starting at `offset` with `data`, growing the
+    /// length of the buffer if they go past it.
+    ///
+    /// Panics if `T` is not the size of the buffer's elements.
+    pub fn update<T: BufferElement>(&self, offset: usize, data: &[T]) -> Result<(), BufferError> {
+        self.check_element::<T>();
+        let end = offset
+            .checked_add(data.len())
+            .ok_or(BufferError::OutOfBounds {
+                start: offset,
+                end: usize::MAX,
+                capacity: self.capacity(),
+            })?;
+        self.check_range(offset..end)?;
+
+        self.bind();
+        gl::buffer_sub_data(self.buffer_type, offset * self.element_size, data);
+        self.len.set(self.len.get().max(end));
+        Ok(())
+    }
+
+    /// Replaces all of the data with `data`. The data store is orphaned
+    /// rather than overwritten while `data` fits into it, so that draws still
+    /// using the old data don't stall the upload, and reallocated to fit
+    /// `data` otherwise.
+    ///
+    /// Panics if `T` is not the size of the buffer's elements.
+    pub fn set_data<T: BufferElement>(&self, data: &[T]) -> Result<(), BufferError> {
+        self.check_element::<T>();
+        self.check_unmapped()?;
+
+        self.bind();
+        if data.len() <= self.capacity() {
+            self.allocate(self.capacity());
+            gl::buffer_sub_data(self.buffer_type, 0, data);
+            self.len.set(data.len());
+        } else {
+            self.fill(data);
+        }
+        Ok(())
+    }
+
+    /// Reallocates the data store to hold `capacity` elements, discarding
+    /// the contents.
+    pub fn resize(&self, capacity: usize) -> Result<(), BufferError> {
+        self.check_unmapped()?;
+        self.bind();
+        self.allocate(capacity);
+        Ok(())
+    }
+
+    /// Discards the contents and gives the buffer a new data store of the
+    /// same capacity, leaving the old one to the draws still using it. Call
+    /// it before rewriting a buffer that was drawn recently.
+    pub fn orphan(&self) -> Result<(), BufferError> {
+        self.resize(self.capacity())
+    }
+
+    /// Maps `range` of the elements into memory, until the returned mapping
+    /// is dropped. Mapping with `MapAccess::WRITE` grows the length of the
+    /// buffer to the end of `range`.
+    ///
+    /// ```ignore
+    /// let mut vertices = buffer.map::<f32>(0..6, MapAccess::WRITE | MapAccess::INVALIDATE_RANGE)?;
+    /// vertices.copy_from_slice(&[0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+    /// ```
+    ///
+    /// Panics if `T` is not the size of the buffer's elements.
+    pub fn map<T: BufferElement>(
+        &self,
+        range: Range<usize>,
+        access: MapAccess,
+    ) -> Result<BufferMapping<'_, T>, BufferError> {
+        self.check_element::<T>();
+        self.check_range(range.clone())?;
+
+        self.bind();
+        let len = range.end - range.start;
+        let data = unsafe {
+            gl::map_buffer_range(
+                self.buffer_type,
+                range.start * self.element_size,
+                len * self.element_size,
+                access,
+            )
+        };
+        if data.is_null() {
+            return Err(BufferError::MapFailed);
+        }
+
+        self.mapped.set(true);
+        if access.contains(MapAccess::WRITE) {
+            self.len.set(self.len.get().max(range.end));
+        }
+        Ok(BufferMapping {
+            buffer: self,
+            data: data as *mut T,
+            len,
+            access,
+        })
+    }
+
+    fn check_element<T: BufferElement>(&self) {
+        assert_eq!(
+            std::mem::size_of::<T>(),
+            self.element_size,
+            "wrong element type for the buffer",
+        );
+    }
+
+    fn check_range(&self, range: Range<usize>) -> Result<(), BufferError> {
+        self.check_unmapped()?;
+        if range.start > range.end || range.end > self.capacity() {
+            return Err(BufferError::OutOfBounds {
+                start: range.start,
+                end: range.end,
+                capacity: self.capacity(),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_unmapped(&self) -> Result<(), BufferError> {
+        if self.mapped.get() {
+            return Err(BufferError::Mapped);
+        }
+        Ok(())
+    }
+
     pub fn bind(&self) {
         gl::bind_buffer(self.buffer_type, &self.buffer_id);
     }
@@ -42,17 +291,33 @@ impl Buffer {
         gl::unbind_buffer(self.buffer_type);
     }
 
+    /// The number of elements in the buffer.
     pub fn len(&self) -> usize {
-        self.len
+        self.len.get()
+    }
+
+    /// The number of elements the data store has room for.
+    pub fn capacity(&self) -> usize {
+        self.capacity.get()
     }
 
     pub fn size(&self) -> usize {
         self.size
     }
 
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+
     pub fn buffer_type(&self) -> BufferType {
         self.buffer_type
     }
+
+    /// The type of the indices in an indices buffer, or None for an array
+    /// buffer.
+    pub fn index_type(&self) -> Option<IndexType> {
+        self.index_type
+    }
 }
 
 impl Drop for Buffer {
@@ -60,3 +325,74 @@ impl Drop for Buffer {
         gl::delete_buffer(&self.buffer_id);
     }
 }
+
+/// A range of a buffer mapped into memory by `Buffer::map`, which is unmapped
+/// when this is dropped.
+///
+/// Reading the elements requires `MapAccess::READ` and writing them
+/// `MapAccess::WRITE`, and doing otherwise panics.
+#[derive(Debug)]
+pub struct BufferMapping<'a, T> {
+    buffer: &'a Buffer,
+    data: *mut T,
+    len: usize,
+    access: MapAccess,
+}
+
+impl<T> BufferMapping<'_, T> {
+    /// The number of mapped elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Makes the writes to `range` of the mapped elements visible, for
+    /// mappings with `MapAccess::FLUSH_EXPLICIT`.
+    pub fn flush(&self, range: Range<usize>) {
+        debug_assert!(self.access.contains(MapAccess::FLUSH_EXPLICIT));
+        debug_assert!(range.start <= range.end && range.end <= self.len);
+
+        let size = std::mem::size_of::<T>();
+        self.buffer.bind();
+        gl::flush_mapped_buffer_range(
+            self.buffer.buffer_type,
+            range.start * size,
+            (range.end - range.start) * size,
+        );
+    }
+}
+
+impl<T> Deref for BufferMapping<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        assert!(
+            self.access.contains(MapAccess::READ),
+            "the buffer is not mapped for reading",
+        );
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+}
+
+impl<T> DerefMut for BufferMapping<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        assert!(
+            self.access.contains(MapAccess::WRITE),
+            "the buffer is not mapped for writing",
+        );
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl<T> Drop for BufferMapping<'_, T> {
+    fn drop(&mut self) {
+        self.buffer.bind();
+        if !gl::unmap_buffer(self.buffer.buffer_type) {
+            log::warn!("the contents of a mapped buffer were lost and have to be uploaded again");
+        }
+        self.buffer.mapped.set(false);
+    }
+}
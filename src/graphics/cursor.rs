@@ -0,0 +1,45 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CursorMode {
+    Normal,
+    /// The cursor is invisible while over the window but moves freely.
+    Hidden,
+    /// The cursor is hidden and locked to the window, and reports unbounded
+    /// movement. Used for first-person camera controls.
+    Captured,
+}
+
+impl CursorMode {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn to_glfw(self) -> glfw::CursorMode {
+        match self {
+            CursorMode::Normal => glfw::CursorMode::Normal,
+            CursorMode::Hidden => glfw::CursorMode::Hidden,
+            CursorMode::Captured => glfw::CursorMode::Disabled,
+        }
+    }
+}
+
+/// The cursor shapes every platform provides.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StandardCursor {
+    Arrow,
+    IBeam,
+    Crosshair,
+    Hand,
+    HorizontalResize,
+    VerticalResize,
+}
+
+impl StandardCursor {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn to_glfw(self) -> glfw::StandardCursor {
+        match self {
+            StandardCursor::Arrow => glfw::StandardCursor::Arrow,
+            StandardCursor::IBeam => glfw::StandardCursor::IBeam,
+            StandardCursor::Crosshair => glfw::StandardCursor::Crosshair,
+            StandardCursor::Hand => glfw::StandardCursor::Hand,
+            StandardCursor::HorizontalResize => glfw::StandardCursor::HResize,
+            StandardCursor::VerticalResize => glfw::StandardCursor::VResize,
+        }
+    }
+}
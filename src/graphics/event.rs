@@ -0,0 +1,83 @@
+use crate::input::{ButtonAction, GamepadEvent, GamepadId, Key, Modifiers, MouseButton};
+use crate::maths::Vector2;
+use std::path::PathBuf;
+
+/// Something that happened to a window or an input device since the last call
+/// to `EventLoop::process_events`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Key {
+        key: Key,
+        action: ButtonAction,
+        modifiers: Modifiers,
+    },
+    /// A unicode character was typed, after keyboard layout and dead keys have
+    /// been applied. Use this rather than `Key` for text input.
+    Char(char),
+    MouseButton {
+        button: MouseButton,
+        action: ButtonAction,
+        modifiers: Modifiers,
+    },
+    /// The cursor moved to a position in screen coordinates relative to the
+    /// top-left corner of the window.
+    CursorMoved(Vector2),
+    CursorEntered(bool),
+    Scroll(Vector2),
+    /// The framebuffer was resized, in pixels.
+    Resized {
+        width: u32,
+        height: u32,
+    },
+    Focused(bool),
+    /// The user asked to close the window. The window's close flag is already
+    /// set; call `Window::set_should_close(false)` to keep it open.
+    CloseRequested,
+    FilesDropped(Vec<PathBuf>),
+    ContentScaleChanged(Vector2),
+    GamepadConnected(GamepadId),
+    GamepadDisconnected(GamepadId),
+}
+
+impl Event {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn from_glfw(event: glfw::WindowEvent) -> Option<Event> {
+        use glfw::WindowEvent;
+
+        let event = match event {
+            WindowEvent::Key(key, _, action, modifiers) => Event::Key {
+                key: Key::from_glfw(key),
+                action: ButtonAction::from_glfw(action),
+                modifiers: Modifiers::from_glfw(modifiers),
+            },
+            WindowEvent::Char(character) => Event::Char(character),
+            WindowEvent::MouseButton(button, action, modifiers) => Event::MouseButton {
+                button: MouseButton::from_glfw(button),
+                action: ButtonAction::from_glfw(action),
+                modifiers: Modifiers::from_glfw(modifiers),
+            },
+            WindowEvent::CursorPos(x, y) => Event::CursorMoved(Vector2::new(x as f32, y as f32)),
+            WindowEvent::CursorEnter(entered) => Event::CursorEntered(entered),
+            WindowEvent::Scroll(x, y) => Event::Scroll(Vector2::new(x as f32, y as f32)),
+            WindowEvent::FramebufferSize(width, height) => Event::Resized {
+                width: width.max(0) as u32,
+                height: height.max(0) as u32,
+            },
+            WindowEvent::Focus(focused) => Event::Focused(focused),
+            WindowEvent::Close => Event::CloseRequested,
+            WindowEvent::FileDrop(paths) => Event::FilesDropped(paths),
+            WindowEvent::ContentScale(x, y) => Event::ContentScaleChanged(Vector2::new(x, y)),
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+impl From<GamepadEvent> for Event {
+    fn from(event: GamepadEvent) -> Self {
+        match event {
+            GamepadEvent::Connected(id) => Event::GamepadConnected(id),
+            GamepadEvent::Disconnected(id) => Event::GamepadDisconnected(id),
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use crate::graphics::gl::{ClearFlag, FramebufferTarget};
+pub use crate::graphics::gl::{FramebufferAttachment, FramebufferIncomplete};
+use crate::graphics::{gl, Image, Texture2D, TextureFilter, TextureFormat, Window};
+use std::cell::Cell;
+use std::rc::Rc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FramebufferError {
+    #[error("the framebuffer is incomplete: {0}")]
+    Incomplete(#[from] FramebufferIncomplete),
+    #[error("{requested} color attachments were requested but only {max} are supported")]
+    TooManyColorAttachments { requested: usize, max: usize },
+    #[error("{0:?} can not be used as a color attachment")]
+    NotColorFormat(TextureFormat),
+    #[error("{0:?} can not be used as a depth or stencil attachment")]
+    NotDepthStencilFormat(TextureFormat),
+}
+
+/// How a framebuffer attachment is stored. Textures can be sampled by shaders
+/// once rendered to, while renderbuffers can only be blitted or read back but
+/// may be faster to render to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Attachment {
+    Texture(TextureFormat),
+    Renderbuffer(TextureFormat),
+}
+
+impl Attachment {
+    #[inline]
+    pub fn format(&self) -> TextureFormat {
+        match *self {
+            Attachment::Texture(format) | Attachment::Renderbuffer(format) => format,
+        }
+    }
+}
+
+/// Configures the attachments of the framebuffer created by `build()`.
+///
+/// ```ignore
+/// let mut scene = FramebufferBuilder::window_sized(&window)
+///     .color(Attachment::Texture(TextureFormat::Rgba8))
+///     .depth_stencil(Attachment::Renderbuffer(TextureFormat::Depth24Stencil8))
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct FramebufferBuilder {
+    width: u32,
+    height: u32,
+    window_size: Option<Rc<Cell<(u32, u32)>>>,
+
+    colors: Vec<Attachment>,
+    depth_stencil: Option<Attachment>,
+}
+
+impl FramebufferBuilder {
+    pub fn new(width: u32, height: u32) -> Self {
+        FramebufferBuilder {
+            width,
+            height,
+            window_size: None,
+            colors: Vec::new(),
+            depth_stencil: None,
+        }
+    }
+
+    /// Matches the size of the window's framebuffer, resizing to follow it
+    /// whenever the window is resized.
+    pub fn window_sized(window: &Window) -> Self {
+        let (width, height) = window.size();
+        FramebufferBuilder {
+            window_size: Some(window.size_cell()),
+            ..FramebufferBuilder::new(width, height)
+        }
+    }
+
+    /// Adds a color attachment. Fragment shader output `i` is written to the
+    /// `i`th color attachment added.
+    pub fn color(mut self, attachment: Attachment) -> Self {
+        self.colors.push(attachment);
+        self
+    }
+
+    /// Sets the depth, stencil or combined depth and stencil attachment,
+    /// depending on its format.
+    pub fn depth_stencil(mut self, attachment: Attachment) -> Self {
+        self.depth_stencil = Some(attachment);
+        self
+    }
+
+    /// Creates the framebuffer on the current context.
+    pub fn build(self) -> Result<Framebuffer, FramebufferError> {
+        if let Some(attachment) = self.colors.iter().find(|a| !a.format().is_color()) {
+            return Err(FramebufferError::NotColorFormat(attachment.format()));
+        }
+        if let Some(attachment) = self.depth_stencil.filter(|a| a.format().is_color()) {
+            return Err(FramebufferError::NotDepthStencilFormat(attachment.format()));
+        }
+        let max = gl::max_color_attachments();
+        if self.colors.len() > max {
+            return Err(FramebufferError::TooManyColorAttachments {
+                requested: self.colors.len(),
+                max,
+            });
+        }
+
+        let mut framebuffer = Framebuffer {
+            width: self.width,
+            height: self.height,
+            window_size: self.window_size,
+            color_attachments: self.colors,
+            depth_stencil_attachment: self.depth_stencil,
+            colors: Vec::new(),
+            depth_stencil: None,
+            framebuffer_id: gl::create_framebuffer(),
+        };
+        framebuffer.attach()?;
+
+        Ok(framebuffer)
+    }
+}
+
+#[derive(Debug)]
+enum AttachmentStorage {
+    Texture(Texture2D),
+    Renderbuffer(Renderbuffer),
+}
+
+impl AttachmentStorage {
+    fn new(attachment: Attachment, width: u32, height: u32) -> Self {
+        match attachment {
+            Attachment::Texture(format) => {
+                AttachmentStorage::Texture(Texture2D::new(width, height, format))
+            }
+            Attachment::Renderbuffer(format) => {
+                AttachmentStorage::Renderbuffer(Renderbuffer::new(width, height, format))
+            }
+        }
+    }
+
+    fn attach(&self, attachment: FramebufferAttachment) {
+        match self {
+            AttachmentStorage::Texture(texture) => gl::framebuffer_texture_2d(
+                FramebufferTarget::Framebuffer,
+                attachment,
+                texture.texture_id(),
+            ),
+            AttachmentStorage::Renderbuffer(renderbuffer) => gl::framebuffer_renderbuffer(
+                FramebufferTarget::Framebuffer,
+                attachment,
+                &renderbuffer.renderbuffer_id,
+            ),
+        }
+    }
+
+    fn texture(&self) -> Option<&Texture2D> {
+        match self {
+            AttachmentStorage::Texture(texture) => Some(texture),
+            AttachmentStorage::Renderbuffer(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Renderbuffer {
+    renderbuffer_id: gl::RenderbufferId,
+}
+
+impl Renderbuffer {
+    fn new(width: u32, height: u32, format: TextureFormat) -> Self {
+        let renderbuffer = Renderbuffer {
+            renderbuffer_id: gl::create_renderbuffer(),
+        };
+        gl::bind_renderbuffer(&renderbuffer.renderbuffer_id);
+        gl::renderbuffer_storage(format, width, height);
+        gl::unbind_renderbuffer();
+        renderbuffer
+    }
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        gl::delete_renderbuffer(&self.renderbuffer_id);
+    }
+}
+
+/// A framebuffer object to render into instead of the window, for example to
+/// sample the result as a texture in a later pass.
+///
+/// Created through a `FramebufferBuilder`.
+#[derive(Debug)]
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    window_size: Option<Rc<Cell<(u32, u32)>>>,
+
+    color_attachments: Vec<Attachment>,
+    depth_stencil_attachment: Option<Attachment>,
+    colors: Vec<AttachmentStorage>,
+    depth_stencil: Option<AttachmentStorage>,
+
+    framebuffer_id: gl::FramebufferId,
+}
+
+impl Framebuffer {
+    /// (Re)creates the attachments at the current size and attaches them.
+    fn attach(&mut self) -> Result<(), FramebufferError> {
+        let (width, height) = (self.width, self.height);
+        self.colors = self
+            .color_attachments
+            .iter()
+            .map(|&attachment| AttachmentStorage::new(attachment, width, height))
+            .collect();
+        self.depth_stencil = self
+            .depth_stencil_attachment
+            .map(|attachment| AttachmentStorage::new(attachment, width, height));
+
+        gl::bind_framebuffer(FramebufferTarget::Framebuffer, &self.framebuffer_id);
+        let mut draw_buffers = Vec::with_capacity(self.colors.len());
+        for (i, storage) in self.colors.iter().enumerate() {
+            let attachment = FramebufferAttachment::Color(i as u32);
+            storage.attach(attachment);
+            draw_buffers.push(attachment);
+        }
+        if let (Some(storage), Some(attachment)) =
+            (&self.depth_stencil, self.depth_stencil_attachment)
+        {
+            storage.attach(attachment.format().attachment(0));
+        }
+        gl::draw_buffers(&draw_buffers);
+
+        let status = gl::check_framebuffer_status(FramebufferTarget::Framebuffer);
+        gl::unbind_framebuffer(FramebufferTarget::Framebuffer);
+        Ok(status?)
+    }
+
+    /// Recreates the attachments at a new size, discarding their contents.
+    /// Does nothing if the size is unchanged.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), FramebufferError> {
+        if (width, height) == (self.width, self.height) {
+            return Ok(());
+        }
+        self.width = width;
+        self.height = height;
+        self.attach()
+    }
+
+    /// Binds the framebuffer so that following draw calls render into it, and
+    /// sets the viewport to cover it. Window sized framebuffers are resized
+    /// first if the window was, unless it is minimized to a size of 0.
+    pub fn bind(&mut self) -> Result<(), FramebufferError> {
+        if let Some(window_size) = &self.window_size {
+            let (width, height) = window_size.get();
+            if width > 0 && height > 0 {
+                self.resize(width, height)?;
+            }
+        }
+
+        gl::bind_framebuffer(FramebufferTarget::Framebuffer, &self.framebuffer_id);
+        gl::set_view_port(0, 0, self.width, self.height);
+        Ok(())
+    }
+
+    /// Binds the default framebuffer again. Call `Window::bind` instead to
+    /// also restore the window's viewport.
+    pub fn unbind(&self) {
+        gl::unbind_framebuffer(FramebufferTarget::Framebuffer);
+    }
+
+    /// Copies the whole framebuffer into `target`, stretching it with
+    /// `filter` if their sizes differ. Color is copied from the first color
+    /// attachment to every color attachment of `target`.
+    pub fn blit(&self, target: &Framebuffer, buffers: &[ClearFlag], filter: TextureFilter) {
+        gl::bind_framebuffer(FramebufferTarget::Read, &self.framebuffer_id);
+        gl::bind_framebuffer(FramebufferTarget::Draw, &target.framebuffer_id);
+        gl::blit_framebuffer(
+            (0, 0, self.width, self.height),
+            (0, 0, target.width, target.height),
+            buffers,
+            filter,
+        );
+        gl::unbind_framebuffer(FramebufferTarget::Framebuffer);
+    }
+
+    /// Copies the whole framebuffer into the window's default framebuffer,
+    /// stretching it to the window's size with `filter` if they differ.
+    pub fn blit_to_window(&self, window: &Window, buffers: &[ClearFlag], filter: TextureFilter) {
+        let (width, height) = window.size();
+        gl::bind_framebuffer(FramebufferTarget::Read, &self.framebuffer_id);
+        gl::unbind_framebuffer(FramebufferTarget::Draw);
+        gl::blit_framebuffer(
+            (0, 0, self.width, self.height),
+            (0, 0, width, height),
+            buffers,
+            filter,
+        );
+        gl::unbind_framebuffer(FramebufferTarget::Framebuffer);
+    }
+
+    /// Reads back the `index`th color attachment as an RGBA image, or None if
+    /// there is no such attachment.
+    pub fn read_pixels(&self, index: usize) -> Option<Image> {
+        if index >= self.colors.len() {
+            return None;
+        }
+
+        gl::bind_framebuffer(FramebufferTarget::Read, &self.framebuffer_id);
+        gl::read_buffer(FramebufferAttachment::Color(index as u32));
+        let image = Image::read_framebuffer(self.width, self.height);
+        gl::unbind_framebuffer(FramebufferTarget::Read);
+        Some(image)
+    }
+
+    /// The texture of the `index`th color attachment, or None if it is a
+    /// renderbuffer or does not exist.
+    pub fn color_texture(&self, index: usize) -> Option<&Texture2D> {
+        self.colors.get(index).and_then(AttachmentStorage::texture)
+    }
+
+    /// The texture of the depth and/or stencil attachment, or None if it is a
+    /// renderbuffer or does not exist.
+    pub fn depth_stencil_texture(&self) -> Option<&Texture2D> {
+        self.depth_stencil
+            .as_ref()
+            .and_then(AttachmentStorage::texture)
+    }
+
+    #[inline]
+    pub fn color_attachment_count(&self) -> usize {
+        self.colors.len()
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        gl::delete_framebuffer(&self.framebuffer_id);
+    }
+}
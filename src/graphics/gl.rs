@@ -1,6 +1,53 @@
 use crate::maths::*;
 use bitflags::bitflags;
 use raw_gl::types::*;
+use std::cell::RefCell;
+use std::convert::TryFrom;
+
+/// The `raw_gl` function of the given name, or its recording stand-in from
+/// `mock` with the `gl-mock` feature.
+macro_rules! gl_raw {
+    ($function:ident) => {{
+        #[cfg(not(feature = "gl-mock"))]
+        let function = raw_gl::$function;
+        #[cfg(feature = "gl-mock")]
+        let function = crate::graphics::gl::mock::$function;
+        function
+    }};
+}
+
+/// The function `gl_call!` calls: `gl_raw!`, or its wrapper from `trace` that
+/// also captures the call with the `gl-trace` feature.
+macro_rules! gl_fn {
+    ($function:ident) => {{
+        #[cfg(not(feature = "gl-trace"))]
+        let function = gl_raw!($function);
+        #[cfg(feature = "gl-trace")]
+        let function = crate::graphics::gl::trace::$function;
+        function
+    }};
+}
+
+/// Calls the `raw_gl` function of the same name. With the `gl-debug`
+/// feature, any error the call raised is logged along with the function's
+/// name.
+macro_rules! gl_call {
+    ($function:ident($($arg:expr),* $(,)?)) => {{
+        let result = gl_fn!($function)($($arg),*);
+        #[cfg(feature = "gl-debug")]
+        report_errors(stringify!($function));
+        result
+    }};
+}
+
+mod cache;
+use self::cache::Slot;
+pub use self::cache::*;
+
+#[cfg(feature = "gl-mock")]
+pub mod mock;
+#[cfg(feature = "gl-trace")]
+pub mod trace;
 
 use thiserror::Error;
 #[derive(Error, Debug)]
@@ -22,6 +69,70 @@ pub enum GLError {
     NulByteInStr(#[from] std::ffi::NulError),
     #[error("failed to get Utf8 str from OpenGL")]
     Utf8(#[from] std::str::Utf8Error),
+
+    #[error("an enum argument is out of range (GL_INVALID_ENUM)")]
+    InvalidEnum,
+    #[error("a numeric argument is out of range (GL_INVALID_VALUE)")]
+    InvalidValue,
+    #[error("the operation is not allowed in the current state (GL_INVALID_OPERATION)")]
+    InvalidOperation,
+    #[error("the framebuffer object is not complete (GL_INVALID_FRAMEBUFFER_OPERATION)")]
+    InvalidFramebufferOperation,
+    #[error("there is not enough memory left to execute the command (GL_OUT_OF_MEMORY)")]
+    OutOfMemory,
+    #[error("an operation would cause an internal stack to underflow (GL_STACK_UNDERFLOW)")]
+    StackUnderflow,
+    #[error("an operation would cause an internal stack to overflow (GL_STACK_OVERFLOW)")]
+    StackOverflow,
+    #[error("unknown OpenGL error code {0:#x}")]
+    Unknown(u32),
+}
+
+impl GLError {
+    fn from_code(code: GLenum) -> GLError {
+        match code {
+            raw_gl::INVALID_ENUM => GLError::InvalidEnum,
+            raw_gl::INVALID_VALUE => GLError::InvalidValue,
+            raw_gl::INVALID_OPERATION => GLError::InvalidOperation,
+            raw_gl::INVALID_FRAMEBUFFER_OPERATION => GLError::InvalidFramebufferOperation,
+            raw_gl::OUT_OF_MEMORY => GLError::OutOfMemory,
+            raw_gl::STACK_UNDERFLOW => GLError::StackUnderflow,
+            raw_gl::STACK_OVERFLOW => GLError::StackOverflow,
+            code => GLError::Unknown(code),
+        }
+    }
+}
+
+/// Returns the oldest error raised by OpenGL since the last check, and clears
+/// the rest. Errors that are cleared this way are logged.
+///
+/// With the `gl-debug` feature every function in this module already checks
+/// for errors after its call, so this is only needed for raw OpenGL calls.
+pub fn check_error() -> Result<(), GLError> {
+    let code = unsafe { gl_fn!(GetError)() };
+    if code == raw_gl::NO_ERROR {
+        return Ok(());
+    }
+
+    loop {
+        let next = unsafe { gl_fn!(GetError)() };
+        if next == raw_gl::NO_ERROR {
+            break;
+        }
+        log::error!("OpenGL error: {}", GLError::from_code(next));
+    }
+    Err(GLError::from_code(code))
+}
+
+#[cfg(feature = "gl-debug")]
+fn report_errors(function: &str) {
+    loop {
+        let code = unsafe { gl_fn!(GetError)() };
+        if code == raw_gl::NO_ERROR {
+            break;
+        }
+        log::error!("gl{} failed: {}", function, GLError::from_code(code));
+    }
 }
 
 /// Load each OpenGL symbol using a custom load function.
@@ -48,7 +159,7 @@ bitflags! {
 /// clear the color buffers. Values are clamped to the range [0, 1].
 #[inline]
 pub fn set_clear_color(red: f32, green: f32, blue: f32, alpha: f32) {
-    unsafe { raw_gl::ClearColor(red, green, blue, alpha) };
+    unsafe { gl_call!(ClearColor(red, green, blue, alpha)) };
 }
 
 /// Sets the bitplane area of the window to values previously selected by
@@ -59,14 +170,14 @@ pub fn set_clear_color(red: f32, green: f32, blue: f32, alpha: f32) {
 pub fn clear(clear_flags: &[ClearFlag]) {
     let mut mask = 0;
     clear_flags.iter().for_each(|flag| mask |= flag.bits);
-    unsafe { raw_gl::Clear(mask) };
+    unsafe { gl_call!(Clear(mask)) };
 }
 
 /// Specifies the affine transformation of `x` and `y` from normalized device
 /// coordinates to window coordinates.
 #[inline]
 pub fn set_view_port(x: u32, y: u32, width: u32, height: u32) {
-    unsafe { raw_gl::Viewport(x as i32, y as i32, width as i32, height as i32) };
+    unsafe { gl_call!(Viewport(x as i32, y as i32, width as i32, height as i32)) };
 }
 
 // TODO: Implement other shader types
@@ -101,7 +212,7 @@ pub struct ShaderId {
 /// Returns Err if OpenGL fails to create a new shader object.
 #[inline]
 pub fn create_shader(shader_type: ShaderType) -> Result<ShaderId, GLError> {
-    let id = unsafe { raw_gl::CreateShader(shader_type.value()) };
+    let id = unsafe { gl_call!(CreateShader(shader_type.value())) };
     if id == 0 {
         Err(GLError::CreateShader)
     } else {
@@ -114,7 +225,7 @@ pub fn create_shader(shader_type: ShaderType) -> Result<ShaderId, GLError> {
 /// deletion and will be deleted after it is detached.
 #[inline]
 pub fn delete_shader(shader: &ShaderId) {
-    unsafe { raw_gl::DeleteShader(shader.id) };
+    unsafe { gl_call!(DeleteShader(shader.id)) };
 }
 
 /// Sets the source code in the given shader object. The source string is not
@@ -125,7 +236,14 @@ pub fn delete_shader(shader: &ShaderId) {
 #[inline]
 pub fn set_shader_source(shader: &ShaderId, source: &str) -> Result<(), GLError> {
     let source = convert_str_into_c_string(source)?;
-    unsafe { raw_gl::ShaderSource(shader.id, 1, &source.as_ptr(), std::ptr::null()) };
+    unsafe {
+        gl_call!(ShaderSource(
+            shader.id,
+            1,
+            &source.as_ptr(),
+            std::ptr::null()
+        ))
+    };
     Ok(())
 }
 
@@ -146,10 +264,10 @@ fn convert_str_into_c_string(str: &str) -> Result<CString, GLError> {
 ///
 /// Returns Err if compilation fails or OpenGL returns a bad info log on error.
 pub fn compile_shader(shader: &ShaderId) -> Result<(), GLError> {
-    unsafe { raw_gl::CompileShader(shader.id) };
+    unsafe { gl_call!(CompileShader(shader.id)) };
 
     let mut success = raw_gl::TRUE as i32;
-    unsafe { raw_gl::GetShaderiv(shader.id, raw_gl::COMPILE_STATUS, &mut success) };
+    unsafe { gl_call!(GetShaderiv(shader.id, raw_gl::COMPILE_STATUS, &mut success)) };
 
     if success == raw_gl::TRUE as i32 {
         Ok(())
@@ -168,7 +286,7 @@ pub struct ProgramId {
 /// Returns Err if OpenGL fails to create a new program object.
 #[inline]
 pub fn create_program() -> Result<ProgramId, GLError> {
-    let id = unsafe { raw_gl::CreateProgram() };
+    let id = unsafe { gl_call!(CreateProgram()) };
     if id == 0 {
         Err(GLError::CreateProgram)
     } else {
@@ -181,21 +299,22 @@ pub fn create_program() -> Result<ProgramId, GLError> {
 /// deletion and will be deleted after it is unbound.
 #[inline]
 pub fn delete_program(program: &ProgramId) {
-    unsafe { raw_gl::DeleteProgram(program.id) };
+    unsafe { gl_call!(DeleteProgram(program.id)) };
+    cache::forget(|slot| *slot == Slot::Program, program.id);
 }
 
 /// Specifies a shader object to be included when the program is linked.
 /// `detach_shader()` should be called after linking.
 #[inline]
 pub fn attach_shader(program: &ProgramId, shader: &ShaderId) {
-    unsafe { raw_gl::AttachShader(program.id, shader.id) };
+    unsafe { gl_call!(AttachShader(program.id, shader.id)) };
 }
 
 /// Detaches a shader object from a specified program.
 /// If the shader has been marked for deletion, it will be deleted too.
 #[inline]
 pub fn detach_shader(program: &ProgramId, shader: &ShaderId) {
-    unsafe { raw_gl::DetachShader(program.id, shader.id) };
+    unsafe { gl_call!(DetachShader(program.id, shader.id)) };
 }
 
 /// Compiles the source code that has been stored in the shader object using
@@ -203,10 +322,10 @@ pub fn detach_shader(program: &ProgramId, shader: &ShaderId) {
 ///
 /// Returns Err if compilation fails or OpenGL returns a bad info log on error.
 pub fn link_program(program: &ProgramId) -> Result<(), GLError> {
-    unsafe { raw_gl::LinkProgram(program.id) };
+    unsafe { gl_call!(LinkProgram(program.id)) };
 
     let mut success = raw_gl::TRUE as i32;
-    unsafe { raw_gl::GetProgramiv(program.id, raw_gl::LINK_STATUS, &mut success) };
+    unsafe { gl_call!(GetProgramiv(program.id, raw_gl::LINK_STATUS, &mut success)) };
 
     if success == raw_gl::TRUE as i32 {
         Ok(())
@@ -225,9 +344,9 @@ pub fn link_program(program: &ProgramId) -> Result<(), GLError> {
 unsafe fn get_info_log<'a>(is_shader: bool, id: u32) -> Result<String, GLError> {
     let mut len: i32 = 0;
     if is_shader {
-        raw_gl::GetShaderiv(id, raw_gl::INFO_LOG_LENGTH, &mut len);
+        gl_call!(GetShaderiv(id, raw_gl::INFO_LOG_LENGTH, &mut len));
     } else {
-        raw_gl::GetProgramiv(id, raw_gl::INFO_LOG_LENGTH, &mut len);
+        gl_call!(GetProgramiv(id, raw_gl::INFO_LOG_LENGTH, &mut len));
     }
     let len = len as usize;
 
@@ -235,19 +354,19 @@ unsafe fn get_info_log<'a>(is_shader: bool, id: u32) -> Result<String, GLError>
     info_log.extend([b' '].iter().cycle().take(len));
 
     if is_shader {
-        raw_gl::GetShaderInfoLog(
+        gl_call!(GetShaderInfoLog(
             id,
             len as i32,
             std::ptr::null_mut(),
             info_log.as_mut_ptr() as *mut GLchar,
-        );
+        ));
     } else {
-        raw_gl::GetProgramInfoLog(
+        gl_call!(GetProgramInfoLog(
             id,
             len as i32,
             std::ptr::null_mut(),
             info_log.as_mut_ptr() as *mut GLchar,
-        );
+        ));
     }
 
     // match std::str::from_utf8(&info_log) {
@@ -257,16 +376,21 @@ unsafe fn get_info_log<'a>(is_shader: bool, id: u32) -> Result<String, GLError>
     Ok(std::str::from_utf8(&info_log)?.to_owned())
 }
 
-/// Sets the specified program object of the current rendering state.
+/// Sets the specified program object of the current rendering state. Does
+/// nothing if it already is.
 #[inline]
 pub fn bind_program(program: &ProgramId) {
-    unsafe { raw_gl::UseProgram(program.id) };
+    if cache::bind(Slot::Program, program.id) {
+        unsafe { gl_call!(UseProgram(program.id)) };
+    }
 }
 
 /// Invalidates the program object of the current rendering state.
 #[inline]
 pub fn unbind_program() {
-    unsafe { raw_gl::UseProgram(0) };
+    if cache::bind(Slot::Program, 0) {
+        unsafe { gl_call!(UseProgram(0)) };
+    }
 }
 
 pub struct UniformLocation {
@@ -283,7 +407,7 @@ pub fn get_uniform_location(
     name: &str,
 ) -> Result<UniformLocation, GLError> {
     let c_name = convert_str_into_c_string(name)?;
-    let location = unsafe { raw_gl::GetUniformLocation(program_id.id, c_name.as_ptr()) };
+    let location = unsafe { gl_call!(GetUniformLocation(program_id.id, c_name.as_ptr())) };
     if location == -1 {
         Err(GLError::InvalidUniformName(name.to_owned()))
     } else {
@@ -297,36 +421,52 @@ pub trait UniformData {
 
 impl UniformData for f32 {
     fn upload(&self, uniform: &UniformLocation) {
-        unsafe { raw_gl::Uniform1f(uniform.location, *self) };
+        unsafe { gl_call!(Uniform1f(uniform.location, *self)) };
     }
 }
 
 impl UniformData for Vector2 {
     fn upload(&self, uniform: &UniformLocation) {
-        unsafe { raw_gl::Uniform2f(uniform.location, self.x, self.y) };
+        unsafe { gl_call!(Uniform2f(uniform.location, self.x, self.y)) };
     }
 }
 
 impl UniformData for Vector3 {
     fn upload(&self, uniform: &UniformLocation) {
-        unsafe { raw_gl::Uniform3f(uniform.location, self.x, self.y, self.z) };
+        unsafe { gl_call!(Uniform3f(uniform.location, self.x, self.y, self.z)) };
     }
 }
 
 impl UniformData for Vector4 {
     fn upload(&self, uniform: &UniformLocation) {
-        unsafe { raw_gl::Uniform4f(uniform.location, self.x, self.y, self.z, self.w) };
+        unsafe { gl_call!(Uniform4f(uniform.location, self.x, self.y, self.z, self.w)) };
     }
 }
 
 impl UniformData for Matrix4 {
     fn upload(&self, uniform: &UniformLocation) {
         unsafe {
-            raw_gl::UniformMatrix4fv(uniform.location, 1, raw_gl::FALSE, &self.0 as *const f32)
+            gl_call!(UniformMatrix4fv(
+                uniform.location,
+                1,
+                raw_gl::FALSE,
+                &self.0 as *const f32
+            ))
         };
     }
 }
 
+/// A sampler uniform, holding the texture unit the sampled texture is bound
+/// to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sampler(pub u32);
+
+impl UniformData for Sampler {
+    fn upload(&self, uniform: &UniformLocation) {
+        unsafe { gl_call!(Uniform1i(uniform.location, self.0 as i32)) };
+    }
+}
+
 pub struct VertexArrayId {
     id: u32,
 }
@@ -335,7 +475,7 @@ pub struct VertexArrayId {
 #[inline]
 pub fn create_vertex_array() -> VertexArrayId {
     let mut id: u32 = 0;
-    unsafe { raw_gl::GenVertexArrays(1, &mut id) };
+    unsafe { gl_call!(GenVertexArrays(1, &mut id)) };
     VertexArrayId { id }
 }
 
@@ -344,19 +484,24 @@ pub fn create_vertex_array() -> VertexArrayId {
 /// it is automatically unbound and the default vao becomes current.
 #[inline]
 pub fn delete_vertex_array(vertex_array: &VertexArrayId) {
-    unsafe { raw_gl::DeleteVertexArrays(1, [vertex_array.id].as_ptr()) };
+    unsafe { gl_call!(DeleteVertexArrays(1, [vertex_array.id].as_ptr())) };
+    cache::forget(|slot| *slot == Slot::VertexArray, vertex_array.id);
 }
 
-/// Binds a vertex array object
+/// Binds a vertex array object, unless it already is.
 #[inline]
 pub fn bind_vertex_array(vertex_array: &VertexArrayId) {
-    unsafe { raw_gl::BindVertexArray(vertex_array.id) };
+    if cache::bind(Slot::VertexArray, vertex_array.id) {
+        unsafe { gl_call!(BindVertexArray(vertex_array.id)) };
+    }
 }
 
 /// Unbinds any currently bound vertex array object
 #[inline]
 pub fn unbind_vertex_array() {
-    unsafe { raw_gl::BindVertexArray(0) };
+    if cache::bind(Slot::VertexArray, 0) {
+        unsafe { gl_call!(BindVertexArray(0)) };
+    }
 }
 
 /// Specify the `attrib_location` and data format of the array of vertex
@@ -386,14 +531,14 @@ pub fn vertex_attrib_pointer_float(
     debug_assert!(location < 16);
     debug_assert!(size < 5);
     unsafe {
-        raw_gl::VertexAttribPointer(
+        gl_call!(VertexAttribPointer(
             location as u32,
             size as i32,
             raw_gl::FLOAT,
             normalized as u8,
             (stride * std::mem::size_of::<f32>()) as i32,
             (offset * std::mem::size_of::<f32>()) as *const raw_gl::types::GLvoid,
-        )
+        ))
     }
 }
 
@@ -401,14 +546,23 @@ pub fn vertex_attrib_pointer_float(
 #[inline]
 pub fn enable_vertex_attrib(location: usize) {
     debug_assert!(location < 16);
-    unsafe { raw_gl::EnableVertexAttribArray(location as u32) };
+    unsafe { gl_call!(EnableVertexAttribArray(location as u32)) };
 }
 
 /// Disable a vertex attribute array
 #[inline]
 pub fn disable_vertex_attrib(location: usize) {
     debug_assert!(location < 16);
-    unsafe { raw_gl::DisableVertexAttribArray(location as u32) };
+    unsafe { gl_call!(DisableVertexAttribArray(location as u32)) };
+}
+
+/// Sets how often a vertex attribute advances during instanced rendering.
+/// With a divisor of 0 it advances once per vertex, otherwise once every
+/// `divisor` instances.
+#[inline]
+pub fn vertex_attrib_divisor(location: usize, divisor: u32) {
+    debug_assert!(location < 16);
+    unsafe { gl_call!(VertexAttribDivisor(location as u32, divisor)) };
 }
 
 #[derive(Debug)]
@@ -420,7 +574,7 @@ pub struct BufferId {
 #[inline]
 pub fn create_buffer() -> BufferId {
     let mut id: u32 = 0;
-    unsafe { raw_gl::GenBuffers(1, &mut id) };
+    unsafe { gl_call!(GenBuffers(1, &mut id)) };
     BufferId { id }
 }
 
@@ -429,7 +583,8 @@ pub fn create_buffer() -> BufferId {
 /// bound, it is automatically unbound.
 #[inline]
 pub fn delete_buffer(buffer: &BufferId) {
-    unsafe { raw_gl::DeleteBuffers(1, [buffer.id].as_ptr()) };
+    unsafe { gl_call!(DeleteBuffers(1, [buffer.id].as_ptr())) };
+    cache::forget(|slot| matches!(slot, Slot::Buffer(_)), buffer.id);
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -447,27 +602,1458 @@ impl BufferType {
     }
 }
 
-/// Binds a buffer object
+/// Binds a buffer object, unless it already is. The element array buffer
+/// binding is part of the bound vertex array object.
 #[inline]
 pub fn bind_buffer(buffer_type: BufferType, buffer: &BufferId) {
-    unsafe { raw_gl::BindBuffer(buffer_type.value(), buffer.id) };
+    if cache::bind(Slot::Buffer(buffer_type.value()), buffer.id) {
+        unsafe { gl_call!(BindBuffer(buffer_type.value(), buffer.id)) };
+    }
 }
 
 /// Unbinds any currently bound buffer object
 #[inline]
 pub fn unbind_buffer(buffer_type: BufferType) {
-    unsafe { raw_gl::BindBuffer(buffer_type.value(), 0) };
+    if cache::bind(Slot::Buffer(buffer_type.value()), 0) {
+        unsafe { gl_call!(BindBuffer(buffer_type.value(), 0)) };
+    }
+}
+
+/// A hint of how often a buffer's contents change, which drivers use to pick
+/// where to store it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BufferUsage {
+    /// Uploaded once and drawn many times.
+    Static,
+    /// Updated now and then and drawn many times.
+    Dynamic,
+    /// Rewritten for about every draw.
+    Stream,
+}
+
+impl BufferUsage {
+    pub fn value(&self) -> u32 {
+        match *self {
+            BufferUsage::Static => raw_gl::STATIC_DRAW,
+            BufferUsage::Dynamic => raw_gl::DYNAMIC_DRAW,
+            BufferUsage::Stream => raw_gl::STREAM_DRAW,
+        }
+    }
 }
 
 // Creates and initializes a buffer object's data store.
 #[inline]
 pub fn create_static_buffer_data<T>(buffer_type: BufferType, data: &[T]) {
+    buffer_data(buffer_type, data, BufferUsage::Static);
+}
+
+/// Creates a new data store for the bound buffer holding `data`, replacing
+/// any previous one.
+#[inline]
+pub fn buffer_data<T>(buffer_type: BufferType, data: &[T], usage: BufferUsage) {
+    unsafe {
+        gl_call!(BufferData(
+            buffer_type.value(),
+            std::mem::size_of_val(data) as isize,
+            data.as_ptr() as *const raw_gl::types::GLvoid,
+            usage.value(),
+        ))
+    };
+}
+
+/// Creates a new, uninitialized data store of `size` bytes for the bound
+/// buffer. Doing so with the current size orphans the old store: draws still
+/// using it keep it alive while new data goes into the new one, instead of
+/// waiting for them.
+#[inline]
+pub fn allocate_buffer_data(buffer_type: BufferType, size: usize, usage: BufferUsage) {
+    unsafe {
+        gl_call!(BufferData(
+            buffer_type.value(),
+            size as isize,
+            std::ptr::null(),
+            usage.value(),
+        ))
+    };
+}
+
+/// Replaces the bound buffer's data starting `offset` bytes into it.
+#[inline]
+pub fn buffer_sub_data<T>(buffer_type: BufferType, offset: usize, data: &[T]) {
     unsafe {
-        raw_gl::BufferData(
+        gl_call!(BufferSubData(
             buffer_type.value(),
-            (data.len() * std::mem::size_of::<T>()) as isize,
+            offset as isize,
+            std::mem::size_of_val(data) as isize,
             data.as_ptr() as *const raw_gl::types::GLvoid,
-            raw_gl::STATIC_DRAW,
-        )
+        ))
+    };
+}
+
+bitflags! {
+    /// How a mapped buffer range is accessed. At least one of `READ` and
+    /// `WRITE` is required.
+    pub struct MapAccess: u32 {
+        const READ = raw_gl::MAP_READ_BIT;
+        const WRITE = raw_gl::MAP_WRITE_BIT;
+        /// The previous contents of the range may be discarded. Not allowed
+        /// with `READ`.
+        const INVALIDATE_RANGE = raw_gl::MAP_INVALIDATE_RANGE_BIT;
+        /// The previous contents of the whole buffer may be discarded. Not
+        /// allowed with `READ`.
+        const INVALIDATE_BUFFER = raw_gl::MAP_INVALIDATE_BUFFER_BIT;
+        /// Writes are only made visible by `flush_mapped_buffer_range`.
+        /// Requires `WRITE`.
+        const FLUSH_EXPLICIT = raw_gl::MAP_FLUSH_EXPLICIT_BIT;
+        /// Doesn't wait for draws still using the buffer, which then may see
+        /// the new data.
+        const UNSYNCHRONIZED = raw_gl::MAP_UNSYNCHRONIZED_BIT;
+    }
+}
+
+/// Maps `length` bytes of the bound buffer, starting `offset` bytes into it,
+/// into client memory. Returns null if the range can't be mapped.
+///
+/// # Safety
+/// The pointer is only valid until `unmap_buffer`, and must only be accessed
+/// as `access` allows.
+#[inline]
+pub unsafe fn map_buffer_range(
+    buffer_type: BufferType,
+    offset: usize,
+    length: usize,
+    access: MapAccess,
+) -> *mut u8 {
+    gl_call!(MapBufferRange(
+        buffer_type.value(),
+        offset as isize,
+        length as isize,
+        access.bits,
+    )) as *mut u8
+}
+
+/// Makes writes to part of a range mapped with `MapAccess::FLUSH_EXPLICIT`
+/// visible. `offset` is relative to the start of the mapped range.
+#[inline]
+pub fn flush_mapped_buffer_range(buffer_type: BufferType, offset: usize, length: usize) {
+    unsafe {
+        gl_call!(FlushMappedBufferRange(
+            buffer_type.value(),
+            offset as isize,
+            length as isize,
+        ))
+    };
+}
+
+/// Unmaps the bound buffer. Returns false if its contents were lost while
+/// mapped, for example by a display mode change, and have to be uploaded
+/// again.
+#[inline]
+pub fn unmap_buffer(buffer_type: BufferType) -> bool {
+    unsafe { gl_call!(UnmapBuffer(buffer_type.value())) == raw_gl::TRUE }
+}
+
+#[derive(Debug)]
+pub struct TextureId {
+    id: u32,
+}
+
+/// Creates an unused texture object and returns its internal id.
+#[inline]
+pub fn create_texture() -> TextureId {
+    let mut id: u32 = 0;
+    unsafe { gl_call!(GenTextures(1, &mut id)) };
+    TextureId { id }
+}
+
+/// Deletes a texture object. If the texture is currently bound, it is
+/// automatically unbound.
+#[inline]
+pub fn delete_texture(texture: &TextureId) {
+    unsafe { gl_call!(DeleteTextures(1, [texture.id].as_ptr())) };
+    cache::forget(|slot| matches!(slot, Slot::Texture { .. }), texture.id);
+}
+
+/// Selects the texture unit that following texture binds apply to.
+#[inline]
+pub fn active_texture(unit: u32) {
+    if cache::bind(Slot::ActiveTexture, unit) {
+        unsafe { gl_call!(ActiveTexture(raw_gl::TEXTURE0 + unit)) };
+    }
+}
+
+/// Binds a 2D texture to the active texture unit, unless it already is.
+#[inline]
+pub fn bind_texture_2d(texture: &TextureId) {
+    if cache::bind_texture(raw_gl::TEXTURE_2D, texture.id) {
+        unsafe { gl_call!(BindTexture(raw_gl::TEXTURE_2D, texture.id)) };
+    }
+}
+
+/// Unbinds any 2D texture bound to the active texture unit.
+#[inline]
+pub fn unbind_texture_2d() {
+    if cache::bind_texture(raw_gl::TEXTURE_2D, 0) {
+        unsafe { gl_call!(BindTexture(raw_gl::TEXTURE_2D, 0)) };
+    }
+}
+
+/// The format texture data is stored in on the GPU. Pixel data is uploaded
+/// with the same channels, as bytes for the 8 bit formats and as floats for
+/// the 32 bit ones.
+///
+/// The depth and stencil formats are meant for framebuffer attachments.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextureFormat {
+    R8,
+    Rgb8,
+    Rgba8,
+    R32F,
+    Rgb32F,
+    Rgba32F,
+    Depth24,
+    Depth32F,
+    Depth24Stencil8,
+    Stencil8,
+}
+
+impl TextureFormat {
+    pub fn internal_format(&self) -> u32 {
+        match *self {
+            TextureFormat::R8 => raw_gl::R8,
+            TextureFormat::Rgb8 => raw_gl::RGB8,
+            TextureFormat::Rgba8 => raw_gl::RGBA8,
+            TextureFormat::R32F => raw_gl::R32F,
+            TextureFormat::Rgb32F => raw_gl::RGB32F,
+            TextureFormat::Rgba32F => raw_gl::RGBA32F,
+            TextureFormat::Depth24 => raw_gl::DEPTH_COMPONENT24,
+            TextureFormat::Depth32F => raw_gl::DEPTH_COMPONENT32F,
+            TextureFormat::Depth24Stencil8 => raw_gl::DEPTH24_STENCIL8,
+            TextureFormat::Stencil8 => raw_gl::STENCIL_INDEX8,
+        }
+    }
+
+    /// The layout of the channels in uploaded pixel data.
+    pub fn format(&self) -> u32 {
+        match *self {
+            TextureFormat::R8 | TextureFormat::R32F => raw_gl::RED,
+            TextureFormat::Rgb8 | TextureFormat::Rgb32F => raw_gl::RGB,
+            TextureFormat::Rgba8 | TextureFormat::Rgba32F => raw_gl::RGBA,
+            TextureFormat::Depth24 | TextureFormat::Depth32F => raw_gl::DEPTH_COMPONENT,
+            TextureFormat::Depth24Stencil8 => raw_gl::DEPTH_STENCIL,
+            TextureFormat::Stencil8 => raw_gl::STENCIL_INDEX,
+        }
+    }
+
+    /// The type of each channel in uploaded pixel data.
+    pub fn data_type(&self) -> u32 {
+        match *self {
+            TextureFormat::R8
+            | TextureFormat::Rgb8
+            | TextureFormat::Rgba8
+            | TextureFormat::Stencil8 => raw_gl::UNSIGNED_BYTE,
+            TextureFormat::R32F
+            | TextureFormat::Rgb32F
+            | TextureFormat::Rgba32F
+            | TextureFormat::Depth32F => raw_gl::FLOAT,
+            TextureFormat::Depth24 => raw_gl::UNSIGNED_INT,
+            TextureFormat::Depth24Stencil8 => raw_gl::UNSIGNED_INT_24_8,
+        }
+    }
+
+    /// The number of channels per pixel.
+    pub fn channels(&self) -> usize {
+        match *self {
+            TextureFormat::Rgb8 | TextureFormat::Rgb32F => 3,
+            TextureFormat::Rgba8 | TextureFormat::Rgba32F => 4,
+            _ => 1,
+        }
+    }
+
+    /// Whether the format stores color, rather than depth or stencil values.
+    pub fn is_color(&self) -> bool {
+        self.attachment(0) == FramebufferAttachment::Color(0)
+    }
+
+    /// The framebuffer attachment point the format is attached to, where
+    /// color formats use the `color_index`th color attachment.
+    pub fn attachment(&self, color_index: u32) -> FramebufferAttachment {
+        match *self {
+            TextureFormat::Depth24 | TextureFormat::Depth32F => FramebufferAttachment::Depth,
+            TextureFormat::Depth24Stencil8 => FramebufferAttachment::DepthStencil,
+            TextureFormat::Stencil8 => FramebufferAttachment::Stencil,
+            _ => FramebufferAttachment::Color(color_index),
+        }
+    }
+}
+
+/// A type pixel data can be uploaded from and read back into. Sealed, as
+/// any bit pattern OpenGL writes has to be a valid value.
+pub trait PixelComponent: Copy + sealed::Sealed {
+    /// The OpenGL type matching `Self`.
+    const DATA_TYPE: u32;
+}
+
+impl PixelComponent for u8 {
+    const DATA_TYPE: u32 = raw_gl::UNSIGNED_BYTE;
+}
+
+impl PixelComponent for f32 {
+    const DATA_TYPE: u32 = raw_gl::FLOAT;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for f32 {}
+}
+
+/// Checks that `len` components of `T` hold `width` by `height` pixels of
+/// `format`, so that OpenGL stays within them.
+///
+/// Panics if they don't, or if `T` isn't the format's data type.
+fn check_pixel_data<T: PixelComponent>(format: TextureFormat, width: u32, height: u32, len: usize) {
+    assert_eq!(
+        T::DATA_TYPE,
+        format.data_type(),
+        "the pixel type doesn't match {:?}",
+        format
+    );
+    let expected = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(format.channels()))
+        .expect("the pixel data size overflows");
+    assert!(
+        len >= expected,
+        "expected {} pixel components but got {}",
+        expected,
+        len
+    );
+}
+
+/// Allocates uninitialized storage for the bound 2D texture.
+#[inline]
+pub fn allocate_texture_2d(format: TextureFormat, width: u32, height: u32) {
+    unsafe {
+        gl_call!(TexImage2D(
+            raw_gl::TEXTURE_2D,
+            0,
+            format.internal_format() as i32,
+            width as i32,
+            height as i32,
+            0,
+            format.format(),
+            format.data_type(),
+            std::ptr::null(),
+        ))
+    };
+}
+
+/// Allocates storage for the bound 2D texture and fills it with `pixels`.
+/// Rows are tightly packed, the first row being at the bottom of the texture.
+///
+/// Panics if `pixels` is too short or of the wrong type for `format`.
+#[inline]
+pub fn texture_image_2d<T: PixelComponent>(
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    pixels: &[T],
+) {
+    check_pixel_data::<T>(format, width, height, pixels.len());
+    unsafe {
+        gl_call!(PixelStorei(raw_gl::UNPACK_ALIGNMENT, 1));
+        gl_call!(TexImage2D(
+            raw_gl::TEXTURE_2D,
+            0,
+            format.internal_format() as i32,
+            width as i32,
+            height as i32,
+            0,
+            format.format(),
+            format.data_type(),
+            pixels.as_ptr() as *const raw_gl::types::GLvoid,
+        ))
+    };
+}
+
+/// Replaces a region of the bound 2D texture with `pixels`.
+///
+/// Panics under the same conditions as `texture_image_2d`.
+#[inline]
+pub fn texture_sub_image_2d<T: PixelComponent>(
+    format: TextureFormat,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    pixels: &[T],
+) {
+    check_pixel_data::<T>(format, width, height, pixels.len());
+    unsafe {
+        gl_call!(PixelStorei(raw_gl::UNPACK_ALIGNMENT, 1));
+        gl_call!(TexSubImage2D(
+            raw_gl::TEXTURE_2D,
+            0,
+            x as i32,
+            y as i32,
+            width as i32,
+            height as i32,
+            format.format(),
+            format.data_type(),
+            pixels.as_ptr() as *const raw_gl::types::GLvoid,
+        ))
+    };
+}
+
+/// Reads a rectangle of pixels from the read buffer of the bound read
+/// framebuffer into `pixels`, converting them to `format`. Rows are tightly
+/// packed, the first row being the bottom one.
+///
+/// Panics if `pixels` is too short or of the wrong type for `format`.
+#[inline]
+pub fn read_pixels<T: PixelComponent>(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    pixels: &mut [T],
+) {
+    check_pixel_data::<T>(format, width, height, pixels.len());
+    unsafe {
+        gl_call!(PixelStorei(raw_gl::PACK_ALIGNMENT, 1));
+        gl_call!(ReadPixels(
+            x as i32,
+            y as i32,
+            width as i32,
+            height as i32,
+            format.format(),
+            format.data_type(),
+            pixels.as_mut_ptr() as *mut raw_gl::types::GLvoid,
+        ))
+    };
+}
+
+/// Generates the mipmap levels of the bound 2D texture from its base level.
+#[inline]
+pub fn generate_mipmap_2d() {
+    unsafe { gl_call!(GenerateMipmap(raw_gl::TEXTURE_2D)) };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+impl TextureFilter {
+    pub fn value(&self) -> u32 {
+        match *self {
+            TextureFilter::Nearest => raw_gl::NEAREST,
+            TextureFilter::Linear => raw_gl::LINEAR,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextureWrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    ClampToBorder,
+}
+
+impl TextureWrap {
+    pub fn value(&self) -> u32 {
+        match *self {
+            TextureWrap::Repeat => raw_gl::REPEAT,
+            TextureWrap::MirroredRepeat => raw_gl::MIRRORED_REPEAT,
+            TextureWrap::ClampToEdge => raw_gl::CLAMP_TO_EDGE,
+            TextureWrap::ClampToBorder => raw_gl::CLAMP_TO_BORDER,
+        }
+    }
+}
+
+/// Sets how the bound 2D texture is sampled when minified. With a `mipmap`
+/// filter, samples are taken from the mipmap levels, which must have been
+/// generated.
+#[inline]
+pub fn set_texture_min_filter_2d(filter: TextureFilter, mipmap: Option<TextureFilter>) {
+    use TextureFilter::*;
+    let value = match (filter, mipmap) {
+        (filter, None) => filter.value(),
+        (Nearest, Some(Nearest)) => raw_gl::NEAREST_MIPMAP_NEAREST,
+        (Nearest, Some(Linear)) => raw_gl::NEAREST_MIPMAP_LINEAR,
+        (Linear, Some(Nearest)) => raw_gl::LINEAR_MIPMAP_NEAREST,
+        (Linear, Some(Linear)) => raw_gl::LINEAR_MIPMAP_LINEAR,
+    };
+    unsafe {
+        gl_call!(TexParameteri(
+            raw_gl::TEXTURE_2D,
+            raw_gl::TEXTURE_MIN_FILTER,
+            value as i32,
+        ))
+    };
+}
+
+/// Sets how the bound 2D texture is sampled when magnified.
+#[inline]
+pub fn set_texture_mag_filter_2d(filter: TextureFilter) {
+    unsafe {
+        gl_call!(TexParameteri(
+            raw_gl::TEXTURE_2D,
+            raw_gl::TEXTURE_MAG_FILTER,
+            filter.value() as i32,
+        ))
+    };
+}
+
+/// Sets how the bound 2D texture is sampled outside of [0, 1] along the s
+/// (horizontal) and t (vertical) axes.
+#[inline]
+pub fn set_texture_wrap_2d(s: TextureWrap, t: TextureWrap) {
+    unsafe {
+        gl_call!(TexParameteri(
+            raw_gl::TEXTURE_2D,
+            raw_gl::TEXTURE_WRAP_S,
+            s.value() as i32,
+        ));
+        gl_call!(TexParameteri(
+            raw_gl::TEXTURE_2D,
+            raw_gl::TEXTURE_WRAP_T,
+            t.value() as i32,
+        ));
+    };
+}
+
+#[derive(Debug)]
+pub struct RenderbufferId {
+    id: u32,
+}
+
+/// Creates an unused renderbuffer object and returns its internal id.
+#[inline]
+pub fn create_renderbuffer() -> RenderbufferId {
+    let mut id: u32 = 0;
+    unsafe { gl_call!(GenRenderbuffers(1, &mut id)) };
+    RenderbufferId { id }
+}
+
+/// Deletes a renderbuffer object, detaching it from the bound framebuffers.
+#[inline]
+pub fn delete_renderbuffer(renderbuffer: &RenderbufferId) {
+    unsafe { gl_call!(DeleteRenderbuffers(1, [renderbuffer.id].as_ptr())) };
+}
+
+#[inline]
+pub fn bind_renderbuffer(renderbuffer: &RenderbufferId) {
+    unsafe { gl_call!(BindRenderbuffer(raw_gl::RENDERBUFFER, renderbuffer.id)) };
+}
+
+#[inline]
+pub fn unbind_renderbuffer() {
+    unsafe { gl_call!(BindRenderbuffer(raw_gl::RENDERBUFFER, 0)) };
+}
+
+/// Allocates storage for the bound renderbuffer.
+#[inline]
+pub fn renderbuffer_storage(format: TextureFormat, width: u32, height: u32) {
+    unsafe {
+        gl_call!(RenderbufferStorage(
+            raw_gl::RENDERBUFFER,
+            format.internal_format(),
+            width as i32,
+            height as i32,
+        ))
     };
 }
+
+#[derive(Debug)]
+pub struct FramebufferId {
+    id: u32,
+}
+
+/// Creates an unused framebuffer object and returns its internal id.
+#[inline]
+pub fn create_framebuffer() -> FramebufferId {
+    let mut id: u32 = 0;
+    unsafe { gl_call!(GenFramebuffers(1, &mut id)) };
+    FramebufferId { id }
+}
+
+/// Deletes a framebuffer object. If it is bound, the default framebuffer is
+/// bound instead.
+#[inline]
+pub fn delete_framebuffer(framebuffer: &FramebufferId) {
+    unsafe { gl_call!(DeleteFramebuffers(1, [framebuffer.id].as_ptr())) };
+}
+
+/// Which operations a framebuffer is bound for. `Framebuffer` binds it for
+/// both reading and drawing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FramebufferTarget {
+    Framebuffer,
+    Read,
+    Draw,
+}
+
+impl FramebufferTarget {
+    pub fn value(&self) -> u32 {
+        match *self {
+            FramebufferTarget::Framebuffer => raw_gl::FRAMEBUFFER,
+            FramebufferTarget::Read => raw_gl::READ_FRAMEBUFFER,
+            FramebufferTarget::Draw => raw_gl::DRAW_FRAMEBUFFER,
+        }
+    }
+}
+
+#[inline]
+pub fn bind_framebuffer(target: FramebufferTarget, framebuffer: &FramebufferId) {
+    unsafe { gl_call!(BindFramebuffer(target.value(), framebuffer.id)) };
+}
+
+/// Binds the default framebuffer of the current context, which is the one
+/// displayed in the window.
+#[inline]
+pub fn unbind_framebuffer(target: FramebufferTarget) {
+    unsafe { gl_call!(BindFramebuffer(target.value(), 0)) };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FramebufferAttachment {
+    Color(u32),
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl FramebufferAttachment {
+    pub fn value(&self) -> u32 {
+        match *self {
+            FramebufferAttachment::Color(index) => raw_gl::COLOR_ATTACHMENT0 + index,
+            FramebufferAttachment::Depth => raw_gl::DEPTH_ATTACHMENT,
+            FramebufferAttachment::Stencil => raw_gl::STENCIL_ATTACHMENT,
+            FramebufferAttachment::DepthStencil => raw_gl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
+
+/// Attaches a 2D texture to the framebuffer bound to `target`.
+#[inline]
+pub fn framebuffer_texture_2d(
+    target: FramebufferTarget,
+    attachment: FramebufferAttachment,
+    texture: &TextureId,
+) {
+    unsafe {
+        gl_call!(FramebufferTexture2D(
+            target.value(),
+            attachment.value(),
+            raw_gl::TEXTURE_2D,
+            texture.id,
+            0,
+        ))
+    };
+}
+
+/// Attaches a renderbuffer to the framebuffer bound to `target`.
+#[inline]
+pub fn framebuffer_renderbuffer(
+    target: FramebufferTarget,
+    attachment: FramebufferAttachment,
+    renderbuffer: &RenderbufferId,
+) {
+    unsafe {
+        gl_call!(FramebufferRenderbuffer(
+            target.value(),
+            attachment.value(),
+            raw_gl::RENDERBUFFER,
+            renderbuffer.id,
+        ))
+    };
+}
+
+/// Selects which color attachments of the bound draw framebuffer fragment
+/// shader outputs are written to. Output `i` is written to `attachments[i]`.
+#[inline]
+pub fn draw_buffers(attachments: &[FramebufferAttachment]) {
+    let values = attachments
+        .iter()
+        .map(FramebufferAttachment::value)
+        .collect::<Vec<u32>>();
+    unsafe { gl_call!(DrawBuffers(values.len() as i32, values.as_ptr())) };
+}
+
+/// Selects the color attachment of the bound read framebuffer that is read
+/// from by blits and pixel readback.
+#[inline]
+pub fn read_buffer(attachment: FramebufferAttachment) {
+    unsafe { gl_call!(ReadBuffer(attachment.value())) };
+}
+
+/// The maximum number of color attachments a framebuffer can have.
+#[inline]
+pub fn max_color_attachments() -> usize {
+    let mut max: i32 = 0;
+    unsafe { gl_call!(GetIntegerv(raw_gl::MAX_COLOR_ATTACHMENTS, &mut max)) };
+    max as usize
+}
+
+/// Why a framebuffer can not be rendered to.
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FramebufferIncomplete {
+    #[error("the default framebuffer does not exist (GL_FRAMEBUFFER_UNDEFINED)")]
+    Undefined,
+    #[error("an attachment is incomplete (GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT)")]
+    IncompleteAttachment,
+    #[error("the framebuffer has no attachments (GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT)")]
+    MissingAttachment,
+    #[error("a draw buffer has no attachment (GL_FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER)")]
+    IncompleteDrawBuffer,
+    #[error("the read buffer has no attachment (GL_FRAMEBUFFER_INCOMPLETE_READ_BUFFER)")]
+    IncompleteReadBuffer,
+    #[error("the combination of attachment formats is not supported (GL_FRAMEBUFFER_UNSUPPORTED)")]
+    Unsupported,
+    #[error(
+        "the attachments have different sample counts (GL_FRAMEBUFFER_INCOMPLETE_MULTISAMPLE)"
+    )]
+    IncompleteMultisample,
+    #[error("the attachments are not all layered (GL_FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS)")]
+    IncompleteLayerTargets,
+    #[error("unknown framebuffer status {0:#x}")]
+    Unknown(u32),
+}
+
+/// Checks whether the framebuffer bound to `target` can be rendered to.
+#[inline]
+pub fn check_framebuffer_status(target: FramebufferTarget) -> Result<(), FramebufferIncomplete> {
+    let status = unsafe { gl_call!(CheckFramebufferStatus(target.value())) };
+    match status {
+        raw_gl::FRAMEBUFFER_COMPLETE => Ok(()),
+        raw_gl::FRAMEBUFFER_UNDEFINED => Err(FramebufferIncomplete::Undefined),
+        raw_gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => {
+            Err(FramebufferIncomplete::IncompleteAttachment)
+        }
+        raw_gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => {
+            Err(FramebufferIncomplete::MissingAttachment)
+        }
+        raw_gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => {
+            Err(FramebufferIncomplete::IncompleteDrawBuffer)
+        }
+        raw_gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => {
+            Err(FramebufferIncomplete::IncompleteReadBuffer)
+        }
+        raw_gl::FRAMEBUFFER_UNSUPPORTED => Err(FramebufferIncomplete::Unsupported),
+        raw_gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => {
+            Err(FramebufferIncomplete::IncompleteMultisample)
+        }
+        raw_gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => {
+            Err(FramebufferIncomplete::IncompleteLayerTargets)
+        }
+        status => Err(FramebufferIncomplete::Unknown(status)),
+    }
+}
+
+/// Copies a rectangle of pixels from the bound read framebuffer to a
+/// rectangle of the bound draw framebuffer, scaling with `filter` if they
+/// differ in size. Rectangles are given as `(x, y, width, height)`.
+///
+/// Depth and stencil buffers can only be copied with the `Nearest` filter.
+///
+/// Panics if a rectangle reaches past `i32::MAX`, which OpenGL can't take.
+#[inline]
+pub fn blit_framebuffer(
+    source: (u32, u32, u32, u32),
+    destination: (u32, u32, u32, u32),
+    buffers: &[ClearFlag],
+    filter: TextureFilter,
+) {
+    let [source_x0, source_y0, source_x1, source_y1] = rectangle_corners(source);
+    let [destination_x0, destination_y0, destination_x1, destination_y1] =
+        rectangle_corners(destination);
+
+    let mut mask = 0;
+    buffers.iter().for_each(|flag| mask |= flag.bits);
+    unsafe {
+        gl_call!(BlitFramebuffer(
+            source_x0,
+            source_y0,
+            source_x1,
+            source_y1,
+            destination_x0,
+            destination_y0,
+            destination_x1,
+            destination_y1,
+            mask,
+            filter.value(),
+        ))
+    };
+}
+
+/// The corners of an `(x, y, width, height)` rectangle as OpenGL takes them.
+fn rectangle_corners(rectangle: (u32, u32, u32, u32)) -> [i32; 4] {
+    let (x, y, width, height) = rectangle;
+    let corners = (|| {
+        let x0 = i32::try_from(x).ok()?;
+        let y0 = i32::try_from(y).ok()?;
+        let x1 = x0.checked_add(i32::try_from(width).ok()?)?;
+        let y1 = y0.checked_add(i32::try_from(height).ok()?)?;
+        Some([x0, y0, x1, y1])
+    })();
+    corners.unwrap_or_else(|| panic!("the rectangle {:?} is out of range", rectangle))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PrimitiveMode {
+    Points,
+    Lines,
+    /// Like `LineStrip`, with the last vertex connected back to the first.
+    LineLoop,
+    LineStrip,
+    Triangles,
+    /// Each vertex after the first two forms a triangle with the two before
+    /// it.
+    TriangleStrip,
+    /// Each vertex after the first two forms a triangle with the one before
+    /// it and the first vertex.
+    TriangleFan,
+}
+
+impl PrimitiveMode {
+    pub fn value(&self) -> u32 {
+        match *self {
+            PrimitiveMode::Points => raw_gl::POINTS,
+            PrimitiveMode::Lines => raw_gl::LINES,
+            PrimitiveMode::LineLoop => raw_gl::LINE_LOOP,
+            PrimitiveMode::LineStrip => raw_gl::LINE_STRIP,
+            PrimitiveMode::Triangles => raw_gl::TRIANGLES,
+            PrimitiveMode::TriangleStrip => raw_gl::TRIANGLE_STRIP,
+            PrimitiveMode::TriangleFan => raw_gl::TRIANGLE_FAN,
+        }
+    }
+}
+
+/// The integer type of the indices in an element array buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndexType {
+    UnsignedByte,
+    UnsignedShort,
+    UnsignedInt,
+}
+
+impl IndexType {
+    pub fn value(&self) -> u32 {
+        match *self {
+            IndexType::UnsignedByte => raw_gl::UNSIGNED_BYTE,
+            IndexType::UnsignedShort => raw_gl::UNSIGNED_SHORT,
+            IndexType::UnsignedInt => raw_gl::UNSIGNED_INT,
+        }
+    }
+
+    /// The size of one index in bytes.
+    pub fn size(&self) -> usize {
+        match *self {
+            IndexType::UnsignedByte => std::mem::size_of::<u8>(),
+            IndexType::UnsignedShort => std::mem::size_of::<u16>(),
+            IndexType::UnsignedInt => std::mem::size_of::<u32>(),
+        }
+    }
+}
+
+/// Renders `count` vertices, starting at `first`, from the enabled vertex
+/// attribute arrays of the bound vertex array object.
+#[inline]
+pub fn draw_arrays(mode: PrimitiveMode, first: usize, count: usize) {
+    unsafe { gl_call!(DrawArrays(mode.value(), first as i32, count as i32)) };
+}
+
+/// Like `draw_arrays`, but renders the vertices `instance_count` times.
+#[inline]
+pub fn draw_arrays_instanced(
+    mode: PrimitiveMode,
+    first: usize,
+    count: usize,
+    instance_count: usize,
+) {
+    unsafe {
+        gl_call!(DrawArraysInstanced(
+            mode.value(),
+            first as i32,
+            count as i32,
+            instance_count as i32,
+        ))
+    };
+}
+
+/// Renders `count` vertices using the indices in the element array buffer of
+/// the bound vertex array object, starting `offset` indices into the buffer.
+#[inline]
+pub fn draw_elements(mode: PrimitiveMode, count: usize, index_type: IndexType, offset: usize) {
+    unsafe {
+        gl_call!(DrawElements(
+            mode.value(),
+            count as i32,
+            index_type.value(),
+            (offset * index_type.size()) as *const raw_gl::types::GLvoid,
+        ))
+    };
+}
+
+/// Like `draw_elements`, but renders the indexed vertices `instance_count`
+/// times.
+#[inline]
+pub fn draw_elements_instanced(
+    mode: PrimitiveMode,
+    count: usize,
+    index_type: IndexType,
+    offset: usize,
+    instance_count: usize,
+) {
+    unsafe {
+        gl_call!(DrawElementsInstanced(
+            mode.value(),
+            count as i32,
+            index_type.value(),
+            (offset * index_type.size()) as *const raw_gl::types::GLvoid,
+            instance_count as i32,
+        ))
+    };
+}
+
+/// A fixed-function stage that is off until enabled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Capability {
+    DepthTest,
+    Blend,
+    CullFace,
+    StencilTest,
+    ScissorTest,
+}
+
+impl Capability {
+    pub fn value(&self) -> u32 {
+        match *self {
+            Capability::DepthTest => raw_gl::DEPTH_TEST,
+            Capability::Blend => raw_gl::BLEND,
+            Capability::CullFace => raw_gl::CULL_FACE,
+            Capability::StencilTest => raw_gl::STENCIL_TEST,
+            Capability::ScissorTest => raw_gl::SCISSOR_TEST,
+        }
+    }
+}
+
+#[inline]
+pub fn enable(capability: Capability) {
+    unsafe { gl_call!(Enable(capability.value())) };
+}
+
+#[inline]
+pub fn disable(capability: Capability) {
+    unsafe { gl_call!(Disable(capability.value())) };
+}
+
+/// How a fragment's value is compared with the one in the depth or stencil
+/// buffer. The fragment passes if `fragment <op> stored` holds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CompareFunc {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+impl CompareFunc {
+    pub fn value(&self) -> u32 {
+        match *self {
+            CompareFunc::Never => raw_gl::NEVER,
+            CompareFunc::Less => raw_gl::LESS,
+            CompareFunc::Equal => raw_gl::EQUAL,
+            CompareFunc::LessOrEqual => raw_gl::LEQUAL,
+            CompareFunc::Greater => raw_gl::GREATER,
+            CompareFunc::NotEqual => raw_gl::NOTEQUAL,
+            CompareFunc::GreaterOrEqual => raw_gl::GEQUAL,
+            CompareFunc::Always => raw_gl::ALWAYS,
+        }
+    }
+}
+
+#[inline]
+pub fn set_depth_func(func: CompareFunc) {
+    unsafe { gl_call!(DepthFunc(func.value())) };
+}
+
+/// Whether fragments passing the depth test write their depth. Also applies
+/// to `gl::clear()`.
+#[inline]
+pub fn set_depth_mask(write: bool) {
+    unsafe { gl_call!(DepthMask(write as GLboolean)) };
+}
+
+/// What the source (fragment) and destination (framebuffer) colors are
+/// multiplied by before being combined by the `BlendEquation`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+    ConstantColor,
+    OneMinusConstantColor,
+    ConstantAlpha,
+    OneMinusConstantAlpha,
+    SrcAlphaSaturate,
+}
+
+impl BlendFactor {
+    pub fn value(&self) -> u32 {
+        match *self {
+            BlendFactor::Zero => raw_gl::ZERO,
+            BlendFactor::One => raw_gl::ONE,
+            BlendFactor::SrcColor => raw_gl::SRC_COLOR,
+            BlendFactor::OneMinusSrcColor => raw_gl::ONE_MINUS_SRC_COLOR,
+            BlendFactor::DstColor => raw_gl::DST_COLOR,
+            BlendFactor::OneMinusDstColor => raw_gl::ONE_MINUS_DST_COLOR,
+            BlendFactor::SrcAlpha => raw_gl::SRC_ALPHA,
+            BlendFactor::OneMinusSrcAlpha => raw_gl::ONE_MINUS_SRC_ALPHA,
+            BlendFactor::DstAlpha => raw_gl::DST_ALPHA,
+            BlendFactor::OneMinusDstAlpha => raw_gl::ONE_MINUS_DST_ALPHA,
+            BlendFactor::ConstantColor => raw_gl::CONSTANT_COLOR,
+            BlendFactor::OneMinusConstantColor => raw_gl::ONE_MINUS_CONSTANT_COLOR,
+            BlendFactor::ConstantAlpha => raw_gl::CONSTANT_ALPHA,
+            BlendFactor::OneMinusConstantAlpha => raw_gl::ONE_MINUS_CONSTANT_ALPHA,
+            BlendFactor::SrcAlphaSaturate => raw_gl::SRC_ALPHA_SATURATE,
+        }
+    }
+}
+
+/// How the weighted source and destination colors are combined.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendEquation {
+    /// `source + destination`
+    Add,
+    /// `source - destination`
+    Subtract,
+    /// `destination - source`
+    ReverseSubtract,
+    /// The component-wise minimum, ignoring the factors.
+    Min,
+    /// The component-wise maximum, ignoring the factors.
+    Max,
+}
+
+impl BlendEquation {
+    pub fn value(&self) -> u32 {
+        match *self {
+            BlendEquation::Add => raw_gl::FUNC_ADD,
+            BlendEquation::Subtract => raw_gl::FUNC_SUBTRACT,
+            BlendEquation::ReverseSubtract => raw_gl::FUNC_REVERSE_SUBTRACT,
+            BlendEquation::Min => raw_gl::MIN,
+            BlendEquation::Max => raw_gl::MAX,
+        }
+    }
+}
+
+/// Sets the blend factors of the color and alpha channels separately.
+#[inline]
+pub fn set_blend_func(
+    source_color: BlendFactor,
+    destination_color: BlendFactor,
+    source_alpha: BlendFactor,
+    destination_alpha: BlendFactor,
+) {
+    unsafe {
+        gl_call!(BlendFuncSeparate(
+            source_color.value(),
+            destination_color.value(),
+            source_alpha.value(),
+            destination_alpha.value(),
+        ))
+    };
+}
+
+#[inline]
+pub fn set_blend_equation(color: BlendEquation, alpha: BlendEquation) {
+    unsafe { gl_call!(BlendEquationSeparate(color.value(), alpha.value())) };
+}
+
+/// The color used by the `Constant*` blend factors.
+#[inline]
+pub fn set_blend_color(red: f32, green: f32, blue: f32, alpha: f32) {
+    unsafe { gl_call!(BlendColor(red, green, blue, alpha)) };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Face {
+    Front,
+    Back,
+    FrontAndBack,
+}
+
+impl Face {
+    pub fn value(&self) -> u32 {
+        match *self {
+            Face::Front => raw_gl::FRONT,
+            Face::Back => raw_gl::BACK,
+            Face::FrontAndBack => raw_gl::FRONT_AND_BACK,
+        }
+    }
+}
+
+/// The order in which the vertices of front facing triangles appear on
+/// screen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl Winding {
+    pub fn value(&self) -> u32 {
+        match *self {
+            Winding::Clockwise => raw_gl::CW,
+            Winding::CounterClockwise => raw_gl::CCW,
+        }
+    }
+}
+
+/// Which faces are discarded while `Capability::CullFace` is enabled.
+#[inline]
+pub fn set_cull_face(face: Face) {
+    unsafe { gl_call!(CullFace(face.value())) };
+}
+
+#[inline]
+pub fn set_front_face(winding: Winding) {
+    unsafe { gl_call!(FrontFace(winding.value())) };
+}
+
+/// What happens to the stored stencil value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StencilOp {
+    Keep,
+    Zero,
+    /// Replaces it with the reference value.
+    Replace,
+    /// Increments it, clamping at the maximum value.
+    Increment,
+    /// Increments it, wrapping to 0 past the maximum value.
+    IncrementWrap,
+    /// Decrements it, clamping at 0.
+    Decrement,
+    /// Decrements it, wrapping to the maximum value past 0.
+    DecrementWrap,
+    Invert,
+}
+
+impl StencilOp {
+    pub fn value(&self) -> u32 {
+        match *self {
+            StencilOp::Keep => raw_gl::KEEP,
+            StencilOp::Zero => raw_gl::ZERO,
+            StencilOp::Replace => raw_gl::REPLACE,
+            StencilOp::Increment => raw_gl::INCR,
+            StencilOp::IncrementWrap => raw_gl::INCR_WRAP,
+            StencilOp::Decrement => raw_gl::DECR,
+            StencilOp::DecrementWrap => raw_gl::DECR_WRAP,
+            StencilOp::Invert => raw_gl::INVERT,
+        }
+    }
+}
+
+/// Fragments pass the stencil test if `(reference & mask) <func> (stored &
+/// mask)` holds.
+#[inline]
+pub fn set_stencil_func(func: CompareFunc, reference: i32, mask: u32) {
+    unsafe { gl_call!(StencilFunc(func.value(), reference, mask)) };
+}
+
+/// The operations for when the stencil test fails, when it passes but the
+/// depth test fails, and when both pass.
+#[inline]
+pub fn set_stencil_op(stencil_fail: StencilOp, depth_fail: StencilOp, pass: StencilOp) {
+    unsafe {
+        gl_call!(StencilOp(
+            stencil_fail.value(),
+            depth_fail.value(),
+            pass.value()
+        ))
+    };
+}
+
+/// The bits of the stencil buffer that can be written. Also applies to
+/// `gl::clear()`.
+#[inline]
+pub fn set_stencil_mask(mask: u32) {
+    unsafe { gl_call!(StencilMask(mask)) };
+}
+
+/// The rectangle fragments are limited to while `Capability::ScissorTest`
+/// is enabled, in window coordinates.
+#[inline]
+pub fn set_scissor(x: u32, y: u32, width: u32, height: u32) {
+    unsafe { gl_call!(Scissor(x as i32, y as i32, width as i32, height as i32)) };
+}
+
+/// How polygons are rasterized.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PolygonMode {
+    /// Only the vertices.
+    Point,
+    /// Only the edges, for wireframes.
+    Line,
+    Fill,
+}
+
+impl PolygonMode {
+    pub fn value(&self) -> u32 {
+        match *self {
+            PolygonMode::Point => raw_gl::POINT,
+            PolygonMode::Line => raw_gl::LINE,
+            PolygonMode::Fill => raw_gl::FILL,
+        }
+    }
+}
+
+/// Sets the polygon mode of both front and back faces, as the core profile
+/// requires.
+#[inline]
+pub fn set_polygon_mode(mode: PolygonMode) {
+    unsafe { gl_call!(PolygonMode(raw_gl::FRONT_AND_BACK, mode.value())) };
+}
+
+/// Which color channels can be written. Also applies to `gl::clear()`.
+#[inline]
+pub fn set_color_mask(red: bool, green: bool, blue: bool, alpha: bool) {
+    unsafe {
+        gl_call!(ColorMask(
+            red as GLboolean,
+            green as GLboolean,
+            blue as GLboolean,
+            alpha as GLboolean,
+        ))
+    };
+}
+
+/// Where a debug message came from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DebugSource {
+    Api,
+    WindowSystem,
+    ShaderCompiler,
+    ThirdParty,
+    Application,
+    Other,
+}
+
+impl DebugSource {
+    fn from_gl(source: GLenum) -> DebugSource {
+        match source {
+            raw_gl::DEBUG_SOURCE_API => DebugSource::Api,
+            raw_gl::DEBUG_SOURCE_WINDOW_SYSTEM => DebugSource::WindowSystem,
+            raw_gl::DEBUG_SOURCE_SHADER_COMPILER => DebugSource::ShaderCompiler,
+            raw_gl::DEBUG_SOURCE_THIRD_PARTY => DebugSource::ThirdParty,
+            raw_gl::DEBUG_SOURCE_APPLICATION => DebugSource::Application,
+            _ => DebugSource::Other,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DebugType {
+    Error,
+    DeprecatedBehavior,
+    UndefinedBehavior,
+    Portability,
+    Performance,
+    Marker,
+    PushGroup,
+    PopGroup,
+    Other,
+}
+
+impl DebugType {
+    fn from_gl(message_type: GLenum) -> DebugType {
+        match message_type {
+            raw_gl::DEBUG_TYPE_ERROR => DebugType::Error,
+            raw_gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => DebugType::DeprecatedBehavior,
+            raw_gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => DebugType::UndefinedBehavior,
+            raw_gl::DEBUG_TYPE_PORTABILITY => DebugType::Portability,
+            raw_gl::DEBUG_TYPE_PERFORMANCE => DebugType::Performance,
+            raw_gl::DEBUG_TYPE_MARKER => DebugType::Marker,
+            raw_gl::DEBUG_TYPE_PUSH_GROUP => DebugType::PushGroup,
+            raw_gl::DEBUG_TYPE_POP_GROUP => DebugType::PopGroup,
+            _ => DebugType::Other,
+        }
+    }
+}
+
+/// Ordered from least to most severe, so handlers can filter with
+/// `message.severity >= DebugSeverity::Medium`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DebugSeverity {
+    Notification,
+    Low,
+    Medium,
+    High,
+}
+
+impl DebugSeverity {
+    fn from_gl(severity: GLenum) -> DebugSeverity {
+        match severity {
+            raw_gl::DEBUG_SEVERITY_HIGH => DebugSeverity::High,
+            raw_gl::DEBUG_SEVERITY_MEDIUM => DebugSeverity::Medium,
+            raw_gl::DEBUG_SEVERITY_LOW => DebugSeverity::Low,
+            _ => DebugSeverity::Notification,
+        }
+    }
+}
+
+/// A message from the driver's debug output.
+#[derive(Debug, Copy, Clone)]
+pub struct DebugMessage<'a> {
+    pub source: DebugSource,
+    pub message_type: DebugType,
+    /// Identifies the message within its source and type. Specific to the
+    /// driver.
+    pub id: u32,
+    pub severity: DebugSeverity,
+    pub text: &'a str,
+}
+
+type DebugMessageHandler = Box<dyn FnMut(&DebugMessage<'_>)>;
+
+thread_local! {
+    static DEBUG_MESSAGE_HANDLER: RefCell<Option<DebugMessageHandler>> = const { RefCell::new(None) };
+}
+
+/// Routes messages from the current context's debug output (OpenGL 4.3 or
+/// KHR_debug) to the debug message handler, which logs them by default.
+/// Output is made synchronous, so the handler runs on the calling thread
+/// inside the OpenGL call that caused the message.
+///
+/// Windows created with `WindowBuilder::debug_context` enable this for
+/// their context automatically.
+///
+/// Returns false if the context doesn't support debug output.
+pub fn enable_debug_output() -> bool {
+    if !raw_gl::DebugMessageCallback::is_loaded() {
+        return false;
+    }
+
+    unsafe {
+        gl_call!(Enable(raw_gl::DEBUG_OUTPUT));
+        gl_call!(Enable(raw_gl::DEBUG_OUTPUT_SYNCHRONOUS));
+        gl_call!(DebugMessageCallback(
+            Some(debug_message_callback),
+            std::ptr::null()
+        ));
+    }
+    true
+}
+
+/// Stops the current context from reporting debug messages.
+pub fn disable_debug_output() {
+    if raw_gl::DebugMessageCallback::is_loaded() {
+        unsafe {
+            gl_call!(DebugMessageCallback(None, std::ptr::null()));
+            gl_call!(Disable(raw_gl::DEBUG_OUTPUT));
+        }
+    }
+}
+
+/// Replaces what happens with debug messages reported on the calling thread.
+///
+/// The handler runs inside a callback from the driver, which a panic can't
+/// unwind through. Panics are caught and logged instead, so record messages
+/// to act on them after the OpenGL call returns:
+///
+/// ```ignore
+/// let errors = Rc::new(RefCell::new(Vec::new()));
+/// let sink = errors.clone();
+/// gl::set_debug_message_handler(move |message| {
+///     if message.severity >= DebugSeverity::Medium {
+///         sink.borrow_mut().push(message.text.to_owned());
+///     }
+/// });
+/// ```
+pub fn set_debug_message_handler<F>(handler: F)
+where
+    F: FnMut(&DebugMessage<'_>) + 'static,
+{
+    DEBUG_MESSAGE_HANDLER.with(|current| *current.borrow_mut() = Some(Box::new(handler)));
+}
+
+/// Goes back to logging debug messages, at a level matching their severity.
+pub fn reset_debug_message_handler() {
+    DEBUG_MESSAGE_HANDLER.with(|current| *current.borrow_mut() = None);
+}
+
+fn log_debug_message(message: &DebugMessage<'_>) {
+    let level = match message.severity {
+        DebugSeverity::High => log::Level::Error,
+        DebugSeverity::Medium => log::Level::Warn,
+        DebugSeverity::Low => log::Level::Info,
+        DebugSeverity::Notification => log::Level::Debug,
+    };
+    log::log!(
+        level,
+        "OpenGL {:?} {:?} ({}): {}",
+        message.source,
+        message.message_type,
+        message.id,
+        message.text
+    );
+}
+
+extern "system" fn debug_message_callback(
+    source: GLenum,
+    message_type: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    length: GLsizei,
+    text: *const GLchar,
+    _: *mut GLvoid,
+) {
+    let bytes = unsafe {
+        if length < 0 {
+            std::ffi::CStr::from_ptr(text).to_bytes()
+        } else {
+            std::slice::from_raw_parts(text as *const u8, length as usize)
+        }
+    };
+    let text = String::from_utf8_lossy(bytes);
+    let message = DebugMessage {
+        source: DebugSource::from_gl(source),
+        message_type: DebugType::from_gl(message_type),
+        id,
+        severity: DebugSeverity::from_gl(severity),
+        text: &text,
+    };
+
+    // Messages caused by the handler's own OpenGL calls are only logged.
+    DEBUG_MESSAGE_HANDLER.with(|handler| match handler.try_borrow_mut() {
+        Ok(mut handler) => match handler.as_mut() {
+            Some(handler) => {
+                let handled =
+                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| handler(&message)));
+                if handled.is_err() {
+                    log::error!("the debug message handler panicked on: {}", message.text);
+                }
+            }
+            None => log_debug_message(&message),
+        },
+        Err(_) => log_debug_message(&message),
+    });
+}
@@ -0,0 +1,122 @@
+//! Tracking of the objects bound to each context, so that binding an object
+//! that is already bound issues no OpenGL call.
+
+use crate::graphics::ContextId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Counts of the binds made through `graphics::gl` on the current context,
+/// for profiling.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BindStats {
+    /// Binds that changed a binding and called OpenGL.
+    pub issued: u64,
+    /// Binds of what was already bound, which were skipped.
+    pub skipped: u64,
+}
+
+/// The binds made on the current context since `reset_bind_stats` was last
+/// called.
+pub fn bind_stats() -> BindStats {
+    with_cache(|cache| cache.stats)
+}
+
+pub fn reset_bind_stats() {
+    with_cache(|cache| cache.stats = BindStats::default());
+}
+
+/// Forgets what is bound to the current context, so that the next binds are
+/// issued whatever they bind. Call it after binding objects through
+/// `raw_gl` or another library.
+pub fn invalidate_bindings() {
+    with_cache(|cache| cache.bound.clear());
+}
+
+/// Forgets a destroyed context.
+pub(crate) fn release_bindings(context: ContextId) {
+    CACHES.with(|caches| caches.borrow_mut().remove(&Some(context)));
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(super) enum Slot {
+    Program,
+    VertexArray,
+    /// A buffer target.
+    Buffer(u32),
+    ActiveTexture,
+    /// A texture target of a texture unit.
+    Texture {
+        unit: u32,
+        target: u32,
+    },
+}
+
+#[derive(Default)]
+struct Cache {
+    /// The object bound to each slot, where it is known.
+    bound: HashMap<Slot, u32>,
+    stats: BindStats,
+}
+
+thread_local! {
+    /// The bindings of each context on this thread, keyed by None when no
+    /// context is current.
+    static CACHES: RefCell<HashMap<Option<ContextId>, Cache>> = RefCell::new(HashMap::new());
+}
+
+fn with_cache<R>(f: impl FnOnce(&mut Cache) -> R) -> R {
+    CACHES.with(|caches| f(caches.borrow_mut().entry(ContextId::current()).or_default()))
+}
+
+/// Records `id` as bound to `slot`, returning whether that changed the
+/// binding, in which case the bind has to be issued.
+pub(super) fn bind(slot: Slot, id: u32) -> bool {
+    with_cache(|cache| {
+        if cache.bound.insert(slot, id) == Some(id) {
+            cache.stats.skipped += 1;
+            return false;
+        }
+        cache.stats.issued += 1;
+        if slot == Slot::VertexArray {
+            // The element array buffer binding belongs to the vertex array.
+            cache
+                .bound
+                .remove(&Slot::Buffer(raw_gl::ELEMENT_ARRAY_BUFFER));
+        }
+        true
+    })
+}
+
+/// Like `bind`, for a texture target of the active texture unit. Always
+/// issued while the active unit is unknown.
+pub(super) fn bind_texture(target: u32, id: u32) -> bool {
+    let unit = with_cache(|cache| cache.bound.get(&Slot::ActiveTexture).copied());
+    match unit {
+        Some(unit) => bind(Slot::Texture { unit, target }, id),
+        None => {
+            with_cache(|cache| cache.stats.issued += 1);
+            true
+        }
+    }
+}
+
+/// Forgets the slots `id` is bound to once it is deleted, as OpenGL may hand
+/// the name out again. Every context is affected, as contexts sharing
+/// objects share their names.
+pub(super) fn forget(matches: impl Fn(&Slot) -> bool, id: u32) {
+    CACHES.with(|caches| {
+        for cache in caches.borrow_mut().values_mut() {
+            let vertex_array_known = cache.bound.contains_key(&Slot::VertexArray);
+            cache
+                .bound
+                .retain(|slot, &mut bound| bound != id || !matches(slot));
+            if vertex_array_known && !cache.bound.contains_key(&Slot::VertexArray) {
+                // The vertex array was deleted while bound, taking its element
+                // array buffer binding with it.
+                cache
+                    .bound
+                    .remove(&Slot::Buffer(raw_gl::ELEMENT_ARRAY_BUFFER));
+            }
+        }
+    });
+}
@@ -0,0 +1,964 @@
+//! A stand-in for OpenGL that records every call made through `graphics::gl`
+//! instead of executing it, enabled by the `gl-mock` feature.
+//!
+//! Object names are handed out like a driver would, and the mock keeps track
+//! of which objects are alive. Queries answer as if everything succeeded:
+//! shaders compile, programs link, framebuffers are complete and uniforms
+//! exist. Nothing needs a context, so graphics code can be tested without a
+//! window:
+//!
+//! ```ignore
+//! let buffer = Buffer::array(&[0.0; 6], 3);
+//! let id = mock::calls_to("GenBuffers")[0].ids(1)[0];
+//! drop(buffer);
+//! assert!(!mock::is_live(ObjectKind::Buffer, id));
+//! ```
+//!
+//! The log and objects are kept per thread, so tests running in parallel
+//! don't see each other's calls.
+
+#![allow(non_snake_case)]
+#![allow(clippy::too_many_arguments)]
+
+use raw_gl::types::*;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::ffi::{c_void, CStr};
+
+/// An argument of a recorded call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    /// Enums, integers and booleans.
+    Int(i64),
+    Float(f32),
+    /// Object names passed in or, for `Gen*` calls, generated.
+    Ids(Vec<u32>),
+    /// Shader sources and uniform names.
+    Str(String),
+    /// Data uploaded with `BufferSubData`, or written to a mapped buffer
+    /// range by the time of `UnmapBuffer`.
+    Bytes(Vec<u8>),
+    /// Any other pointer, by address.
+    Pointer(usize),
+}
+
+/// A call to an OpenGL function, named without its `gl` prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub function: &'static str,
+    pub args: Vec<Arg>,
+}
+
+impl Call {
+    /// The integer argument at `index`. Panics if it is not one.
+    pub fn int(&self, index: usize) -> i64 {
+        match &self.args[index] {
+            Arg::Int(value) => *value,
+            arg => panic!("argument {} of {} is {:?}", index, self.function, arg),
+        }
+    }
+
+    /// The float argument at `index`. Panics if it is not one.
+    pub fn float(&self, index: usize) -> f32 {
+        match &self.args[index] {
+            Arg::Float(value) => *value,
+            arg => panic!("argument {} of {} is {:?}", index, self.function, arg),
+        }
+    }
+
+    /// The object names argument at `index`. Panics if it is not one.
+    pub fn ids(&self, index: usize) -> &[u32] {
+        match &self.args[index] {
+            Arg::Ids(ids) => ids,
+            arg => panic!("argument {} of {} is {:?}", index, self.function, arg),
+        }
+    }
+
+    /// The string argument at `index`. Panics if it is not one.
+    pub fn str(&self, index: usize) -> &str {
+        match &self.args[index] {
+            Arg::Str(value) => value,
+            arg => panic!("argument {} of {} is {:?}", index, self.function, arg),
+        }
+    }
+
+    /// The data argument at `index`. Panics if it is not one.
+    pub fn bytes(&self, index: usize) -> &[u8] {
+        match &self.args[index] {
+            Arg::Bytes(bytes) => bytes,
+            arg => panic!("argument {} of {} is {:?}", index, self.function, arg),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ObjectKind {
+    Buffer,
+    VertexArray,
+    Texture,
+    Framebuffer,
+    Renderbuffer,
+    Shader,
+    Program,
+}
+
+#[derive(Default)]
+struct State {
+    calls: Vec<Call>,
+    next_id: u32,
+    live: HashMap<ObjectKind, BTreeSet<u32>>,
+    uniforms: HashMap<(u32, String), i32>,
+    errors: VecDeque<GLenum>,
+    /// The buffer bound to each target.
+    bound_buffers: HashMap<GLenum, GLuint>,
+    /// The memory handed out by `MapBufferRange` for each mapped buffer,
+    /// aligned for any element type, and its length in bytes.
+    mapped: HashMap<GLuint, (Vec<u64>, usize)>,
+}
+
+impl State {
+    fn create(&mut self, kind: ObjectKind) -> u32 {
+        self.next_id += 1;
+        self.live.entry(kind).or_default().insert(self.next_id);
+        self.next_id
+    }
+
+    fn delete(&mut self, kind: ObjectKind, id: u32) {
+        if let Some(live) = self.live.get_mut(&kind) {
+            live.remove(&id);
+        }
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+fn with_state<R>(f: impl FnOnce(&mut State) -> R) -> R {
+    STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+fn record(function: &'static str, args: Vec<Arg>) {
+    with_state(|state| state.calls.push(Call { function, args }));
+}
+
+/// Every call recorded on this thread since the last `reset` or `take_calls`.
+pub fn calls() -> Vec<Call> {
+    with_state(|state| state.calls.clone())
+}
+
+/// Returns the recorded calls and clears the log.
+pub fn take_calls() -> Vec<Call> {
+    with_state(|state| std::mem::take(&mut state.calls))
+}
+
+/// The recorded calls to `function`, named without its `gl` prefix.
+pub fn calls_to(function: &str) -> Vec<Call> {
+    with_state(|state| {
+        state
+            .calls
+            .iter()
+            .filter(|call| call.function == function)
+            .cloned()
+            .collect()
+    })
+}
+
+/// Whether `function` has been called.
+pub fn called(function: &str) -> bool {
+    with_state(|state| state.calls.iter().any(|call| call.function == function))
+}
+
+/// Whether an object has been created and not deleted yet.
+pub fn is_live(kind: ObjectKind, id: u32) -> bool {
+    with_state(|state| state.live.get(&kind).is_some_and(|live| live.contains(&id)))
+}
+
+/// The names of the objects of a kind that are alive, in creation order.
+pub fn live_objects(kind: ObjectKind) -> Vec<u32> {
+    with_state(|state| {
+        state
+            .live
+            .get(&kind)
+            .map_or_else(Vec::new, |live| live.iter().copied().collect())
+    })
+}
+
+/// Makes the next `glGetError` report `code`, e.g. `raw_gl::INVALID_VALUE`.
+pub fn push_error(code: GLenum) {
+    with_state(|state| state.errors.push_back(code));
+}
+
+/// Clears the log, forgets every object and restarts object names at 1.
+/// Also forgets what `graphics::gl` thinks is bound, as the names are reused.
+pub fn reset() {
+    with_state(|state| *state = State::default());
+    super::invalidate_bindings();
+}
+
+fn int(value: impl Into<i64>) -> Arg {
+    Arg::Int(value.into())
+}
+
+fn pointer<T>(pointer: *const T) -> Arg {
+    Arg::Pointer(pointer as usize)
+}
+
+unsafe fn gen(function: &'static str, kind: ObjectKind, n: GLsizei, ids: *mut GLuint) {
+    let ids = std::slice::from_raw_parts_mut(ids, n as usize);
+    with_state(|state| ids.iter_mut().for_each(|id| *id = state.create(kind)));
+    record(function, vec![int(n), Arg::Ids(ids.to_vec())]);
+}
+
+unsafe fn delete(function: &'static str, kind: ObjectKind, n: GLsizei, ids: *const GLuint) {
+    let ids = std::slice::from_raw_parts(ids, n as usize);
+    with_state(|state| ids.iter().for_each(|&id| state.delete(kind, id)));
+    record(function, vec![int(n), Arg::Ids(ids.to_vec())]);
+}
+
+pub(super) unsafe fn GetError() -> GLenum {
+    with_state(|state| state.errors.pop_front().unwrap_or(raw_gl::NO_ERROR))
+}
+
+pub(super) unsafe fn ActiveTexture(texture: GLenum) {
+    record("ActiveTexture", vec![int(texture)]);
+}
+
+pub(super) unsafe fn AttachShader(program: GLuint, shader: GLuint) {
+    record("AttachShader", vec![int(program), int(shader)]);
+}
+
+pub(super) unsafe fn BindBuffer(target: GLenum, buffer: GLuint) {
+    with_state(|state| state.bound_buffers.insert(target, buffer));
+    record("BindBuffer", vec![int(target), int(buffer)]);
+}
+
+pub(super) unsafe fn BindFramebuffer(target: GLenum, framebuffer: GLuint) {
+    record("BindFramebuffer", vec![int(target), int(framebuffer)]);
+}
+
+pub(super) unsafe fn BindRenderbuffer(target: GLenum, renderbuffer: GLuint) {
+    record("BindRenderbuffer", vec![int(target), int(renderbuffer)]);
+}
+
+pub(super) unsafe fn BindTexture(target: GLenum, texture: GLuint) {
+    record("BindTexture", vec![int(target), int(texture)]);
+}
+
+pub(super) unsafe fn BindVertexArray(array: GLuint) {
+    record("BindVertexArray", vec![int(array)]);
+}
+
+pub(super) unsafe fn BlendColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+    record(
+        "BlendColor",
+        vec![
+            Arg::Float(red),
+            Arg::Float(green),
+            Arg::Float(blue),
+            Arg::Float(alpha),
+        ],
+    );
+}
+
+pub(super) unsafe fn BlendEquationSeparate(mode_rgb: GLenum, mode_alpha: GLenum) {
+    record(
+        "BlendEquationSeparate",
+        vec![int(mode_rgb), int(mode_alpha)],
+    );
+}
+
+pub(super) unsafe fn BlendFuncSeparate(
+    src_rgb: GLenum,
+    dst_rgb: GLenum,
+    src_alpha: GLenum,
+    dst_alpha: GLenum,
+) {
+    record(
+        "BlendFuncSeparate",
+        vec![int(src_rgb), int(dst_rgb), int(src_alpha), int(dst_alpha)],
+    );
+}
+
+pub(super) unsafe fn BlitFramebuffer(
+    src_x0: GLint,
+    src_y0: GLint,
+    src_x1: GLint,
+    src_y1: GLint,
+    dst_x0: GLint,
+    dst_y0: GLint,
+    dst_x1: GLint,
+    dst_y1: GLint,
+    mask: GLbitfield,
+    filter: GLenum,
+) {
+    record(
+        "BlitFramebuffer",
+        vec![
+            int(src_x0),
+            int(src_y0),
+            int(src_x1),
+            int(src_y1),
+            int(dst_x0),
+            int(dst_y0),
+            int(dst_x1),
+            int(dst_y1),
+            int(mask),
+            int(filter),
+        ],
+    );
+}
+
+pub(super) unsafe fn BufferData(
+    target: GLenum,
+    size: GLsizeiptr,
+    data: *const c_void,
+    usage: GLenum,
+) {
+    record(
+        "BufferData",
+        vec![int(target), int(size as i64), pointer(data), int(usage)],
+    );
+}
+
+pub(super) unsafe fn BufferSubData(
+    target: GLenum,
+    offset: GLintptr,
+    size: GLsizeiptr,
+    data: *const c_void,
+) {
+    let data = std::slice::from_raw_parts(data as *const u8, size as usize);
+    record(
+        "BufferSubData",
+        vec![
+            int(target),
+            int(offset as i64),
+            int(size as i64),
+            Arg::Bytes(data.to_vec()),
+        ],
+    );
+}
+
+pub(super) unsafe fn CheckFramebufferStatus(target: GLenum) -> GLenum {
+    record("CheckFramebufferStatus", vec![int(target)]);
+    raw_gl::FRAMEBUFFER_COMPLETE
+}
+
+pub(super) unsafe fn Clear(mask: GLbitfield) {
+    record("Clear", vec![int(mask)]);
+}
+
+pub(super) unsafe fn ClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+    record(
+        "ClearColor",
+        vec![
+            Arg::Float(red),
+            Arg::Float(green),
+            Arg::Float(blue),
+            Arg::Float(alpha),
+        ],
+    );
+}
+
+pub(super) unsafe fn ColorMask(
+    red: GLboolean,
+    green: GLboolean,
+    blue: GLboolean,
+    alpha: GLboolean,
+) {
+    record(
+        "ColorMask",
+        vec![int(red), int(green), int(blue), int(alpha)],
+    );
+}
+
+pub(super) unsafe fn CompileShader(shader: GLuint) {
+    record("CompileShader", vec![int(shader)]);
+}
+
+pub(super) unsafe fn CreateProgram() -> GLuint {
+    let id = with_state(|state| state.create(ObjectKind::Program));
+    record("CreateProgram", vec![Arg::Ids(vec![id])]);
+    id
+}
+
+pub(super) unsafe fn CreateShader(shader_type: GLenum) -> GLuint {
+    let id = with_state(|state| state.create(ObjectKind::Shader));
+    record("CreateShader", vec![int(shader_type), Arg::Ids(vec![id])]);
+    id
+}
+
+pub(super) unsafe fn CullFace(mode: GLenum) {
+    record("CullFace", vec![int(mode)]);
+}
+
+pub(super) unsafe fn DebugMessageCallback(callback: GLDEBUGPROC, user_param: *const c_void) {
+    record(
+        "DebugMessageCallback",
+        vec![int(callback.is_some()), pointer(user_param)],
+    );
+}
+
+/// Also unmaps and unbinds the deleted buffers.
+pub(super) unsafe fn DeleteBuffers(n: GLsizei, buffers: *const GLuint) {
+    let deleted = std::slice::from_raw_parts(buffers, n as usize);
+    with_state(|state| {
+        for buffer in deleted {
+            state.mapped.remove(buffer);
+        }
+        state
+            .bound_buffers
+            .retain(|_, bound| !deleted.contains(bound));
+    });
+    delete("DeleteBuffers", ObjectKind::Buffer, n, buffers);
+}
+
+pub(super) unsafe fn DeleteFramebuffers(n: GLsizei, framebuffers: *const GLuint) {
+    delete(
+        "DeleteFramebuffers",
+        ObjectKind::Framebuffer,
+        n,
+        framebuffers,
+    );
+}
+
+pub(super) unsafe fn DeleteProgram(program: GLuint) {
+    with_state(|state| state.delete(ObjectKind::Program, program));
+    record("DeleteProgram", vec![int(program)]);
+}
+
+pub(super) unsafe fn DeleteRenderbuffers(n: GLsizei, renderbuffers: *const GLuint) {
+    delete(
+        "DeleteRenderbuffers",
+        ObjectKind::Renderbuffer,
+        n,
+        renderbuffers,
+    );
+}
+
+pub(super) unsafe fn DeleteShader(shader: GLuint) {
+    with_state(|state| state.delete(ObjectKind::Shader, shader));
+    record("DeleteShader", vec![int(shader)]);
+}
+
+pub(super) unsafe fn DeleteTextures(n: GLsizei, textures: *const GLuint) {
+    delete("DeleteTextures", ObjectKind::Texture, n, textures);
+}
+
+pub(super) unsafe fn DeleteVertexArrays(n: GLsizei, arrays: *const GLuint) {
+    delete("DeleteVertexArrays", ObjectKind::VertexArray, n, arrays);
+}
+
+pub(super) unsafe fn DepthFunc(func: GLenum) {
+    record("DepthFunc", vec![int(func)]);
+}
+
+pub(super) unsafe fn DepthMask(flag: GLboolean) {
+    record("DepthMask", vec![int(flag)]);
+}
+
+pub(super) unsafe fn DetachShader(program: GLuint, shader: GLuint) {
+    record("DetachShader", vec![int(program), int(shader)]);
+}
+
+pub(super) unsafe fn Disable(cap: GLenum) {
+    record("Disable", vec![int(cap)]);
+}
+
+pub(super) unsafe fn DisableVertexAttribArray(index: GLuint) {
+    record("DisableVertexAttribArray", vec![int(index)]);
+}
+
+pub(super) unsafe fn DrawArrays(mode: GLenum, first: GLint, count: GLsizei) {
+    record("DrawArrays", vec![int(mode), int(first), int(count)]);
+}
+
+pub(super) unsafe fn DrawArraysInstanced(
+    mode: GLenum,
+    first: GLint,
+    count: GLsizei,
+    instance_count: GLsizei,
+) {
+    record(
+        "DrawArraysInstanced",
+        vec![int(mode), int(first), int(count), int(instance_count)],
+    );
+}
+
+pub(super) unsafe fn DrawBuffers(n: GLsizei, buffers: *const GLenum) {
+    let buffers = std::slice::from_raw_parts(buffers, n as usize);
+    record("DrawBuffers", vec![int(n), Arg::Ids(buffers.to_vec())]);
+}
+
+pub(super) unsafe fn DrawElements(
+    mode: GLenum,
+    count: GLsizei,
+    index_type: GLenum,
+    indices: *const c_void,
+) {
+    record(
+        "DrawElements",
+        vec![int(mode), int(count), int(index_type), pointer(indices)],
+    );
+}
+
+pub(super) unsafe fn DrawElementsInstanced(
+    mode: GLenum,
+    count: GLsizei,
+    index_type: GLenum,
+    indices: *const c_void,
+    instance_count: GLsizei,
+) {
+    record(
+        "DrawElementsInstanced",
+        vec![
+            int(mode),
+            int(count),
+            int(index_type),
+            pointer(indices),
+            int(instance_count),
+        ],
+    );
+}
+
+pub(super) unsafe fn Enable(cap: GLenum) {
+    record("Enable", vec![int(cap)]);
+}
+
+pub(super) unsafe fn EnableVertexAttribArray(index: GLuint) {
+    record("EnableVertexAttribArray", vec![int(index)]);
+}
+
+pub(super) unsafe fn FlushMappedBufferRange(target: GLenum, offset: GLintptr, length: GLsizeiptr) {
+    record(
+        "FlushMappedBufferRange",
+        vec![int(target), int(offset as i64), int(length as i64)],
+    );
+}
+
+pub(super) unsafe fn FramebufferRenderbuffer(
+    target: GLenum,
+    attachment: GLenum,
+    renderbuffer_target: GLenum,
+    renderbuffer: GLuint,
+) {
+    record(
+        "FramebufferRenderbuffer",
+        vec![
+            int(target),
+            int(attachment),
+            int(renderbuffer_target),
+            int(renderbuffer),
+        ],
+    );
+}
+
+pub(super) unsafe fn FramebufferTexture2D(
+    target: GLenum,
+    attachment: GLenum,
+    texture_target: GLenum,
+    texture: GLuint,
+    level: GLint,
+) {
+    record(
+        "FramebufferTexture2D",
+        vec![
+            int(target),
+            int(attachment),
+            int(texture_target),
+            int(texture),
+            int(level),
+        ],
+    );
+}
+
+pub(super) unsafe fn FrontFace(mode: GLenum) {
+    record("FrontFace", vec![int(mode)]);
+}
+
+pub(super) unsafe fn GenBuffers(n: GLsizei, buffers: *mut GLuint) {
+    gen("GenBuffers", ObjectKind::Buffer, n, buffers);
+}
+
+pub(super) unsafe fn GenFramebuffers(n: GLsizei, framebuffers: *mut GLuint) {
+    gen("GenFramebuffers", ObjectKind::Framebuffer, n, framebuffers);
+}
+
+pub(super) unsafe fn GenRenderbuffers(n: GLsizei, renderbuffers: *mut GLuint) {
+    gen(
+        "GenRenderbuffers",
+        ObjectKind::Renderbuffer,
+        n,
+        renderbuffers,
+    );
+}
+
+pub(super) unsafe fn GenTextures(n: GLsizei, textures: *mut GLuint) {
+    gen("GenTextures", ObjectKind::Texture, n, textures);
+}
+
+pub(super) unsafe fn GenVertexArrays(n: GLsizei, arrays: *mut GLuint) {
+    gen("GenVertexArrays", ObjectKind::VertexArray, n, arrays);
+}
+
+pub(super) unsafe fn GenerateMipmap(target: GLenum) {
+    record("GenerateMipmap", vec![int(target)]);
+}
+
+/// Reports 8 color attachments, and 0 for anything else.
+pub(super) unsafe fn GetIntegerv(name: GLenum, data: *mut GLint) {
+    *data = match name {
+        raw_gl::MAX_COLOR_ATTACHMENTS => 8,
+        _ => 0,
+    };
+    record("GetIntegerv", vec![int(name)]);
+}
+
+pub(super) unsafe fn GetProgramInfoLog(
+    program: GLuint,
+    buffer_size: GLsizei,
+    _length: *mut GLsizei,
+    _info_log: *mut GLchar,
+) {
+    record("GetProgramInfoLog", vec![int(program), int(buffer_size)]);
+}
+
+/// Reports successful links with empty info logs.
+pub(super) unsafe fn GetProgramiv(program: GLuint, name: GLenum, params: *mut GLint) {
+    *params = match name {
+        raw_gl::LINK_STATUS => raw_gl::TRUE as GLint,
+        _ => 0,
+    };
+    record("GetProgramiv", vec![int(program), int(name)]);
+}
+
+pub(super) unsafe fn GetShaderInfoLog(
+    shader: GLuint,
+    buffer_size: GLsizei,
+    _length: *mut GLsizei,
+    _info_log: *mut GLchar,
+) {
+    record("GetShaderInfoLog", vec![int(shader), int(buffer_size)]);
+}
+
+/// Reports successful compiles with empty info logs.
+pub(super) unsafe fn GetShaderiv(shader: GLuint, name: GLenum, params: *mut GLint) {
+    *params = match name {
+        raw_gl::COMPILE_STATUS => raw_gl::TRUE as GLint,
+        _ => 0,
+    };
+    record("GetShaderiv", vec![int(shader), int(name)]);
+}
+
+/// Every name is an active uniform, located in the order it was first asked
+/// for in its program.
+pub(super) unsafe fn GetUniformLocation(program: GLuint, name: *const GLchar) -> GLint {
+    let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+    let location = with_state(|state| {
+        let next = state
+            .uniforms
+            .keys()
+            .filter(|(uniform_program, _)| *uniform_program == program)
+            .count() as GLint;
+        *state
+            .uniforms
+            .entry((program, name.clone()))
+            .or_insert(next)
+    });
+    record("GetUniformLocation", vec![int(program), Arg::Str(name)]);
+    location
+}
+
+pub(super) unsafe fn LinkProgram(program: GLuint) {
+    record("LinkProgram", vec![int(program)]);
+}
+
+/// Hands out new zeroed memory of the requested length. Like OpenGL, reports
+/// `GL_INVALID_OPERATION` and returns null if no buffer is bound to `target`
+/// or the bound one is already mapped.
+pub(super) unsafe fn MapBufferRange(
+    target: GLenum,
+    offset: GLintptr,
+    length: GLsizeiptr,
+    access: GLbitfield,
+) -> *mut c_void {
+    record(
+        "MapBufferRange",
+        vec![
+            int(target),
+            int(offset as i64),
+            int(length as i64),
+            int(access),
+        ],
+    );
+    let length = length as usize;
+    with_state(|state| {
+        let buffer = state.bound_buffers.get(&target).copied().unwrap_or(0);
+        if buffer == 0 || state.mapped.contains_key(&buffer) {
+            state.errors.push_back(raw_gl::INVALID_OPERATION);
+            return std::ptr::null_mut();
+        }
+        let (memory, _) = state
+            .mapped
+            .entry(buffer)
+            .or_insert((vec![0; length.div_ceil(8)], length));
+        memory.as_mut_ptr() as *mut c_void
+    })
+}
+
+pub(super) unsafe fn PixelStorei(name: GLenum, param: GLint) {
+    record("PixelStorei", vec![int(name), int(param)]);
+}
+
+pub(super) unsafe fn PolygonMode(face: GLenum, mode: GLenum) {
+    record("PolygonMode", vec![int(face), int(mode)]);
+}
+
+pub(super) unsafe fn ReadBuffer(source: GLenum) {
+    record("ReadBuffer", vec![int(source)]);
+}
+
+/// Leaves `pixels` untouched.
+pub(super) unsafe fn ReadPixels(
+    x: GLint,
+    y: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    data_type: GLenum,
+    pixels: *mut c_void,
+) {
+    record(
+        "ReadPixels",
+        vec![
+            int(x),
+            int(y),
+            int(width),
+            int(height),
+            int(format),
+            int(data_type),
+            pointer(pixels),
+        ],
+    );
+}
+
+pub(super) unsafe fn RenderbufferStorage(
+    target: GLenum,
+    internal_format: GLenum,
+    width: GLsizei,
+    height: GLsizei,
+) {
+    record(
+        "RenderbufferStorage",
+        vec![int(target), int(internal_format), int(width), int(height)],
+    );
+}
+
+pub(super) unsafe fn Scissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    record("Scissor", vec![int(x), int(y), int(width), int(height)]);
+}
+
+/// Records the sources joined together.
+pub(super) unsafe fn ShaderSource(
+    shader: GLuint,
+    count: GLsizei,
+    string: *const *const GLchar,
+    _length: *const GLint,
+) {
+    let source = std::slice::from_raw_parts(string, count as usize)
+        .iter()
+        .map(|&source| CStr::from_ptr(source).to_string_lossy())
+        .collect::<String>();
+    record(
+        "ShaderSource",
+        vec![int(shader), int(count), Arg::Str(source)],
+    );
+}
+
+pub(super) unsafe fn StencilFunc(func: GLenum, reference: GLint, mask: GLuint) {
+    record("StencilFunc", vec![int(func), int(reference), int(mask)]);
+}
+
+pub(super) unsafe fn StencilMask(mask: GLuint) {
+    record("StencilMask", vec![int(mask)]);
+}
+
+pub(super) unsafe fn StencilOp(stencil_fail: GLenum, depth_fail: GLenum, pass: GLenum) {
+    record(
+        "StencilOp",
+        vec![int(stencil_fail), int(depth_fail), int(pass)],
+    );
+}
+
+pub(super) unsafe fn TexImage2D(
+    target: GLenum,
+    level: GLint,
+    internal_format: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    border: GLint,
+    format: GLenum,
+    data_type: GLenum,
+    pixels: *const c_void,
+) {
+    record(
+        "TexImage2D",
+        vec![
+            int(target),
+            int(level),
+            int(internal_format),
+            int(width),
+            int(height),
+            int(border),
+            int(format),
+            int(data_type),
+            pointer(pixels),
+        ],
+    );
+}
+
+pub(super) unsafe fn TexParameteri(target: GLenum, name: GLenum, param: GLint) {
+    record("TexParameteri", vec![int(target), int(name), int(param)]);
+}
+
+pub(super) unsafe fn TexSubImage2D(
+    target: GLenum,
+    level: GLint,
+    x: GLint,
+    y: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    data_type: GLenum,
+    pixels: *const c_void,
+) {
+    record(
+        "TexSubImage2D",
+        vec![
+            int(target),
+            int(level),
+            int(x),
+            int(y),
+            int(width),
+            int(height),
+            int(format),
+            int(data_type),
+            pointer(pixels),
+        ],
+    );
+}
+
+pub(super) unsafe fn Uniform1f(location: GLint, v0: GLfloat) {
+    record("Uniform1f", vec![int(location), Arg::Float(v0)]);
+}
+
+pub(super) unsafe fn Uniform1i(location: GLint, v0: GLint) {
+    record("Uniform1i", vec![int(location), int(v0)]);
+}
+
+pub(super) unsafe fn Uniform2f(location: GLint, v0: GLfloat, v1: GLfloat) {
+    record(
+        "Uniform2f",
+        vec![int(location), Arg::Float(v0), Arg::Float(v1)],
+    );
+}
+
+pub(super) unsafe fn Uniform3f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
+    record(
+        "Uniform3f",
+        vec![
+            int(location),
+            Arg::Float(v0),
+            Arg::Float(v1),
+            Arg::Float(v2),
+        ],
+    );
+}
+
+pub(super) unsafe fn Uniform4f(
+    location: GLint,
+    v0: GLfloat,
+    v1: GLfloat,
+    v2: GLfloat,
+    v3: GLfloat,
+) {
+    record(
+        "Uniform4f",
+        vec![
+            int(location),
+            Arg::Float(v0),
+            Arg::Float(v1),
+            Arg::Float(v2),
+            Arg::Float(v3),
+        ],
+    );
+}
+
+pub(super) unsafe fn UniformMatrix4fv(
+    location: GLint,
+    count: GLsizei,
+    transpose: GLboolean,
+    value: *const GLfloat,
+) {
+    record(
+        "UniformMatrix4fv",
+        vec![int(location), int(count), int(transpose), pointer(value)],
+    );
+}
+
+/// Records what was written to the memory mapped for the buffer bound to
+/// `target`. Reports `GL_INVALID_OPERATION` and returns false if it isn't
+/// mapped.
+pub(super) unsafe fn UnmapBuffer(target: GLenum) -> GLboolean {
+    let data = with_state(|state| {
+        let buffer = state.bound_buffers.get(&target).copied().unwrap_or(0);
+        let mapped = state.mapped.remove(&buffer);
+        if mapped.is_none() {
+            state.errors.push_back(raw_gl::INVALID_OPERATION);
+        }
+        mapped
+    });
+    let unmapped = data.is_some();
+    let data = data.map_or_else(Vec::new, |(memory, length)| {
+        std::slice::from_raw_parts(memory.as_ptr() as *const u8, length).to_vec()
+    });
+    record("UnmapBuffer", vec![int(target), Arg::Bytes(data)]);
+    if unmapped {
+        raw_gl::TRUE
+    } else {
+        raw_gl::FALSE
+    }
+}
+
+pub(super) unsafe fn UseProgram(program: GLuint) {
+    record("UseProgram", vec![int(program)]);
+}
+
+pub(super) unsafe fn VertexAttribDivisor(index: GLuint, divisor: GLuint) {
+    record("VertexAttribDivisor", vec![int(index), int(divisor)]);
+}
+
+pub(super) unsafe fn VertexAttribPointer(
+    index: GLuint,
+    size: GLint,
+    data_type: GLenum,
+    normalized: GLboolean,
+    stride: GLsizei,
+    pointer: *const c_void,
+) {
+    record(
+        "VertexAttribPointer",
+        vec![
+            int(index),
+            int(size),
+            int(data_type),
+            int(normalized),
+            int(stride),
+            self::pointer(pointer),
+        ],
+    );
+}
+
+pub(super) unsafe fn Viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    record("Viewport", vec![int(x), int(y), int(width), int(height)]);
+}
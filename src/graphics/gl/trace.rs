@@ -0,0 +1,1775 @@
+//! Capturing of the OpenGL calls made through `graphics::gl` into a trace
+//! that can be saved and replayed later, enabled by the `gl-trace` feature.
+//!
+//! ```ignore
+//! // Capture the 3 frames following the next one.
+//! gl::trace::capture(1, 3);
+//!
+//! // Later, once `Window::update` has been called enough times:
+//! if let Some(trace) = gl::trace::take_trace() {
+//!     trace.save("bug.trace")?;
+//! }
+//! ```
+//!
+//! Frames end in `Window::update`. Objects used in the captured frames may
+//! have been created before them, so once a capture starts every call is
+//! recorded; only draws, clears, blits and readbacks before the first
+//! captured frame are left out. Start capturing before loading resources
+//! that the captured frames use.
+//!
+//! Replaying maps the object names and uniform locations from the trace to
+//! the ones handed out by the replaying context. See the `replay_trace`
+//! example for a tool that dumps the framebuffer after every draw call.
+//!
+//! Capture happens per thread, on the thread making the OpenGL calls.
+
+#![allow(non_snake_case)]
+#![allow(clippy::too_many_arguments)]
+
+use crate::graphics::{Image, RenderState};
+use raw_gl::types::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ffi::{c_void, CStr, CString};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TraceError {
+    #[error("failed to access the trace file")]
+    Io(#[from] io::Error),
+    #[error("malformed trace on line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("argument {index} of {function} is missing or has the wrong type")]
+    InvalidArgument {
+        function: &'static str,
+        index: usize,
+    },
+}
+
+/// An argument of a captured call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Enums, integers and booleans.
+    Int(i64),
+    Float(f32),
+    /// Object names passed in or, for `Gen*` and `Create*` calls, generated.
+    Ids(Vec<u32>),
+    /// Data uploaded to buffers, textures and uniforms.
+    Bytes(Vec<u8>),
+    /// Shader sources and uniform names.
+    Str(String),
+    /// An offset into a bound buffer, or 0 for a null pointer.
+    Pointer(u64),
+}
+
+/// A call to an OpenGL function, named without its `gl` prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceCall {
+    pub function: &'static str,
+    pub args: Vec<Value>,
+}
+
+impl TraceCall {
+    fn int(&self, index: usize) -> Result<i64, TraceError> {
+        match self.args.get(index) {
+            Some(Value::Int(value)) => Ok(*value),
+            _ => Err(self.invalid(index)),
+        }
+    }
+
+    fn float(&self, index: usize) -> Result<f32, TraceError> {
+        match self.args.get(index) {
+            Some(Value::Float(value)) => Ok(*value),
+            _ => Err(self.invalid(index)),
+        }
+    }
+
+    fn ids(&self, index: usize) -> Result<&[u32], TraceError> {
+        match self.args.get(index) {
+            Some(Value::Ids(ids)) => Ok(ids),
+            _ => Err(self.invalid(index)),
+        }
+    }
+
+    fn str(&self, index: usize) -> Result<&str, TraceError> {
+        match self.args.get(index) {
+            Some(Value::Str(value)) => Ok(value),
+            _ => Err(self.invalid(index)),
+        }
+    }
+
+    /// A size or count, which can't be negative.
+    fn size(&self, index: usize) -> Result<usize, TraceError> {
+        usize::try_from(self.int(index)?).map_err(|_| self.invalid(index))
+    }
+
+    /// The first of the generated names.
+    fn id(&self, index: usize) -> Result<u32, TraceError> {
+        self.ids(index)?
+            .first()
+            .copied()
+            .ok_or_else(|| self.invalid(index))
+    }
+
+    /// Uploaded data of at least `size` bytes.
+    fn bytes(&self, index: usize, size: usize) -> Result<*const c_void, TraceError> {
+        match self.args.get(index) {
+            Some(Value::Bytes(bytes)) if bytes.len() >= size => Ok(bytes.as_ptr() as *const c_void),
+            _ => Err(self.invalid(index)),
+        }
+    }
+
+    /// Uploaded data of at least `size` bytes, or a null pointer to upload
+    /// nothing.
+    fn data(&self, index: usize, size: usize) -> Result<*const c_void, TraceError> {
+        match self.args.get(index) {
+            Some(Value::Pointer(0)) => Ok(std::ptr::null()),
+            _ => self.bytes(index, size),
+        }
+    }
+
+    /// An offset into a bound buffer.
+    fn offset(&self, index: usize) -> Result<*const c_void, TraceError> {
+        match self.args.get(index) {
+            Some(Value::Pointer(offset)) => Ok(*offset as usize as *const c_void),
+            _ => Err(self.invalid(index)),
+        }
+    }
+
+    fn invalid(&self, index: usize) -> TraceError {
+        TraceError::InvalidArgument {
+            function: self.function,
+            index,
+        }
+    }
+}
+
+/// A captured sequence of OpenGL calls.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    pub calls: Vec<TraceCall>,
+    /// The index into `calls` where each captured frame starts.
+    pub frames: Vec<usize>,
+}
+
+const HEADER: &str = "crean-trace 1";
+
+impl Trace {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TraceError> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TraceError> {
+        Trace::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the trace as text, one call per line.
+    pub fn write(&self, mut writer: impl Write) -> Result<(), TraceError> {
+        writeln!(writer, "{}", HEADER)?;
+        let mut frames = self.frames.iter().peekable();
+        for (i, call) in self.calls.iter().enumerate() {
+            while frames.next_if(|&&start| start == i).is_some() {
+                writeln!(writer, "frame")?;
+            }
+            write!(writer, "{}", call.function)?;
+            for arg in &call.args {
+                write!(writer, " ")?;
+                write_value(&mut writer, arg)?;
+            }
+            writeln!(writer)?;
+        }
+        for _ in frames {
+            writeln!(writer, "frame")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read(reader: impl Read) -> Result<Self, TraceError> {
+        let mut trace = Trace::default();
+        let mut lines = BufReader::new(reader).lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(parse_error(1, "not a crean trace"));
+        }
+
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            let number = i + 2;
+            let mut parts = line.split(' ');
+            let function = parts.next().unwrap_or_default();
+            if function == "frame" {
+                trace.frames.push(trace.calls.len());
+                continue;
+            }
+
+            let function = FUNCTIONS
+                .iter()
+                .find(|&&name| name == function)
+                .ok_or_else(|| parse_error(number, &format!("unknown function {}", function)))?;
+            let args = parts
+                .map(|part| read_value(part).ok_or_else(|| parse_error(number, part)))
+                .collect::<Result<Vec<_>, _>>()?;
+            trace.calls.push(TraceCall { function, args });
+        }
+        Ok(trace)
+    }
+
+    /// Executes the trace on the current context. `on_draw` is called after
+    /// every draw call in a captured frame, with the result in the bound
+    /// framebuffer.
+    ///
+    /// Stops with `TraceError::InvalidArgument` at a call the trace doesn't
+    /// hold valid arguments for, such as uploaded data shorter than the size
+    /// passed with it, before the call reaches OpenGL.
+    pub fn replay<F>(&self, mut on_draw: F) -> Result<(), TraceError>
+    where
+        F: FnMut(&ReplayedDraw),
+    {
+        let mut replayer = Replayer::default();
+        let mut frame = None;
+        let mut draw = 0;
+        for (i, call) in self.calls.iter().enumerate() {
+            if let Ok(index) = self.frames.binary_search(&i) {
+                frame = Some(index);
+                draw = 0;
+            }
+
+            unsafe { replayer.execute(call)? };
+
+            if let (Some(frame), true) = (frame, call.function.starts_with("Draw")) {
+                if call.function != "DrawBuffers" {
+                    on_draw(&ReplayedDraw {
+                        frame,
+                        draw,
+                        function: call.function,
+                    });
+                    draw += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_error(line: usize, message: &str) -> TraceError {
+    TraceError::Parse {
+        line,
+        message: message.to_owned(),
+    }
+}
+
+fn write_value(writer: &mut impl Write, value: &Value) -> io::Result<()> {
+    match value {
+        Value::Int(value) => write!(writer, "i{}", value),
+        Value::Float(value) => write!(writer, "f{:x}", value.to_bits()),
+        Value::Ids(ids) => {
+            let ids = ids.iter().map(u32::to_string).collect::<Vec<_>>();
+            write!(writer, "n{}", ids.join(","))
+        }
+        Value::Bytes(bytes) => {
+            write!(writer, "b")?;
+            write_hex(writer, bytes)
+        }
+        Value::Str(value) => {
+            write!(writer, "s")?;
+            write_hex(writer, value.as_bytes())
+        }
+        Value::Pointer(offset) => write!(writer, "p{}", offset),
+    }
+}
+
+fn write_hex(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    bytes
+        .iter()
+        .try_for_each(|byte| write!(writer, "{:02x}", byte))
+}
+
+fn read_value(text: &str) -> Option<Value> {
+    let (kind, text) = text.split_at(text.char_indices().nth(1)?.0);
+    Some(match kind {
+        "i" => Value::Int(text.parse().ok()?),
+        "f" => Value::Float(f32::from_bits(u32::from_str_radix(text, 16).ok()?)),
+        "n" if text.is_empty() => Value::Ids(Vec::new()),
+        "n" => Value::Ids(
+            text.split(',')
+                .map(|id| id.parse().ok())
+                .collect::<Option<_>>()?,
+        ),
+        "b" => Value::Bytes(read_hex(text)?),
+        "s" => Value::Str(String::from_utf8(read_hex(text)?).ok()?),
+        "p" => Value::Pointer(text.parse().ok()?),
+        _ => return None,
+    })
+}
+
+fn read_hex(text: &str) -> Option<Vec<u8>> {
+    let digit = |byte: u8| (byte as char).to_digit(16).map(|digit| digit as u8);
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| match *pair {
+            [high, low] => Some(digit(high)? << 4 | digit(low)?),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A draw call that has just been replayed.
+#[derive(Debug)]
+pub struct ReplayedDraw {
+    /// The index of the captured frame.
+    pub frame: usize,
+    /// The index of the draw call within the frame.
+    pub draw: usize,
+    pub function: &'static str,
+}
+
+impl ReplayedDraw {
+    /// Reads back the color of the framebuffer the draw rendered into, the
+    /// size of the viewport.
+    pub fn read_framebuffer(&self) -> Image {
+        let mut framebuffer: GLint = 0;
+        let mut viewport: [GLint; 4] = [0; 4];
+        unsafe {
+            gl_raw!(GetIntegerv)(raw_gl::DRAW_FRAMEBUFFER_BINDING, &mut framebuffer);
+            gl_raw!(GetIntegerv)(raw_gl::VIEWPORT, viewport.as_mut_ptr());
+            gl_raw!(BindFramebuffer)(raw_gl::READ_FRAMEBUFFER, framebuffer as GLuint);
+        }
+        Image::read_framebuffer(viewport[2].max(0) as u32, viewport[3].max(0) as u32)
+    }
+}
+
+struct Capture {
+    first_frame: u32,
+    frame_count: u32,
+    frame: u32,
+    trace: Trace,
+}
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Capture>> = const { RefCell::new(None) };
+    static FINISHED: RefCell<Option<Trace>> = const { RefCell::new(None) };
+    /// The buffer bound to each target, as several buffers can be mapped on
+    /// the same target at once.
+    static BOUND_BUFFERS: RefCell<HashMap<GLenum, GLuint>> = RefCell::new(HashMap::new());
+    /// The memory, length and access of the range mapped for each buffer, so
+    /// that what was written to it can be captured on unmapping.
+    static MAPPED: RefCell<HashMap<GLuint, (*const u8, usize, GLbitfield)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Starts capturing on the calling thread, discarding any unfinished
+/// capture. Frames are counted from the current one, so a `first_frame` of
+/// 0 includes it. With a `frame_count` of 0 there is nothing to capture, and
+/// an empty trace is finished right away.
+///
+/// The render state of the current context is set again to record it, and
+/// its cached bindings are forgotten so that the trace binds objects before
+/// relying on them.
+pub fn capture(first_frame: u32, frame_count: u32) {
+    if frame_count == 0 {
+        CAPTURE.with(|capture| *capture.borrow_mut() = None);
+        FINISHED.with(|finished| *finished.borrow_mut() = Some(Trace::default()));
+        return;
+    }
+
+    let mut trace = Trace::default();
+    if first_frame == 0 {
+        trace.frames.push(0);
+    }
+    CAPTURE.with(|capture| {
+        *capture.borrow_mut() = Some(Capture {
+            first_frame,
+            frame_count,
+            frame: 0,
+            trace,
+        })
+    });
+
+    super::invalidate_bindings();
+    if let Some(state) = RenderState::current() {
+        RenderState::invalidate();
+        state.apply();
+    }
+}
+
+#[inline]
+pub fn is_capturing() -> bool {
+    CAPTURE.with(|capture| capture.borrow().is_some())
+}
+
+/// Stops capturing early, returning what has been captured so far.
+pub fn stop_capture() -> Option<Trace> {
+    CAPTURE.with(|capture| capture.borrow_mut().take().map(|capture| capture.trace))
+}
+
+/// Takes the trace of the last capture that captured all of its frames.
+pub fn take_trace() -> Option<Trace> {
+    FINISHED.with(|finished| finished.borrow_mut().take())
+}
+
+/// Marks the end of a frame. Called by `Window::update`.
+pub fn end_frame() {
+    let finished = CAPTURE.with(|capture| {
+        let mut capture = capture.borrow_mut();
+        let current = capture.as_mut()?;
+        current.frame += 1;
+        if current.frame >= current.first_frame.saturating_add(current.frame_count) {
+            return capture.take().map(|capture| capture.trace);
+        }
+        if current.frame >= current.first_frame {
+            let start = current.trace.calls.len();
+            current.trace.frames.push(start);
+        }
+        None
+    });
+    if let Some(trace) = finished {
+        FINISHED.with(|finished| *finished.borrow_mut() = Some(trace));
+    }
+}
+
+/// Whether a call to `function` would be captured right now.
+fn recording(function: &str) -> bool {
+    CAPTURE.with(|capture| match &*capture.borrow() {
+        Some(capture) => capture.frame >= capture.first_frame || !produces_pixels(function),
+        None => false,
+    })
+}
+
+fn produces_pixels(function: &str) -> bool {
+    function.starts_with("Draw") && function != "DrawBuffers"
+        || matches!(function, "Clear" | "BlitFramebuffer" | "ReadPixels")
+}
+
+fn push(function: &'static str, args: Vec<Value>) {
+    CAPTURE.with(|capture| {
+        if let Some(capture) = &mut *capture.borrow_mut() {
+            capture.trace.calls.push(TraceCall { function, args });
+        }
+    });
+}
+
+/// Captures a call, only evaluating its arguments if it is recorded.
+macro_rules! traced {
+    ($function:ident($($value:expr),* $(,)?)) => {
+        if recording(stringify!($function)) {
+            push(stringify!($function), vec![$($value),*]);
+        }
+    };
+}
+
+fn int(value: impl Into<i64>) -> Value {
+    Value::Int(value.into())
+}
+
+unsafe fn ids(n: GLsizei, ids: *const GLuint) -> Value {
+    Value::Ids(std::slice::from_raw_parts(ids, n.max(0) as usize).to_vec())
+}
+
+/// Copies `size` bytes of uploaded data, or records a null pointer.
+unsafe fn bytes(data: *const c_void, size: usize) -> Value {
+    if data.is_null() {
+        Value::Pointer(0)
+    } else {
+        Value::Bytes(std::slice::from_raw_parts(data as *const u8, size).to_vec())
+    }
+}
+
+/// The size of the pixel data of a texture upload with rows aligned to
+/// `alignment` bytes, or None for a format or type `graphics::gl` doesn't
+/// upload. Uploads through `graphics::gl` always set an unpack alignment of 1.
+fn pixels_size(
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    data_type: GLenum,
+    alignment: usize,
+) -> Option<usize> {
+    let channels = match format {
+        raw_gl::RED | raw_gl::DEPTH_COMPONENT | raw_gl::DEPTH_STENCIL | raw_gl::STENCIL_INDEX => 1,
+        raw_gl::RGB => 3,
+        raw_gl::RGBA => 4,
+        _ => return None,
+    };
+    let pixel = match data_type {
+        raw_gl::UNSIGNED_BYTE => channels,
+        raw_gl::UNSIGNED_INT_24_8 => 4,
+        raw_gl::UNSIGNED_INT | raw_gl::FLOAT => channels * 4,
+        _ => return None,
+    };
+
+    let (width, height) = (width.max(0) as usize, height.max(0) as usize);
+    if width == 0 || height == 0 {
+        return Some(0);
+    }
+    let row = width.checked_mul(pixel)?;
+    let stride = row.checked_add(alignment - 1)? / alignment * alignment;
+    stride.checked_mul(height - 1)?.checked_add(row)
+}
+
+/// Every function that can appear in a trace.
+const FUNCTIONS: &[&str] = &[
+    "ActiveTexture",
+    "AttachShader",
+    "BindBuffer",
+    "BindFramebuffer",
+    "BindRenderbuffer",
+    "BindTexture",
+    "BindVertexArray",
+    "BlendColor",
+    "BlendEquationSeparate",
+    "BlendFuncSeparate",
+    "BlitFramebuffer",
+    "BufferData",
+    "BufferSubData",
+    "Clear",
+    "ClearColor",
+    "ColorMask",
+    "CompileShader",
+    "CreateProgram",
+    "CreateShader",
+    "CullFace",
+    "DeleteBuffers",
+    "DeleteFramebuffers",
+    "DeleteProgram",
+    "DeleteRenderbuffers",
+    "DeleteShader",
+    "DeleteTextures",
+    "DeleteVertexArrays",
+    "DepthFunc",
+    "DepthMask",
+    "DetachShader",
+    "Disable",
+    "DisableVertexAttribArray",
+    "DrawArrays",
+    "DrawArraysInstanced",
+    "DrawBuffers",
+    "DrawElements",
+    "DrawElementsInstanced",
+    "Enable",
+    "EnableVertexAttribArray",
+    "FlushMappedBufferRange",
+    "FramebufferRenderbuffer",
+    "FramebufferTexture2D",
+    "FrontFace",
+    "GenBuffers",
+    "GenFramebuffers",
+    "GenRenderbuffers",
+    "GenTextures",
+    "GenVertexArrays",
+    "GenerateMipmap",
+    "GetUniformLocation",
+    "LinkProgram",
+    "MapBufferRange",
+    "PixelStorei",
+    "PolygonMode",
+    "ReadBuffer",
+    "ReadPixels",
+    "RenderbufferStorage",
+    "Scissor",
+    "ShaderSource",
+    "StencilFunc",
+    "StencilMask",
+    "StencilOp",
+    "TexImage2D",
+    "TexParameteri",
+    "TexSubImage2D",
+    "Uniform1f",
+    "Uniform1i",
+    "Uniform2f",
+    "Uniform3f",
+    "Uniform4f",
+    "UniformMatrix4fv",
+    "UnmapBuffer",
+    "UseProgram",
+    "VertexAttribDivisor",
+    "VertexAttribPointer",
+    "Viewport",
+];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Namespace {
+    Buffer,
+    VertexArray,
+    Texture,
+    Framebuffer,
+    Renderbuffer,
+    /// Shaders and programs share their names.
+    Program,
+}
+
+/// Maps names and uniform locations from the trace to the replaying context.
+#[derive(Default)]
+struct Replayer {
+    names: HashMap<(Namespace, u32), u32>,
+    uniforms: HashMap<(u32, i32), i32>,
+    program: u32,
+    /// The traced name of the buffer bound to each target.
+    buffers: HashMap<GLenum, u32>,
+    /// The memory and length of the range mapped for each buffer, by traced
+    /// name.
+    mapped: HashMap<u32, (*mut u8, usize)>,
+    /// The unpack alignment set by the trace, if it set one.
+    unpack_alignment: Option<usize>,
+}
+
+impl Replayer {
+    fn name(&self, namespace: Namespace, id: i64) -> GLuint {
+        let id = id as u32;
+        if id == 0 {
+            return 0;
+        }
+        self.names
+            .get(&(namespace, id))
+            .copied()
+            .unwrap_or_else(|| {
+                log::warn!("trace uses an unknown object {}", id);
+                id
+            })
+    }
+
+    fn names(&self, namespace: Namespace, ids: &[u32]) -> Vec<GLuint> {
+        ids.iter()
+            .map(|&id| self.name(namespace, id as i64))
+            .collect()
+    }
+
+    /// The traced name of the buffer bound to `target`.
+    fn bound_buffer(&self, target: GLenum) -> u32 {
+        self.buffers.get(&target).copied().unwrap_or(0)
+    }
+
+    /// The size of the pixel data a texture upload reads.
+    fn pixels_size(
+        &self,
+        call: &TraceCall,
+        width: usize,
+        format: usize,
+    ) -> Result<usize, TraceError> {
+        pixels_size(
+            call.int(width)? as GLsizei,
+            call.int(width + 1)? as GLsizei,
+            call.int(format)? as GLenum,
+            call.int(format + 1)? as GLenum,
+            self.unpack_alignment.unwrap_or(4),
+        )
+        .ok_or_else(|| call.invalid(format))
+    }
+
+    fn location(&self, location: i64) -> GLint {
+        let location = location as i32;
+        self.uniforms
+            .get(&(self.program, location))
+            .copied()
+            .unwrap_or(location)
+    }
+
+    unsafe fn generate(
+        &mut self,
+        namespace: Namespace,
+        call: &TraceCall,
+        generate: unsafe fn(GLsizei, *mut GLuint),
+    ) -> Result<(), TraceError> {
+        let traced = call.ids(1)?;
+        let mut ids = vec![0; traced.len()];
+        generate(ids.len() as GLsizei, ids.as_mut_ptr());
+        for (&traced, id) in traced.iter().zip(ids) {
+            self.names.insert((namespace, traced), id);
+        }
+        Ok(())
+    }
+
+    unsafe fn delete(
+        &mut self,
+        namespace: Namespace,
+        call: &TraceCall,
+        delete: unsafe fn(GLsizei, *const GLuint),
+    ) -> Result<(), TraceError> {
+        let ids = self.names(namespace, call.ids(1)?);
+        delete(ids.len() as GLsizei, ids.as_ptr());
+        for &traced in call.ids(1)? {
+            self.names.remove(&(namespace, traced));
+        }
+        Ok(())
+    }
+
+    unsafe fn execute(&mut self, call: &TraceCall) -> Result<(), TraceError> {
+        use Namespace::*;
+        let c = call;
+        match c.function {
+            "ActiveTexture" => gl_raw!(ActiveTexture)(c.int(0)? as GLenum),
+            "AttachShader" => {
+                gl_raw!(AttachShader)(self.name(Program, c.int(0)?), self.name(Program, c.int(1)?))
+            }
+            "BindBuffer" => {
+                let target = c.int(0)? as GLenum;
+                self.buffers.insert(target, c.int(1)? as u32);
+                gl_raw!(BindBuffer)(target, self.name(Buffer, c.int(1)?))
+            }
+            "BindFramebuffer" => {
+                gl_raw!(BindFramebuffer)(c.int(0)? as GLenum, self.name(Framebuffer, c.int(1)?))
+            }
+            "BindRenderbuffer" => {
+                gl_raw!(BindRenderbuffer)(c.int(0)? as GLenum, self.name(Renderbuffer, c.int(1)?))
+            }
+            "BindTexture" => {
+                gl_raw!(BindTexture)(c.int(0)? as GLenum, self.name(Texture, c.int(1)?))
+            }
+            "BindVertexArray" => gl_raw!(BindVertexArray)(self.name(VertexArray, c.int(0)?)),
+            "BlendColor" => gl_raw!(BlendColor)(c.float(0)?, c.float(1)?, c.float(2)?, c.float(3)?),
+            "BlendEquationSeparate" => {
+                gl_raw!(BlendEquationSeparate)(c.int(0)? as GLenum, c.int(1)? as GLenum)
+            }
+            "BlendFuncSeparate" => gl_raw!(BlendFuncSeparate)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLenum,
+                c.int(2)? as GLenum,
+                c.int(3)? as GLenum,
+            ),
+            "BlitFramebuffer" => gl_raw!(BlitFramebuffer)(
+                c.int(0)? as GLint,
+                c.int(1)? as GLint,
+                c.int(2)? as GLint,
+                c.int(3)? as GLint,
+                c.int(4)? as GLint,
+                c.int(5)? as GLint,
+                c.int(6)? as GLint,
+                c.int(7)? as GLint,
+                c.int(8)? as GLbitfield,
+                c.int(9)? as GLenum,
+            ),
+            "BufferData" => {
+                let size = c.size(1)?;
+                gl_raw!(BufferData)(
+                    c.int(0)? as GLenum,
+                    size as GLsizeiptr,
+                    c.data(2, size)?,
+                    c.int(3)? as GLenum,
+                )
+            }
+            "BufferSubData" => {
+                let size = c.size(2)?;
+                gl_raw!(BufferSubData)(
+                    c.int(0)? as GLenum,
+                    c.int(1)? as GLintptr,
+                    size as GLsizeiptr,
+                    c.bytes(3, size)?,
+                )
+            }
+            "Clear" => gl_raw!(Clear)(c.int(0)? as GLbitfield),
+            "ClearColor" => gl_raw!(ClearColor)(c.float(0)?, c.float(1)?, c.float(2)?, c.float(3)?),
+            "ColorMask" => gl_raw!(ColorMask)(
+                c.int(0)? as GLboolean,
+                c.int(1)? as GLboolean,
+                c.int(2)? as GLboolean,
+                c.int(3)? as GLboolean,
+            ),
+            "CompileShader" => gl_raw!(CompileShader)(self.name(Program, c.int(0)?)),
+            "CreateProgram" => {
+                let id = gl_raw!(CreateProgram)();
+                self.names.insert((Program, c.id(0)?), id);
+            }
+            "CreateShader" => {
+                let id = gl_raw!(CreateShader)(c.int(0)? as GLenum);
+                self.names.insert((Program, c.id(1)?), id);
+            }
+            "CullFace" => gl_raw!(CullFace)(c.int(0)? as GLenum),
+            "DeleteBuffers" => {
+                let deleted = c.ids(1)?;
+                self.buffers.retain(|_, buffer| !deleted.contains(buffer));
+                self.mapped.retain(|buffer, _| !deleted.contains(buffer));
+                self.delete(Buffer, c, gl_raw!(DeleteBuffers))?
+            }
+            "DeleteFramebuffers" => self.delete(Framebuffer, c, gl_raw!(DeleteFramebuffers))?,
+            "DeleteProgram" | "DeleteShader" => {
+                let id = self.name(Program, c.int(0)?);
+                if c.function == "DeleteProgram" {
+                    gl_raw!(DeleteProgram)(id);
+                } else {
+                    gl_raw!(DeleteShader)(id);
+                }
+                self.names.remove(&(Program, c.int(0)? as u32));
+            }
+            "DeleteRenderbuffers" => self.delete(Renderbuffer, c, gl_raw!(DeleteRenderbuffers))?,
+            "DeleteTextures" => self.delete(Texture, c, gl_raw!(DeleteTextures))?,
+            "DeleteVertexArrays" => self.delete(VertexArray, c, gl_raw!(DeleteVertexArrays))?,
+            "DepthFunc" => gl_raw!(DepthFunc)(c.int(0)? as GLenum),
+            "DepthMask" => gl_raw!(DepthMask)(c.int(0)? as GLboolean),
+            "DetachShader" => {
+                gl_raw!(DetachShader)(self.name(Program, c.int(0)?), self.name(Program, c.int(1)?))
+            }
+            "Disable" => gl_raw!(Disable)(c.int(0)? as GLenum),
+            "DisableVertexAttribArray" => gl_raw!(DisableVertexAttribArray)(c.int(0)? as GLuint),
+            "DrawArrays" => gl_raw!(DrawArrays)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLint,
+                c.int(2)? as GLsizei,
+            ),
+            "DrawArraysInstanced" => gl_raw!(DrawArraysInstanced)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLint,
+                c.int(2)? as GLsizei,
+                c.int(3)? as GLsizei,
+            ),
+            "DrawBuffers" => {
+                let buffers = c.ids(1)?;
+                gl_raw!(DrawBuffers)(buffers.len() as GLsizei, buffers.as_ptr())
+            }
+            "DrawElements" => gl_raw!(DrawElements)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLsizei,
+                c.int(2)? as GLenum,
+                c.offset(3)?,
+            ),
+            "DrawElementsInstanced" => gl_raw!(DrawElementsInstanced)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLsizei,
+                c.int(2)? as GLenum,
+                c.offset(3)?,
+                c.int(4)? as GLsizei,
+            ),
+            "Enable" => gl_raw!(Enable)(c.int(0)? as GLenum),
+            "EnableVertexAttribArray" => gl_raw!(EnableVertexAttribArray)(c.int(0)? as GLuint),
+            // The written data is copied in all at once when unmapping.
+            "FlushMappedBufferRange" => {}
+            "FramebufferRenderbuffer" => gl_raw!(FramebufferRenderbuffer)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLenum,
+                c.int(2)? as GLenum,
+                self.name(Renderbuffer, c.int(3)?),
+            ),
+            "FramebufferTexture2D" => gl_raw!(FramebufferTexture2D)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLenum,
+                c.int(2)? as GLenum,
+                self.name(Texture, c.int(3)?),
+                c.int(4)? as GLint,
+            ),
+            "FrontFace" => gl_raw!(FrontFace)(c.int(0)? as GLenum),
+            "GenBuffers" => self.generate(Buffer, c, gl_raw!(GenBuffers))?,
+            "GenFramebuffers" => self.generate(Framebuffer, c, gl_raw!(GenFramebuffers))?,
+            "GenRenderbuffers" => self.generate(Renderbuffer, c, gl_raw!(GenRenderbuffers))?,
+            "GenTextures" => self.generate(Texture, c, gl_raw!(GenTextures))?,
+            "GenVertexArrays" => self.generate(VertexArray, c, gl_raw!(GenVertexArrays))?,
+            "GenerateMipmap" => gl_raw!(GenerateMipmap)(c.int(0)? as GLenum),
+            "GetUniformLocation" => {
+                let traced_program = c.int(0)? as u32;
+                let name = CString::new(c.str(1)?).map_err(|_| c.invalid(1))?;
+                let location = gl_raw!(GetUniformLocation)(
+                    self.name(Program, traced_program as i64),
+                    name.as_ptr(),
+                );
+                self.uniforms
+                    .insert((traced_program, c.int(2)? as i32), location);
+            }
+            "LinkProgram" => gl_raw!(LinkProgram)(self.name(Program, c.int(0)?)),
+            "MapBufferRange" => {
+                let target = c.int(0)? as GLenum;
+                let length = c.size(2)?;
+                let access = c.int(3)? as GLbitfield & !raw_gl::MAP_FLUSH_EXPLICIT_BIT;
+                let data = gl_raw!(MapBufferRange)(
+                    target,
+                    c.int(1)? as GLintptr,
+                    length as GLsizeiptr,
+                    access,
+                );
+                if !data.is_null() {
+                    let buffer = self.bound_buffer(target);
+                    self.mapped.insert(buffer, (data as *mut u8, length));
+                }
+            }
+            "PixelStorei" => {
+                let (name, value) = (c.int(0)? as GLenum, c.int(1)?);
+                if !matches!(value, 1 | 2 | 4 | 8) {
+                    return Err(c.invalid(1));
+                }
+                // Other unpack parameters would change how much data uploads
+                // read, which the sizes checked here don't account for.
+                match name {
+                    raw_gl::UNPACK_ALIGNMENT => self.unpack_alignment = Some(value as usize),
+                    raw_gl::PACK_ALIGNMENT => {}
+                    _ => return Err(c.invalid(0)),
+                }
+                gl_raw!(PixelStorei)(name, value as GLint)
+            }
+            "PolygonMode" => gl_raw!(PolygonMode)(c.int(0)? as GLenum, c.int(1)? as GLenum),
+            "ReadBuffer" => gl_raw!(ReadBuffer)(c.int(0)? as GLenum),
+            // The data read back at capture is already gone.
+            "ReadPixels" => {}
+            "RenderbufferStorage" => gl_raw!(RenderbufferStorage)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLenum,
+                c.int(2)? as GLsizei,
+                c.int(3)? as GLsizei,
+            ),
+            "Scissor" => gl_raw!(Scissor)(
+                c.int(0)? as GLint,
+                c.int(1)? as GLint,
+                c.int(2)? as GLsizei,
+                c.int(3)? as GLsizei,
+            ),
+            "ShaderSource" => {
+                let source = CString::new(c.str(1)?).map_err(|_| c.invalid(1))?;
+                gl_raw!(ShaderSource)(
+                    self.name(Program, c.int(0)?),
+                    1,
+                    &source.as_ptr(),
+                    std::ptr::null(),
+                )
+            }
+            "StencilFunc" => {
+                gl_raw!(StencilFunc)(c.int(0)? as GLenum, c.int(1)? as GLint, c.int(2)? as GLuint)
+            }
+            "StencilMask" => gl_raw!(StencilMask)(c.int(0)? as GLuint),
+            "StencilOp" => gl_raw!(StencilOp)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLenum,
+                c.int(2)? as GLenum,
+            ),
+            "TexImage2D" => gl_raw!(TexImage2D)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLint,
+                c.int(2)? as GLint,
+                c.int(3)? as GLsizei,
+                c.int(4)? as GLsizei,
+                c.int(5)? as GLint,
+                c.int(6)? as GLenum,
+                c.int(7)? as GLenum,
+                c.data(8, self.pixels_size(c, 3, 6)?)?,
+            ),
+            "TexParameteri" => {
+                gl_raw!(TexParameteri)(c.int(0)? as GLenum, c.int(1)? as GLenum, c.int(2)? as GLint)
+            }
+            "TexSubImage2D" => gl_raw!(TexSubImage2D)(
+                c.int(0)? as GLenum,
+                c.int(1)? as GLint,
+                c.int(2)? as GLint,
+                c.int(3)? as GLint,
+                c.int(4)? as GLsizei,
+                c.int(5)? as GLsizei,
+                c.int(6)? as GLenum,
+                c.int(7)? as GLenum,
+                c.bytes(8, self.pixels_size(c, 4, 6)?)?,
+            ),
+            "Uniform1f" => gl_raw!(Uniform1f)(self.location(c.int(0)?), c.float(1)?),
+            "Uniform1i" => gl_raw!(Uniform1i)(self.location(c.int(0)?), c.int(1)? as GLint),
+            "Uniform2f" => gl_raw!(Uniform2f)(self.location(c.int(0)?), c.float(1)?, c.float(2)?),
+            "Uniform3f" => gl_raw!(Uniform3f)(
+                self.location(c.int(0)?),
+                c.float(1)?,
+                c.float(2)?,
+                c.float(3)?,
+            ),
+            "Uniform4f" => gl_raw!(Uniform4f)(
+                self.location(c.int(0)?),
+                c.float(1)?,
+                c.float(2)?,
+                c.float(3)?,
+                c.float(4)?,
+            ),
+            "UniformMatrix4fv" => {
+                let count = c.size(1)?;
+                let size = count
+                    .checked_mul(16 * std::mem::size_of::<GLfloat>())
+                    .ok_or_else(|| c.invalid(1))?;
+                gl_raw!(UniformMatrix4fv)(
+                    self.location(c.int(0)?),
+                    count as GLsizei,
+                    c.int(2)? as GLboolean,
+                    c.bytes(3, size)? as *const GLfloat,
+                )
+            }
+            "UnmapBuffer" => {
+                let target = c.int(0)? as GLenum;
+                let mapped = self.mapped.remove(&self.bound_buffer(target));
+                let result = match (mapped, c.args.get(1)) {
+                    (Some((data, length)), Some(Value::Bytes(written))) if !data.is_null() => {
+                        if written.len() <= length {
+                            std::ptr::copy_nonoverlapping(written.as_ptr(), data, written.len());
+                            Ok(())
+                        } else {
+                            Err(c.invalid(1))
+                        }
+                    }
+                    _ => Ok(()),
+                };
+                gl_raw!(UnmapBuffer)(target);
+                result?;
+            }
+            "UseProgram" => {
+                self.program = c.int(0)? as u32;
+                gl_raw!(UseProgram)(self.name(Program, c.int(0)?))
+            }
+            "VertexAttribDivisor" => {
+                gl_raw!(VertexAttribDivisor)(c.int(0)? as GLuint, c.int(1)? as GLuint)
+            }
+            "VertexAttribPointer" => gl_raw!(VertexAttribPointer)(
+                c.int(0)? as GLuint,
+                c.int(1)? as GLint,
+                c.int(2)? as GLenum,
+                c.int(3)? as GLboolean,
+                c.int(4)? as GLsizei,
+                c.offset(5)?,
+            ),
+            "Viewport" => gl_raw!(Viewport)(
+                c.int(0)? as GLint,
+                c.int(1)? as GLint,
+                c.int(2)? as GLsizei,
+                c.int(3)? as GLsizei,
+            ),
+            _ => log::warn!("cannot replay {}", c.function),
+        }
+        Ok(())
+    }
+}
+
+// Capturing wrappers around every function `graphics::gl` calls. Queries
+// aren't captured, as they don't change any state.
+
+pub(super) unsafe fn GetError() -> GLenum {
+    gl_raw!(GetError)()
+}
+
+pub(super) unsafe fn GetIntegerv(name: GLenum, data: *mut GLint) {
+    gl_raw!(GetIntegerv)(name, data)
+}
+
+pub(super) unsafe fn GetProgramInfoLog(
+    program: GLuint,
+    buffer_size: GLsizei,
+    length: *mut GLsizei,
+    info_log: *mut GLchar,
+) {
+    gl_raw!(GetProgramInfoLog)(program, buffer_size, length, info_log)
+}
+
+pub(super) unsafe fn GetProgramiv(program: GLuint, name: GLenum, params: *mut GLint) {
+    gl_raw!(GetProgramiv)(program, name, params)
+}
+
+pub(super) unsafe fn GetShaderInfoLog(
+    shader: GLuint,
+    buffer_size: GLsizei,
+    length: *mut GLsizei,
+    info_log: *mut GLchar,
+) {
+    gl_raw!(GetShaderInfoLog)(shader, buffer_size, length, info_log)
+}
+
+pub(super) unsafe fn GetShaderiv(shader: GLuint, name: GLenum, params: *mut GLint) {
+    gl_raw!(GetShaderiv)(shader, name, params)
+}
+
+pub(super) unsafe fn CheckFramebufferStatus(target: GLenum) -> GLenum {
+    gl_raw!(CheckFramebufferStatus)(target)
+}
+
+pub(super) unsafe fn DebugMessageCallback(callback: GLDEBUGPROC, user_param: *const c_void) {
+    gl_raw!(DebugMessageCallback)(callback, user_param)
+}
+
+/// Captures the location too, to map it to the replayed one.
+pub(super) unsafe fn GetUniformLocation(program: GLuint, name: *const GLchar) -> GLint {
+    let location = gl_raw!(GetUniformLocation)(program, name);
+    traced!(GetUniformLocation(
+        int(program),
+        Value::Str(CStr::from_ptr(name).to_string_lossy().into_owned()),
+        int(location),
+    ));
+    location
+}
+
+pub(super) unsafe fn ActiveTexture(texture: GLenum) {
+    traced!(ActiveTexture(int(texture)));
+    gl_raw!(ActiveTexture)(texture)
+}
+
+pub(super) unsafe fn AttachShader(program: GLuint, shader: GLuint) {
+    traced!(AttachShader(int(program), int(shader)));
+    gl_raw!(AttachShader)(program, shader)
+}
+
+fn bound_buffer(target: GLenum) -> GLuint {
+    BOUND_BUFFERS.with(|bound| bound.borrow().get(&target).copied().unwrap_or(0))
+}
+
+pub(super) unsafe fn BindBuffer(target: GLenum, buffer: GLuint) {
+    BOUND_BUFFERS.with(|bound| bound.borrow_mut().insert(target, buffer));
+    traced!(BindBuffer(int(target), int(buffer)));
+    gl_raw!(BindBuffer)(target, buffer)
+}
+
+pub(super) unsafe fn BindFramebuffer(target: GLenum, framebuffer: GLuint) {
+    traced!(BindFramebuffer(int(target), int(framebuffer)));
+    gl_raw!(BindFramebuffer)(target, framebuffer)
+}
+
+pub(super) unsafe fn BindRenderbuffer(target: GLenum, renderbuffer: GLuint) {
+    traced!(BindRenderbuffer(int(target), int(renderbuffer)));
+    gl_raw!(BindRenderbuffer)(target, renderbuffer)
+}
+
+pub(super) unsafe fn BindTexture(target: GLenum, texture: GLuint) {
+    traced!(BindTexture(int(target), int(texture)));
+    gl_raw!(BindTexture)(target, texture)
+}
+
+pub(super) unsafe fn BindVertexArray(array: GLuint) {
+    traced!(BindVertexArray(int(array)));
+    gl_raw!(BindVertexArray)(array)
+}
+
+pub(super) unsafe fn BlendColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+    traced!(BlendColor(
+        Value::Float(red),
+        Value::Float(green),
+        Value::Float(blue),
+        Value::Float(alpha)
+    ));
+    gl_raw!(BlendColor)(red, green, blue, alpha)
+}
+
+pub(super) unsafe fn BlendEquationSeparate(mode_rgb: GLenum, mode_alpha: GLenum) {
+    traced!(BlendEquationSeparate(int(mode_rgb), int(mode_alpha)));
+    gl_raw!(BlendEquationSeparate)(mode_rgb, mode_alpha)
+}
+
+pub(super) unsafe fn BlendFuncSeparate(
+    src_rgb: GLenum,
+    dst_rgb: GLenum,
+    src_alpha: GLenum,
+    dst_alpha: GLenum,
+) {
+    traced!(BlendFuncSeparate(
+        int(src_rgb),
+        int(dst_rgb),
+        int(src_alpha),
+        int(dst_alpha)
+    ));
+    gl_raw!(BlendFuncSeparate)(src_rgb, dst_rgb, src_alpha, dst_alpha)
+}
+
+pub(super) unsafe fn BlitFramebuffer(
+    src_x0: GLint,
+    src_y0: GLint,
+    src_x1: GLint,
+    src_y1: GLint,
+    dst_x0: GLint,
+    dst_y0: GLint,
+    dst_x1: GLint,
+    dst_y1: GLint,
+    mask: GLbitfield,
+    filter: GLenum,
+) {
+    traced!(BlitFramebuffer(
+        int(src_x0),
+        int(src_y0),
+        int(src_x1),
+        int(src_y1),
+        int(dst_x0),
+        int(dst_y0),
+        int(dst_x1),
+        int(dst_y1),
+        int(mask),
+        int(filter),
+    ));
+    gl_raw!(BlitFramebuffer)(
+        src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1, mask, filter,
+    )
+}
+
+pub(super) unsafe fn BufferData(
+    target: GLenum,
+    size: GLsizeiptr,
+    data: *const c_void,
+    usage: GLenum,
+) {
+    traced!(BufferData(
+        int(target),
+        int(size as i64),
+        bytes(data, size as usize),
+        int(usage),
+    ));
+    gl_raw!(BufferData)(target, size, data, usage)
+}
+
+pub(super) unsafe fn BufferSubData(
+    target: GLenum,
+    offset: GLintptr,
+    size: GLsizeiptr,
+    data: *const c_void,
+) {
+    traced!(BufferSubData(
+        int(target),
+        int(offset as i64),
+        int(size as i64),
+        bytes(data, size as usize),
+    ));
+    gl_raw!(BufferSubData)(target, offset, size, data)
+}
+
+pub(super) unsafe fn Clear(mask: GLbitfield) {
+    traced!(Clear(int(mask)));
+    gl_raw!(Clear)(mask)
+}
+
+pub(super) unsafe fn ClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+    traced!(ClearColor(
+        Value::Float(red),
+        Value::Float(green),
+        Value::Float(blue),
+        Value::Float(alpha),
+    ));
+    gl_raw!(ClearColor)(red, green, blue, alpha)
+}
+
+pub(super) unsafe fn ColorMask(
+    red: GLboolean,
+    green: GLboolean,
+    blue: GLboolean,
+    alpha: GLboolean,
+) {
+    traced!(ColorMask(int(red), int(green), int(blue), int(alpha)));
+    gl_raw!(ColorMask)(red, green, blue, alpha)
+}
+
+pub(super) unsafe fn CompileShader(shader: GLuint) {
+    traced!(CompileShader(int(shader)));
+    gl_raw!(CompileShader)(shader)
+}
+
+pub(super) unsafe fn CreateProgram() -> GLuint {
+    let id = gl_raw!(CreateProgram)();
+    traced!(CreateProgram(Value::Ids(vec![id])));
+    id
+}
+
+pub(super) unsafe fn CreateShader(shader_type: GLenum) -> GLuint {
+    let id = gl_raw!(CreateShader)(shader_type);
+    traced!(CreateShader(int(shader_type), Value::Ids(vec![id])));
+    id
+}
+
+pub(super) unsafe fn CullFace(mode: GLenum) {
+    traced!(CullFace(int(mode)));
+    gl_raw!(CullFace)(mode)
+}
+
+pub(super) unsafe fn DeleteBuffers(n: GLsizei, buffers: *const GLuint) {
+    let deleted = std::slice::from_raw_parts(buffers, n.max(0) as usize);
+    BOUND_BUFFERS.with(|bound| {
+        bound
+            .borrow_mut()
+            .retain(|_, buffer| !deleted.contains(buffer))
+    });
+    MAPPED.with(|mapped| {
+        mapped
+            .borrow_mut()
+            .retain(|buffer, _| !deleted.contains(buffer))
+    });
+    traced!(DeleteBuffers(int(n), ids(n, buffers)));
+    gl_raw!(DeleteBuffers)(n, buffers)
+}
+
+pub(super) unsafe fn DeleteFramebuffers(n: GLsizei, framebuffers: *const GLuint) {
+    traced!(DeleteFramebuffers(int(n), ids(n, framebuffers)));
+    gl_raw!(DeleteFramebuffers)(n, framebuffers)
+}
+
+pub(super) unsafe fn DeleteProgram(program: GLuint) {
+    traced!(DeleteProgram(int(program)));
+    gl_raw!(DeleteProgram)(program)
+}
+
+pub(super) unsafe fn DeleteRenderbuffers(n: GLsizei, renderbuffers: *const GLuint) {
+    traced!(DeleteRenderbuffers(int(n), ids(n, renderbuffers)));
+    gl_raw!(DeleteRenderbuffers)(n, renderbuffers)
+}
+
+pub(super) unsafe fn DeleteShader(shader: GLuint) {
+    traced!(DeleteShader(int(shader)));
+    gl_raw!(DeleteShader)(shader)
+}
+
+pub(super) unsafe fn DeleteTextures(n: GLsizei, textures: *const GLuint) {
+    traced!(DeleteTextures(int(n), ids(n, textures)));
+    gl_raw!(DeleteTextures)(n, textures)
+}
+
+pub(super) unsafe fn DeleteVertexArrays(n: GLsizei, arrays: *const GLuint) {
+    traced!(DeleteVertexArrays(int(n), ids(n, arrays)));
+    gl_raw!(DeleteVertexArrays)(n, arrays)
+}
+
+pub(super) unsafe fn DepthFunc(func: GLenum) {
+    traced!(DepthFunc(int(func)));
+    gl_raw!(DepthFunc)(func)
+}
+
+pub(super) unsafe fn DepthMask(flag: GLboolean) {
+    traced!(DepthMask(int(flag)));
+    gl_raw!(DepthMask)(flag)
+}
+
+pub(super) unsafe fn DetachShader(program: GLuint, shader: GLuint) {
+    traced!(DetachShader(int(program), int(shader)));
+    gl_raw!(DetachShader)(program, shader)
+}
+
+pub(super) unsafe fn Disable(cap: GLenum) {
+    traced!(Disable(int(cap)));
+    gl_raw!(Disable)(cap)
+}
+
+pub(super) unsafe fn DisableVertexAttribArray(index: GLuint) {
+    traced!(DisableVertexAttribArray(int(index)));
+    gl_raw!(DisableVertexAttribArray)(index)
+}
+
+pub(super) unsafe fn DrawArrays(mode: GLenum, first: GLint, count: GLsizei) {
+    traced!(DrawArrays(int(mode), int(first), int(count)));
+    gl_raw!(DrawArrays)(mode, first, count)
+}
+
+pub(super) unsafe fn DrawArraysInstanced(
+    mode: GLenum,
+    first: GLint,
+    count: GLsizei,
+    instance_count: GLsizei,
+) {
+    traced!(DrawArraysInstanced(
+        int(mode),
+        int(first),
+        int(count),
+        int(instance_count),
+    ));
+    gl_raw!(DrawArraysInstanced)(mode, first, count, instance_count)
+}
+
+pub(super) unsafe fn DrawBuffers(n: GLsizei, buffers: *const GLenum) {
+    traced!(DrawBuffers(int(n), ids(n, buffers)));
+    gl_raw!(DrawBuffers)(n, buffers)
+}
+
+pub(super) unsafe fn DrawElements(
+    mode: GLenum,
+    count: GLsizei,
+    index_type: GLenum,
+    indices: *const c_void,
+) {
+    traced!(DrawElements(
+        int(mode),
+        int(count),
+        int(index_type),
+        Value::Pointer(indices as u64),
+    ));
+    gl_raw!(DrawElements)(mode, count, index_type, indices)
+}
+
+pub(super) unsafe fn DrawElementsInstanced(
+    mode: GLenum,
+    count: GLsizei,
+    index_type: GLenum,
+    indices: *const c_void,
+    instance_count: GLsizei,
+) {
+    traced!(DrawElementsInstanced(
+        int(mode),
+        int(count),
+        int(index_type),
+        Value::Pointer(indices as u64),
+        int(instance_count),
+    ));
+    gl_raw!(DrawElementsInstanced)(mode, count, index_type, indices, instance_count)
+}
+
+pub(super) unsafe fn Enable(cap: GLenum) {
+    traced!(Enable(int(cap)));
+    gl_raw!(Enable)(cap)
+}
+
+pub(super) unsafe fn EnableVertexAttribArray(index: GLuint) {
+    traced!(EnableVertexAttribArray(int(index)));
+    gl_raw!(EnableVertexAttribArray)(index)
+}
+
+pub(super) unsafe fn FlushMappedBufferRange(target: GLenum, offset: GLintptr, length: GLsizeiptr) {
+    traced!(FlushMappedBufferRange(
+        int(target),
+        int(offset as i64),
+        int(length as i64),
+    ));
+    gl_raw!(FlushMappedBufferRange)(target, offset, length)
+}
+
+pub(super) unsafe fn FramebufferRenderbuffer(
+    target: GLenum,
+    attachment: GLenum,
+    renderbuffer_target: GLenum,
+    renderbuffer: GLuint,
+) {
+    traced!(FramebufferRenderbuffer(
+        int(target),
+        int(attachment),
+        int(renderbuffer_target),
+        int(renderbuffer),
+    ));
+    gl_raw!(FramebufferRenderbuffer)(target, attachment, renderbuffer_target, renderbuffer)
+}
+
+pub(super) unsafe fn FramebufferTexture2D(
+    target: GLenum,
+    attachment: GLenum,
+    texture_target: GLenum,
+    texture: GLuint,
+    level: GLint,
+) {
+    traced!(FramebufferTexture2D(
+        int(target),
+        int(attachment),
+        int(texture_target),
+        int(texture),
+        int(level),
+    ));
+    gl_raw!(FramebufferTexture2D)(target, attachment, texture_target, texture, level)
+}
+
+pub(super) unsafe fn FrontFace(mode: GLenum) {
+    traced!(FrontFace(int(mode)));
+    gl_raw!(FrontFace)(mode)
+}
+
+pub(super) unsafe fn GenBuffers(n: GLsizei, buffers: *mut GLuint) {
+    gl_raw!(GenBuffers)(n, buffers);
+    traced!(GenBuffers(int(n), ids(n, buffers)));
+}
+
+pub(super) unsafe fn GenFramebuffers(n: GLsizei, framebuffers: *mut GLuint) {
+    gl_raw!(GenFramebuffers)(n, framebuffers);
+    traced!(GenFramebuffers(int(n), ids(n, framebuffers)));
+}
+
+pub(super) unsafe fn GenRenderbuffers(n: GLsizei, renderbuffers: *mut GLuint) {
+    gl_raw!(GenRenderbuffers)(n, renderbuffers);
+    traced!(GenRenderbuffers(int(n), ids(n, renderbuffers)));
+}
+
+pub(super) unsafe fn GenTextures(n: GLsizei, textures: *mut GLuint) {
+    gl_raw!(GenTextures)(n, textures);
+    traced!(GenTextures(int(n), ids(n, textures)));
+}
+
+pub(super) unsafe fn GenVertexArrays(n: GLsizei, arrays: *mut GLuint) {
+    gl_raw!(GenVertexArrays)(n, arrays);
+    traced!(GenVertexArrays(int(n), ids(n, arrays)));
+}
+
+pub(super) unsafe fn GenerateMipmap(target: GLenum) {
+    traced!(GenerateMipmap(int(target)));
+    gl_raw!(GenerateMipmap)(target)
+}
+
+pub(super) unsafe fn LinkProgram(program: GLuint) {
+    traced!(LinkProgram(int(program)));
+    gl_raw!(LinkProgram)(program)
+}
+
+pub(super) unsafe fn MapBufferRange(
+    target: GLenum,
+    offset: GLintptr,
+    length: GLsizeiptr,
+    access: GLbitfield,
+) -> *mut c_void {
+    traced!(MapBufferRange(
+        int(target),
+        int(offset as i64),
+        int(length as i64),
+        int(access),
+    ));
+    let data = gl_raw!(MapBufferRange)(target, offset, length, access);
+    if !data.is_null() {
+        let buffer = bound_buffer(target);
+        MAPPED.with(|mapped| {
+            mapped
+                .borrow_mut()
+                .insert(buffer, (data as *const u8, length as usize, access))
+        });
+    }
+    data
+}
+
+pub(super) unsafe fn PixelStorei(name: GLenum, param: GLint) {
+    traced!(PixelStorei(int(name), int(param)));
+    gl_raw!(PixelStorei)(name, param)
+}
+
+pub(super) unsafe fn PolygonMode(face: GLenum, mode: GLenum) {
+    traced!(PolygonMode(int(face), int(mode)));
+    gl_raw!(PolygonMode)(face, mode)
+}
+
+pub(super) unsafe fn ReadBuffer(source: GLenum) {
+    traced!(ReadBuffer(int(source)));
+    gl_raw!(ReadBuffer)(source)
+}
+
+pub(super) unsafe fn ReadPixels(
+    x: GLint,
+    y: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    data_type: GLenum,
+    pixels: *mut c_void,
+) {
+    traced!(ReadPixels(
+        int(x),
+        int(y),
+        int(width),
+        int(height),
+        int(format),
+        int(data_type),
+    ));
+    gl_raw!(ReadPixels)(x, y, width, height, format, data_type, pixels)
+}
+
+pub(super) unsafe fn RenderbufferStorage(
+    target: GLenum,
+    internal_format: GLenum,
+    width: GLsizei,
+    height: GLsizei,
+) {
+    traced!(RenderbufferStorage(
+        int(target),
+        int(internal_format),
+        int(width),
+        int(height),
+    ));
+    gl_raw!(RenderbufferStorage)(target, internal_format, width, height)
+}
+
+pub(super) unsafe fn Scissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    traced!(Scissor(int(x), int(y), int(width), int(height)));
+    gl_raw!(Scissor)(x, y, width, height)
+}
+
+/// Captures the sources joined together.
+pub(super) unsafe fn ShaderSource(
+    shader: GLuint,
+    count: GLsizei,
+    string: *const *const GLchar,
+    length: *const GLint,
+) {
+    traced!(ShaderSource(
+        int(shader),
+        Value::Str(
+            std::slice::from_raw_parts(string, count.max(0) as usize)
+                .iter()
+                .map(|&source| CStr::from_ptr(source).to_string_lossy())
+                .collect(),
+        ),
+    ));
+    gl_raw!(ShaderSource)(shader, count, string, length)
+}
+
+pub(super) unsafe fn StencilFunc(func: GLenum, reference: GLint, mask: GLuint) {
+    traced!(StencilFunc(int(func), int(reference), int(mask)));
+    gl_raw!(StencilFunc)(func, reference, mask)
+}
+
+pub(super) unsafe fn StencilMask(mask: GLuint) {
+    traced!(StencilMask(int(mask)));
+    gl_raw!(StencilMask)(mask)
+}
+
+pub(super) unsafe fn StencilOp(stencil_fail: GLenum, depth_fail: GLenum, pass: GLenum) {
+    traced!(StencilOp(int(stencil_fail), int(depth_fail), int(pass)));
+    gl_raw!(StencilOp)(stencil_fail, depth_fail, pass)
+}
+
+pub(super) unsafe fn TexImage2D(
+    target: GLenum,
+    level: GLint,
+    internal_format: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    border: GLint,
+    format: GLenum,
+    data_type: GLenum,
+    pixels: *const c_void,
+) {
+    traced!(TexImage2D(
+        int(target),
+        int(level),
+        int(internal_format),
+        int(width),
+        int(height),
+        int(border),
+        int(format),
+        int(data_type),
+        bytes(
+            pixels,
+            pixels_size(width, height, format, data_type, 1).unwrap_or(0),
+        ),
+    ));
+    gl_raw!(TexImage2D)(
+        target,
+        level,
+        internal_format,
+        width,
+        height,
+        border,
+        format,
+        data_type,
+        pixels,
+    )
+}
+
+pub(super) unsafe fn TexParameteri(target: GLenum, name: GLenum, param: GLint) {
+    traced!(TexParameteri(int(target), int(name), int(param)));
+    gl_raw!(TexParameteri)(target, name, param)
+}
+
+pub(super) unsafe fn TexSubImage2D(
+    target: GLenum,
+    level: GLint,
+    x: GLint,
+    y: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    data_type: GLenum,
+    pixels: *const c_void,
+) {
+    traced!(TexSubImage2D(
+        int(target),
+        int(level),
+        int(x),
+        int(y),
+        int(width),
+        int(height),
+        int(format),
+        int(data_type),
+        bytes(
+            pixels,
+            pixels_size(width, height, format, data_type, 1).unwrap_or(0),
+        ),
+    ));
+    gl_raw!(TexSubImage2D)(
+        target, level, x, y, width, height, format, data_type, pixels,
+    )
+}
+
+pub(super) unsafe fn Uniform1f(location: GLint, v0: GLfloat) {
+    traced!(Uniform1f(int(location), Value::Float(v0)));
+    gl_raw!(Uniform1f)(location, v0)
+}
+
+pub(super) unsafe fn Uniform1i(location: GLint, v0: GLint) {
+    traced!(Uniform1i(int(location), int(v0)));
+    gl_raw!(Uniform1i)(location, v0)
+}
+
+pub(super) unsafe fn Uniform2f(location: GLint, v0: GLfloat, v1: GLfloat) {
+    traced!(Uniform2f(int(location), Value::Float(v0), Value::Float(v1)));
+    gl_raw!(Uniform2f)(location, v0, v1)
+}
+
+pub(super) unsafe fn Uniform3f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
+    traced!(Uniform3f(
+        int(location),
+        Value::Float(v0),
+        Value::Float(v1),
+        Value::Float(v2),
+    ));
+    gl_raw!(Uniform3f)(location, v0, v1, v2)
+}
+
+pub(super) unsafe fn Uniform4f(
+    location: GLint,
+    v0: GLfloat,
+    v1: GLfloat,
+    v2: GLfloat,
+    v3: GLfloat,
+) {
+    traced!(Uniform4f(
+        int(location),
+        Value::Float(v0),
+        Value::Float(v1),
+        Value::Float(v2),
+        Value::Float(v3),
+    ));
+    gl_raw!(Uniform4f)(location, v0, v1, v2, v3)
+}
+
+pub(super) unsafe fn UniformMatrix4fv(
+    location: GLint,
+    count: GLsizei,
+    transpose: GLboolean,
+    value: *const GLfloat,
+) {
+    traced!(UniformMatrix4fv(
+        int(location),
+        int(count),
+        int(transpose),
+        bytes(
+            value as *const c_void,
+            count.max(0) as usize * 16 * std::mem::size_of::<GLfloat>(),
+        ),
+    ));
+    gl_raw!(UniformMatrix4fv)(location, count, transpose, value)
+}
+
+/// Captures what was written to the mapped range, which is all of it as far
+/// as the trace knows.
+pub(super) unsafe fn UnmapBuffer(target: GLenum) -> GLboolean {
+    let buffer = bound_buffer(target);
+    let range = MAPPED.with(|mapped| mapped.borrow_mut().remove(&buffer));
+    traced!(UnmapBuffer(
+        int(target),
+        match range {
+            Some((data, length, access)) if access & raw_gl::MAP_WRITE_BIT != 0 => {
+                bytes(data as *const c_void, length)
+            }
+            _ => Value::Pointer(0),
+        },
+    ));
+    gl_raw!(UnmapBuffer)(target)
+}
+
+pub(super) unsafe fn UseProgram(program: GLuint) {
+    traced!(UseProgram(int(program)));
+    gl_raw!(UseProgram)(program)
+}
+
+pub(super) unsafe fn VertexAttribDivisor(index: GLuint, divisor: GLuint) {
+    traced!(VertexAttribDivisor(int(index), int(divisor)));
+    gl_raw!(VertexAttribDivisor)(index, divisor)
+}
+
+pub(super) unsafe fn VertexAttribPointer(
+    index: GLuint,
+    size: GLint,
+    data_type: GLenum,
+    normalized: GLboolean,
+    stride: GLsizei,
+    pointer: *const c_void,
+) {
+    traced!(VertexAttribPointer(
+        int(index),
+        int(size),
+        int(data_type),
+        int(normalized),
+        int(stride),
+        Value::Pointer(pointer as u64),
+    ));
+    gl_raw!(VertexAttribPointer)(index, size, data_type, normalized, stride, pointer)
+}
+
+pub(super) unsafe fn Viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    traced!(Viewport(int(x), int(y), int(width), int(height)));
+    gl_raw!(Viewport)(x, y, width, height)
+}
@@ -0,0 +1,97 @@
+use crate::graphics::{
+    gl, ContextId, Image, RenderState, WindowBackend, WindowBuilder, WindowError,
+};
+
+/// An OpenGL context backed by a hidden window or an offscreen surface, for
+/// rendering where nothing should be shown on screen. Every `graphics` type
+/// works with it exactly as with a `Window`.
+///
+/// With `WindowBuilder::software_rendering` the context runs on Mesa's
+/// llvmpipe, which needs no GPU. The windowing system still needs a display
+/// connection to create the hidden window, so on machines without one use
+/// `offscreen()` with the `egl` feature, run under a virtual display such as
+/// `xvfb-run`, or use `ContextApi::OsMesa` if GLFW was built with it.
+pub struct HeadlessContext {
+    width: u32,
+    height: u32,
+
+    context: ContextId,
+    backend: Box<dyn WindowBackend>,
+}
+
+impl HeadlessContext {
+    /// Creates a software rendered OpenGL 3.3 core context with a default
+    /// framebuffer of `width` by `height` pixels. This briefly sets an
+    /// environment variable, see `WindowBuilder::software_rendering`.
+    pub fn new(width: u32, height: u32) -> Result<Self, WindowError> {
+        WindowBuilder::new(width, height, "crean headless")
+            .software_rendering(true)
+            .build_headless()
+    }
+
+    /// Creates a software rendered OpenGL 3.3 core context on Mesa's
+    /// surfaceless EGL platform, with an offscreen default framebuffer of
+    /// `width` by `height` pixels. It needs no display connection. This
+    /// briefly sets an environment variable, see
+    /// `WindowBuilder::software_rendering`.
+    #[cfg(feature = "egl")]
+    pub fn offscreen(width: u32, height: u32) -> Result<Self, WindowError> {
+        WindowBuilder::new(width, height, "crean offscreen")
+            .software_rendering(true)
+            .build_offscreen()
+    }
+
+    pub(crate) fn from_backend(mut backend: Box<dyn WindowBackend>, debug_context: bool) -> Self {
+        let context = ContextId::next();
+        context.make_current(&mut *backend);
+        gl::load_with(|s| backend.get_proc_address(s));
+        if debug_context && !gl::enable_debug_output() {
+            log::warn!("the OpenGL context does not support debug output");
+        }
+        RenderState::default().apply();
+
+        let (width, height) = backend.framebuffer_size();
+        gl::set_view_port(0, 0, width, height);
+
+        HeadlessContext {
+            width,
+            height,
+            context,
+            backend,
+        }
+    }
+
+    #[inline]
+    pub fn context_id(&self) -> ContextId {
+        self.context
+    }
+
+    /// Makes this context current on the calling thread.
+    #[inline]
+    pub fn make_current(&mut self) {
+        self.context.make_current(&mut *self.backend);
+    }
+
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        self.context.is_current()
+    }
+
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Reads back what has been rendered to the default framebuffer.
+    pub fn read_pixels(&mut self) -> Image {
+        self.make_current();
+        gl::unbind_framebuffer(gl::FramebufferTarget::Read);
+        Image::read_framebuffer(self.width, self.height)
+    }
+}
+
+impl Drop for HeadlessContext {
+    fn drop(&mut self) {
+        self.context.release();
+    }
+}
@@ -0,0 +1,226 @@
+use crate::graphics::{gl, TextureFormat};
+#[cfg(feature = "png")]
+use std::{fs::File, io, io::BufReader, io::BufWriter, io::Read, io::Write, path::Path};
+#[cfg(feature = "png")]
+use thiserror::Error;
+
+#[cfg(feature = "png")]
+#[derive(Error, Debug)]
+pub enum ImageError {
+    #[error("failed to access the image file")]
+    Io(#[from] io::Error),
+    #[error("failed to decode the png: {0}")]
+    Decode(#[from] png::DecodingError),
+    #[error("failed to encode the png: {0}")]
+    Encode(#[from] png::EncodingError),
+}
+
+/// An image with 8 bit red, green, blue and alpha channels, stored row by row
+/// starting at the top-left corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// The size in bytes of the pixel data of a `width` by `height` image, or
+    /// None if it overflows.
+    fn data_len(width: u32, height: u32) -> Option<usize> {
+        (width as usize)
+            .checked_mul(height as usize)?
+            .checked_mul(4)
+    }
+
+    /// Creates a transparent black image.
+    ///
+    /// Panics if the size of the pixel data overflows `usize`.
+    pub fn new(width: u32, height: u32) -> Self {
+        let len = Image::data_len(width, height).expect("image dimensions overflow");
+        Image {
+            width,
+            height,
+            pixels: vec![0; len],
+        }
+    }
+
+    /// Wraps RGBA pixel data. Returns None if `pixels` does not hold exactly
+    /// `width * height * 4` bytes.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Option<Self> {
+        if Some(pixels.len()) == Image::data_len(width, height) {
+            Some(Image {
+                width,
+                height,
+                pixels,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Loads a PNG file, converting it to RGBA.
+    #[cfg(feature = "png")]
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        Image::read_png(BufReader::new(File::open(path)?))
+    }
+
+    /// Decodes a PNG image, converting it to RGBA.
+    #[cfg(feature = "png")]
+    pub fn read_png(reader: impl Read) -> Result<Self, ImageError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            // Indexed images are expanded to RGB or RGBA by the decoder.
+            png::ColorType::Grayscale | png::ColorType::Indexed => {
+                buffer.iter().flat_map(|&p| [p, p, p, 255]).collect()
+            }
+        };
+
+        Ok(Image {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    /// Writes the image to a PNG file, replacing it if it exists.
+    #[cfg(feature = "png")]
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+
+    /// Encodes the image as an RGBA PNG.
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, writer: impl Write) -> Result<(), ImageError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Reads the color of the bound read framebuffer, which OpenGL stores
+    /// starting at the bottom row.
+    pub(crate) fn read_framebuffer(width: u32, height: u32) -> Self {
+        let mut image = Image::new(width, height);
+        gl::read_pixels(0, 0, width, height, TextureFormat::Rgba8, &mut image.pixels);
+        image.flip_vertically();
+        image
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    #[inline]
+    pub fn pixels_mut(&mut self) -> &mut [u8] {
+        &mut self.pixels
+    }
+
+    #[inline]
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Panics if `(x, y)` is outside of the image.
+    #[inline]
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.index(x, y);
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    /// Panics if `(x, y)` is outside of the image.
+    #[inline]
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let i = self.index(x, y);
+        self.pixels[i..i + 4].copy_from_slice(&rgba);
+    }
+
+    /// Swaps the rows of the image top to bottom.
+    pub fn flip_vertically(&mut self) {
+        let row_len = self.width as usize * 4;
+        let height = self.height as usize;
+        for y in 0..height / 2 {
+            let (top, bottom) = self.pixels.split_at_mut((height - 1 - y) * row_len);
+            top[y * row_len..(y + 1) * row_len].swap_with_slice(&mut bottom[..row_len]);
+        }
+    }
+
+    #[inline]
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) is outside of a {}x{} image",
+            x,
+            y,
+            self.width,
+            self.height,
+        );
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    /// Packs the pixels the way GLFW expects cursor and icon images.
+    #[cfg(feature = "glfw")]
+    pub(crate) fn to_glfw(&self) -> glfw::PixelImage {
+        glfw::PixelImage {
+            width: self.width,
+            height: self.height,
+            pixels: self
+                .pixels
+                .chunks_exact(4)
+                .map(|p| u32::from_ne_bytes([p[0], p[1], p[2], p[3]]))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "outside of a 2x2 image")]
+    fn pixels_past_the_row_panic() {
+        Image::new(2, 2).pixel(2, 0);
+    }
+}
@@ -1,13 +1,43 @@
+mod backend;
+pub use self::backend::*;
+
 mod buffer;
 pub use self::buffer::*;
 
+mod cursor;
+pub use self::cursor::*;
+
+mod event;
+pub use self::event::*;
+
+mod framebuffer;
+pub use self::framebuffer::*;
+
 pub mod gl;
 
+mod headless;
+pub use self::headless::*;
+
+mod image;
+pub use self::image::*;
+
+mod platform;
+pub use self::platform::*;
+
+mod render_state;
+pub use self::render_state::*;
+
 mod shader;
 pub use self::shader::*;
 
+mod texture;
+pub use self::texture::*;
+
 mod vertex_array;
 pub use self::vertex_array::*;
 
 mod window;
 pub use self::window::*;
+
+mod window_builder;
+pub use self::window_builder::*;
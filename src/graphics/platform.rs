@@ -0,0 +1,183 @@
+#[cfg(feature = "glfw")]
+use crate::graphics::GlfwPlatform;
+#[cfg(feature = "winit")]
+use crate::graphics::WinitPlatform;
+use crate::graphics::{
+    gl, Backend, EventLoop, FullscreenMode, HeadlessContext, PlatformBackend, RenderState, Window,
+    WindowBackend, WindowBuilder, WindowError,
+};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+thread_local! {
+    static CURRENT_CONTEXT: Cell<Option<ContextId>> = const { Cell::new(None) };
+}
+
+/// Identifies the OpenGL context of a `Window` or `HeadlessContext`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ContextId(u32);
+
+impl ContextId {
+    pub(crate) fn next() -> Self {
+        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+        ContextId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Makes `backend`'s context current on the calling thread, unless it
+    /// already is.
+    pub(crate) fn make_current(self, backend: &mut dyn WindowBackend) {
+        if !self.is_current() {
+            backend.make_current();
+            CURRENT_CONTEXT.with(|current| current.set(Some(self)));
+        }
+    }
+
+    /// The context current on the calling thread, if any.
+    #[inline]
+    pub(crate) fn current() -> Option<ContextId> {
+        CURRENT_CONTEXT.with(|current| current.get())
+    }
+
+    #[inline]
+    pub(crate) fn is_current(self) -> bool {
+        CURRENT_CONTEXT.with(|current| current.get() == Some(self))
+    }
+
+    /// Forgets the context if it is current, along with its cached render
+    /// state and bindings, for when its window is destroyed.
+    pub(crate) fn release(self) {
+        if self.is_current() {
+            CURRENT_CONTEXT.with(|current| current.set(None));
+        }
+        RenderState::release(self);
+        gl::release_bindings(self);
+    }
+}
+
+/// Owns the windowing system and creates windows from it.
+///
+/// `Window::new` and `WindowBuilder::build` set up a platform of their own,
+/// which is enough for a single window. Use a `Platform` directly to open
+/// several windows, optionally sharing textures, buffers and shaders between
+/// their OpenGL contexts.
+///
+/// ```ignore
+/// let mut platform = Platform::new()?;
+/// let (main, mut main_events) = platform.create_window(WindowBuilder::new(1280, 720, "Editor"))?;
+/// let (preview, mut preview_events) =
+///     platform.create_shared_window(WindowBuilder::new(640, 360, "Preview"), &main)?;
+/// ```
+///
+/// Windows keep the platform alive, so it may be dropped before them.
+pub struct Platform {
+    backend: Box<dyn PlatformBackend>,
+}
+
+impl Platform {
+    /// Initializes the default backend. Must be called on the main thread.
+    ///
+    /// Returns Err if the windowing system is unavailable, for example
+    /// without a display.
+    pub fn new() -> Result<Self, WindowError> {
+        Platform::with_backend(Backend::default())
+    }
+
+    /// Returns Err under the same conditions as `new()`.
+    pub fn with_backend(backend: Backend) -> Result<Self, WindowError> {
+        let backend: Box<dyn PlatformBackend> = match backend {
+            #[cfg(feature = "glfw")]
+            Backend::Glfw => Box::new(GlfwPlatform::new()?),
+            #[cfg(feature = "winit")]
+            Backend::Winit => Box::new(WinitPlatform::new()?),
+        };
+        Ok(Platform { backend })
+    }
+
+    /// Creates a window with its own OpenGL context and makes that context
+    /// current.
+    pub fn create_window(
+        &mut self,
+        builder: WindowBuilder,
+    ) -> Result<(Window, EventLoop), WindowError> {
+        self.window(builder, None)
+    }
+
+    /// Creates a window whose OpenGL context shares objects such as buffers,
+    /// textures and shader programs with `share`'s context, and makes it
+    /// current. Container objects like vertex arrays and framebuffers are
+    /// never shared and must be created per context.
+    ///
+    /// Returns Err if `share` was created by a platform with another backend.
+    pub fn create_shared_window(
+        &mut self,
+        builder: WindowBuilder,
+        share: &Window,
+    ) -> Result<(Window, EventLoop), WindowError> {
+        self.window(builder, Some(share))
+    }
+
+    /// Creates a hidden context, optionally sharing objects with `share`.
+    /// Useful for loading resources for a window on another context.
+    pub fn create_headless(
+        &mut self,
+        mut builder: WindowBuilder,
+        share: Option<&Window>,
+    ) -> Result<HeadlessContext, WindowError> {
+        builder.visible = false;
+        builder.fullscreen = None;
+
+        let backend = {
+            let _creation = builder.begin_context_creation();
+            self.backend
+                .create_window(&builder, share.map(Window::backend))?
+        };
+        Ok(HeadlessContext::from_backend(
+            backend,
+            builder.debug_context,
+        ))
+    }
+
+    fn window(
+        &mut self,
+        builder: WindowBuilder,
+        share: Option<&Window>,
+    ) -> Result<(Window, EventLoop), WindowError> {
+        let backend = {
+            let _creation = builder.begin_context_creation();
+            self.backend
+                .create_window(&builder, share.map(Window::backend))?
+        };
+        let fullscreen = builder
+            .fullscreen
+            .map_or(FullscreenMode::Windowed, FullscreenMode::Exclusive);
+
+        let window = Window::from_backend(
+            backend,
+            builder.title,
+            fullscreen,
+            builder.swap_interval,
+            builder.debug_context,
+        );
+        Ok((window, EventLoop::new()))
+    }
+
+    /// The context current on the calling thread, if it was made current
+    /// through this crate.
+    pub fn current_context(&self) -> Option<ContextId> {
+        CURRENT_CONTEXT.with(|current| current.get())
+    }
+
+    /// Leaves the calling thread without a current context.
+    pub fn detach_current_context(&mut self) {
+        self.backend.detach_current_context();
+        CURRENT_CONTEXT.with(|current| current.set(None));
+    }
+
+    /// Processes pending events for every window. Each window's `EventLoop`
+    /// does this as well, so this is only needed when no event loop is run,
+    /// for example while a modal operation blocks.
+    #[inline]
+    pub fn poll_events(&mut self) {
+        self.backend.poll_events();
+    }
+}
@@ -0,0 +1,329 @@
+use crate::graphics::{gl, ContextId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+pub use crate::graphics::gl::{
+    BlendEquation, BlendFactor, CompareFunc, Face, PolygonMode, StencilOp, Winding,
+};
+
+/// How fragments are blended into the framebuffer, as `source * factor
+/// <equation> destination * factor`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BlendState {
+    pub source_color: BlendFactor,
+    pub destination_color: BlendFactor,
+    pub source_alpha: BlendFactor,
+    pub destination_alpha: BlendFactor,
+    pub color_equation: BlendEquation,
+    pub alpha_equation: BlendEquation,
+    /// The color used by the `Constant*` factors.
+    pub constant: [f32; 4],
+}
+
+impl BlendState {
+    /// Standard transparency, for colors that aren't premultiplied by alpha.
+    pub const ALPHA: BlendState =
+        BlendState::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha);
+    /// Transparency for colors premultiplied by alpha.
+    pub const PREMULTIPLIED_ALPHA: BlendState =
+        BlendState::new(BlendFactor::One, BlendFactor::OneMinusSrcAlpha);
+    /// Adds the fragment onto the framebuffer, for lights and particles.
+    pub const ADDITIVE: BlendState = BlendState::new(BlendFactor::One, BlendFactor::One);
+    /// Multiplies the framebuffer by the fragment.
+    pub const MULTIPLY: BlendState = BlendState::new(BlendFactor::DstColor, BlendFactor::Zero);
+
+    /// Blends color and alpha alike, adding the weighted colors.
+    pub const fn new(source: BlendFactor, destination: BlendFactor) -> Self {
+        BlendState {
+            source_color: source,
+            destination_color: destination,
+            source_alpha: source,
+            destination_alpha: destination,
+            color_equation: BlendEquation::Add,
+            alpha_equation: BlendEquation::Add,
+            constant: [0.0; 4],
+        }
+    }
+
+    pub const fn with_equation(mut self, equation: BlendEquation) -> Self {
+        self.color_equation = equation;
+        self.alpha_equation = equation;
+        self
+    }
+}
+
+/// The stencil test, applied to both faces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StencilState {
+    pub func: CompareFunc,
+    pub reference: i32,
+    /// The bits of the reference and stored values that are compared.
+    pub read_mask: u32,
+    /// When the stencil test fails.
+    pub stencil_fail: StencilOp,
+    /// When the stencil test passes but the depth test fails.
+    pub depth_fail: StencilOp,
+    /// When both tests pass.
+    pub pass: StencilOp,
+}
+
+impl StencilState {
+    /// Passes where `func` holds for `reference`, leaving the buffer as is.
+    pub const fn test(func: CompareFunc, reference: i32) -> Self {
+        StencilState {
+            func,
+            reference,
+            read_mask: !0,
+            stencil_fail: StencilOp::Keep,
+            depth_fail: StencilOp::Keep,
+            pass: StencilOp::Keep,
+        }
+    }
+
+    /// Writes `reference` wherever a fragment is drawn, for masking later
+    /// draws with `test`.
+    pub const fn write(reference: i32) -> Self {
+        StencilState {
+            pass: StencilOp::Replace,
+            ..StencilState::test(CompareFunc::Always, reference)
+        }
+    }
+}
+
+/// The fixed-function state that draw calls render with.
+///
+/// Applying a state only issues the OpenGL calls for what differs from the
+/// state last applied to the current context. That cache only knows about
+/// states applied through `RenderState`, so call `RenderState::invalidate`
+/// after changing any of it through `gl` directly.
+///
+/// ```ignore
+/// let transparent = RenderState {
+///     blend: Some(BlendState::ALPHA),
+///     depth_write: false,
+///     ..RenderState::default()
+/// };
+/// transparent.apply();
+/// ```
+///
+/// Like in OpenGL, `depth_write`, `stencil_write_mask`, `color_mask` and
+/// `scissor` also limit what `gl::clear` clears. `Window::update` lifts them
+/// for its clear.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RenderState {
+    /// The depth test, or None to disable it.
+    pub depth_test: Option<CompareFunc>,
+    /// Whether fragments write their depth. Nothing is written while the
+    /// depth test is disabled.
+    pub depth_write: bool,
+    /// Blending, or None to overwrite the framebuffer.
+    pub blend: Option<BlendState>,
+    /// The faces to cull, or None to draw both.
+    pub cull_face: Option<Face>,
+    pub front_face: Winding,
+    /// The stencil test, or None to disable it.
+    pub stencil: Option<StencilState>,
+    /// The bits of the stencil buffer that can be written.
+    pub stencil_write_mask: u32,
+    /// Limits rendering to `(x, y, width, height)` in window coordinates.
+    pub scissor: Option<(u32, u32, u32, u32)>,
+    pub polygon_mode: PolygonMode,
+    /// Which of the red, green, blue and alpha channels can be written.
+    pub color_mask: [bool; 4],
+}
+
+thread_local! {
+    /// The state last applied to each context on this thread, keyed by None
+    /// when no context is current. None once it is unknown.
+    static APPLIED: RefCell<HashMap<Option<ContextId>, Option<RenderState>>> =
+        RefCell::new(HashMap::new());
+}
+
+impl RenderState {
+    /// The state of a new OpenGL context, with the depth test disabled.
+    pub const INITIAL: RenderState = RenderState {
+        depth_test: None,
+        depth_write: true,
+        blend: None,
+        cull_face: None,
+        front_face: Winding::CounterClockwise,
+        stencil: None,
+        stencil_write_mask: !0,
+        scissor: None,
+        polygon_mode: PolygonMode::Fill,
+        color_mask: [true; 4],
+    };
+
+    /// Makes this the state of the current context.
+    pub fn apply(&self) {
+        APPLIED.with(|applied| {
+            let mut applied = applied.borrow_mut();
+            let current = applied
+                .entry(ContextId::current())
+                .or_insert(Some(RenderState::INITIAL));
+            self.apply_changes(current.as_ref());
+            *current = Some(*self);
+        });
+    }
+
+    /// The state last applied to the current context, or None if it is
+    /// unknown after `invalidate`.
+    pub fn current() -> Option<RenderState> {
+        APPLIED.with(|applied| {
+            applied
+                .borrow()
+                .get(&ContextId::current())
+                .copied()
+                .unwrap_or(Some(RenderState::INITIAL))
+        })
+    }
+
+    /// Forgets the state applied to the current context, so that the next
+    /// `apply` sets all of it. For after changing the state through `gl` or
+    /// another library.
+    pub fn invalidate() {
+        APPLIED.with(|applied| applied.borrow_mut().insert(ContextId::current(), None));
+    }
+
+    /// Forgets a destroyed context.
+    pub(crate) fn release(context: ContextId) {
+        APPLIED.with(|applied| applied.borrow_mut().remove(&Some(context)));
+    }
+
+    /// Issues the calls for what differs from `current`, or for everything if
+    /// it is unknown.
+    fn apply_changes(&self, current: Option<&RenderState>) {
+        if differs(current, self, |state| state.depth_test) {
+            match self.depth_test {
+                Some(func) => {
+                    if was_disabled(current, |state| state.depth_test) {
+                        gl::enable(gl::Capability::DepthTest);
+                    }
+                    gl::set_depth_func(func);
+                }
+                None => gl::disable(gl::Capability::DepthTest),
+            }
+        }
+        if differs(current, self, |state| state.depth_write) {
+            gl::set_depth_mask(self.depth_write);
+        }
+
+        if differs(current, self, |state| state.blend) {
+            match self.blend {
+                Some(blend) => {
+                    if was_disabled(current, |state| state.blend) {
+                        gl::enable(gl::Capability::Blend);
+                    }
+                    gl::set_blend_func(
+                        blend.source_color,
+                        blend.destination_color,
+                        blend.source_alpha,
+                        blend.destination_alpha,
+                    );
+                    gl::set_blend_equation(blend.color_equation, blend.alpha_equation);
+                    let [red, green, blue, alpha] = blend.constant;
+                    gl::set_blend_color(red, green, blue, alpha);
+                }
+                None => gl::disable(gl::Capability::Blend),
+            }
+        }
+
+        if differs(current, self, |state| state.cull_face) {
+            match self.cull_face {
+                Some(face) => {
+                    if was_disabled(current, |state| state.cull_face) {
+                        gl::enable(gl::Capability::CullFace);
+                    }
+                    gl::set_cull_face(face);
+                }
+                None => gl::disable(gl::Capability::CullFace),
+            }
+        }
+        if differs(current, self, |state| state.front_face) {
+            gl::set_front_face(self.front_face);
+        }
+
+        if differs(current, self, |state| state.stencil) {
+            match self.stencil {
+                Some(stencil) => {
+                    if was_disabled(current, |state| state.stencil) {
+                        gl::enable(gl::Capability::StencilTest);
+                    }
+                    gl::set_stencil_func(stencil.func, stencil.reference, stencil.read_mask);
+                    gl::set_stencil_op(stencil.stencil_fail, stencil.depth_fail, stencil.pass);
+                }
+                None => gl::disable(gl::Capability::StencilTest),
+            }
+        }
+        if differs(current, self, |state| state.stencil_write_mask) {
+            gl::set_stencil_mask(self.stencil_write_mask);
+        }
+
+        if differs(current, self, |state| state.scissor) {
+            match self.scissor {
+                Some((x, y, width, height)) => {
+                    if was_disabled(current, |state| state.scissor) {
+                        gl::enable(gl::Capability::ScissorTest);
+                    }
+                    gl::set_scissor(x, y, width, height);
+                }
+                None => gl::disable(gl::Capability::ScissorTest),
+            }
+        }
+
+        if differs(current, self, |state| state.polygon_mode) {
+            gl::set_polygon_mode(self.polygon_mode);
+        }
+        if differs(current, self, |state| state.color_mask) {
+            let [red, green, blue, alpha] = self.color_mask;
+            gl::set_color_mask(red, green, blue, alpha);
+        }
+    }
+
+    /// This state without the write masks and scissor, which also limit
+    /// clears.
+    pub(crate) fn unmasked(self) -> RenderState {
+        RenderState {
+            depth_write: true,
+            stencil_write_mask: !0,
+            scissor: None,
+            color_mask: [true; 4],
+            ..self
+        }
+    }
+}
+
+/// Whether `field` differs between the states, or `current` is unknown.
+fn differs<T, F>(current: Option<&RenderState>, new: &RenderState, field: F) -> bool
+where
+    T: PartialEq,
+    F: Fn(&RenderState) -> T,
+{
+    match current {
+        Some(current) => field(current) != field(new),
+        None => true,
+    }
+}
+
+/// Whether the capability `field` configures is disabled in `current`, or
+/// `current` is unknown.
+fn was_disabled<T, F>(current: Option<&RenderState>, field: F) -> bool
+where
+    F: Fn(&RenderState) -> Option<T>,
+{
+    match current {
+        Some(current) => field(current).is_none(),
+        None => true,
+    }
+}
+
+impl Default for RenderState {
+    /// The initial state with the depth test enabled, drawing the nearest
+    /// fragments.
+    fn default() -> Self {
+        RenderState {
+            depth_test: Some(CompareFunc::Less),
+            ..RenderState::INITIAL
+        }
+    }
+}
@@ -0,0 +1,220 @@
+pub use crate::graphics::gl::{PixelComponent, Sampler, TextureFilter, TextureFormat, TextureWrap};
+#[cfg(feature = "png")]
+use crate::graphics::ImageError;
+use crate::graphics::{gl, Image};
+#[cfg(feature = "png")]
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TextureError {
+    #[error("expected {expected} pixel components but got {actual}")]
+    InvalidDataLength { expected: usize, actual: usize },
+    #[error("{0:?} textures can not be uploaded from this pixel type")]
+    FormatMismatch(TextureFormat),
+    #[error("the region does not fit inside the texture")]
+    OutOfBounds,
+    #[cfg(feature = "png")]
+    #[error("failed to load the image: {0}")]
+    Image(#[from] ImageError),
+}
+
+/// A two dimensional texture. The first row of pixel data is at the bottom of
+/// the texture, at a t texture coordinate of 0.
+///
+/// New textures are sampled linearly without mipmaps and clamp to their edges.
+#[derive(Debug)]
+pub struct Texture2D {
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+
+    texture_id: gl::TextureId,
+}
+
+impl Texture2D {
+    /// Creates a texture with uninitialized contents, to be rendered into or
+    /// uploaded to later.
+    pub fn new(width: u32, height: u32, format: TextureFormat) -> Self {
+        let texture = Texture2D::create(width, height, format);
+        gl::allocate_texture_2d(format, width, height);
+        gl::unbind_texture_2d();
+        texture
+    }
+
+    /// Creates a texture filled with `pixels`, which must hold a value of the
+    /// format's type for every channel of every pixel.
+    pub fn from_pixels<T: PixelComponent>(
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[T],
+    ) -> Result<Self, TextureError> {
+        check_pixels(width, height, format, pixels)?;
+
+        let texture = Texture2D::create(width, height, format);
+        gl::texture_image_2d(format, width, height, pixels);
+        gl::unbind_texture_2d();
+        Ok(texture)
+    }
+
+    /// Creates an RGBA texture from an image. The image's top row ends up at
+    /// the bottom of the texture, so a t texture coordinate of 0 samples the
+    /// top of the image.
+    pub fn from_image(image: &Image) -> Self {
+        let texture = Texture2D::create(image.width(), image.height(), TextureFormat::Rgba8);
+        gl::texture_image_2d(
+            TextureFormat::Rgba8,
+            image.width(),
+            image.height(),
+            image.pixels(),
+        );
+        gl::unbind_texture_2d();
+        texture
+    }
+
+    /// Loads an RGBA texture from a PNG file, like `from_image`.
+    #[cfg(feature = "png")]
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, TextureError> {
+        Ok(Texture2D::from_image(&Image::load_png(path)?))
+    }
+
+    fn create(width: u32, height: u32, format: TextureFormat) -> Self {
+        let texture = Texture2D {
+            width,
+            height,
+            format,
+            texture_id: gl::create_texture(),
+        };
+
+        gl::bind_texture_2d(&texture.texture_id);
+        gl::set_texture_min_filter_2d(TextureFilter::Linear, None);
+        gl::set_texture_mag_filter_2d(TextureFilter::Linear);
+        gl::set_texture_wrap_2d(TextureWrap::ClampToEdge, TextureWrap::ClampToEdge);
+
+        texture
+    }
+
+    /// Replaces the whole contents of the texture.
+    pub fn upload<T: PixelComponent>(&mut self, pixels: &[T]) -> Result<(), TextureError> {
+        self.upload_region(0, 0, self.width, self.height, pixels)
+    }
+
+    /// Replaces a region of the texture, starting `x` pixels from the left and
+    /// `y` rows from the bottom.
+    pub fn upload_region<T: PixelComponent>(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        pixels: &[T],
+    ) -> Result<(), TextureError> {
+        let fits =
+            |start: u32, len: u32, size: u32| start.checked_add(len).is_some_and(|end| end <= size);
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return Err(TextureError::OutOfBounds);
+        }
+        check_pixels(width, height, self.format, pixels)?;
+
+        gl::bind_texture_2d(&self.texture_id);
+        gl::texture_sub_image_2d(self.format, x, y, width, height, pixels);
+        gl::unbind_texture_2d();
+        Ok(())
+    }
+
+    /// Generates the mipmap levels from the current contents. Needs to be
+    /// called again after uploading for the mipmaps to stay in sync.
+    pub fn generate_mipmaps(&mut self) {
+        gl::bind_texture_2d(&self.texture_id);
+        gl::generate_mipmap_2d();
+        gl::unbind_texture_2d();
+    }
+
+    /// Sets how the texture is sampled when drawn smaller than its size. With
+    /// a `mipmap` filter, the mipmaps must have been generated.
+    pub fn set_min_filter(&mut self, filter: TextureFilter, mipmap: Option<TextureFilter>) {
+        gl::bind_texture_2d(&self.texture_id);
+        gl::set_texture_min_filter_2d(filter, mipmap);
+        gl::unbind_texture_2d();
+    }
+
+    /// Sets how the texture is sampled when drawn larger than its size.
+    pub fn set_mag_filter(&mut self, filter: TextureFilter) {
+        gl::bind_texture_2d(&self.texture_id);
+        gl::set_texture_mag_filter_2d(filter);
+        gl::unbind_texture_2d();
+    }
+
+    pub fn set_wrap(&mut self, s: TextureWrap, t: TextureWrap) {
+        gl::bind_texture_2d(&self.texture_id);
+        gl::set_texture_wrap_2d(s, t);
+        gl::unbind_texture_2d();
+    }
+
+    /// Binds the texture to a texture unit. Upload `Sampler(unit)` to a
+    /// sampler uniform for a shader to sample it.
+    pub fn bind(&self, unit: u32) {
+        gl::active_texture(unit);
+        gl::bind_texture_2d(&self.texture_id);
+        gl::active_texture(0);
+    }
+
+    pub fn unbind(&self, unit: u32) {
+        gl::active_texture(unit);
+        gl::unbind_texture_2d();
+        gl::active_texture(0);
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    #[inline]
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub(crate) fn texture_id(&self) -> &gl::TextureId {
+        &self.texture_id
+    }
+}
+
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        gl::delete_texture(&self.texture_id);
+    }
+}
+
+fn check_pixels<T: PixelComponent>(
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    pixels: &[T],
+) -> Result<(), TextureError> {
+    if T::DATA_TYPE != format.data_type() {
+        return Err(TextureError::FormatMismatch(format));
+    }
+
+    let expected = (width as usize)
+        .saturating_mul(height as usize)
+        .saturating_mul(format.channels());
+    if pixels.len() != expected {
+        return Err(TextureError::InvalidDataLength {
+            expected,
+            actual: pixels.len(),
+        });
+    }
+    Ok(())
+}
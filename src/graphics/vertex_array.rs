@@ -1,9 +1,11 @@
+pub use crate::graphics::gl::PrimitiveMode;
 use crate::graphics::{gl, Buffer};
 use std::rc::Rc;
 
 pub struct VertexArray {
     indices_buffer: Option<Rc<Buffer>>,
     array_buffers: [Option<Rc<Buffer>>; 16],
+    divisors: [u32; 16],
 
     vertex_array_id: gl::VertexArrayId,
 }
@@ -21,6 +23,7 @@ impl VertexArray {
         VertexArray {
             indices_buffer: None,
             array_buffers: empty_array_buffers,
+            divisors: [0; 16],
             vertex_array_id: gl::create_vertex_array(),
         }
     }
@@ -45,20 +48,68 @@ impl VertexArray {
     }
 
     pub fn put_array_buffer_ref(&mut self, location: usize, buffer: &Rc<Buffer>) {
-        debug_assert_eq!(buffer.buffer_type(), gl::BufferType::ArrayBuffer);
-        debug_assert!(location < 16);
+        self.put_divided_array_buffer_ref(location, buffer, 0);
+    }
 
-        self.array_buffers[location] = None;
+    /// Puts a buffer whose elements advance once per instance instead of once
+    /// per vertex, for use with `draw_instanced`.
+    ///
+    /// Elements with more than 4 components, like a 4x4 transformation matrix,
+    /// take up consecutive locations of 4 components each, the same way a
+    /// `mat4` attribute does in a shader.
+    pub fn put_instance_buffer(&mut self, location: usize, buffer: Buffer) {
+        self.put_instance_buffer_ref(location, &Rc::new(buffer));
+    }
+
+    pub fn put_instance_buffer_ref(&mut self, location: usize, buffer: &Rc<Buffer>) {
+        self.put_divided_array_buffer_ref(location, buffer, 1);
+    }
+
+    pub fn put_divided_array_buffer(&mut self, location: usize, buffer: Buffer, divisor: u32) {
+        self.put_divided_array_buffer_ref(location, &Rc::new(buffer), divisor);
+    }
+
+    /// Puts an array buffer whose elements advance every `divisor` instances,
+    /// or once per vertex if `divisor` is 0.
+    ///
+    /// Panics if the buffer's elements are not 1 to 16 floats, or if they
+    /// would take up attribute locations past 15.
+    pub fn put_divided_array_buffer_ref(
+        &mut self,
+        location: usize,
+        buffer: &Rc<Buffer>,
+        divisor: u32,
+    ) {
+        debug_assert_eq!(buffer.buffer_type(), gl::BufferType::ArrayBuffer);
+        assert!(
+            buffer.size() > 0 && buffer.size() <= 16,
+            "array buffer elements must be 1 to 16 floats"
+        );
+
+        let locations = buffer.size().div_ceil(4);
+        assert!(
+            location < 16 && locations <= 16 - location,
+            "attribute locations {}..{} are out of range",
+            location,
+            location.saturating_add(locations),
+        );
 
         self.bind();
         buffer.bind();
-        gl::enable_vertex_attrib(location);
-        gl::vertex_attrib_pointer_float(location, buffer.size(), false, 0, 0);
-        gl::disable_vertex_attrib(location);
+        for i in 0..locations {
+            let size = (buffer.size() - i * 4).min(4);
+            let stride = if locations > 1 { buffer.size() } else { 0 };
+
+            gl::enable_vertex_attrib(location + i);
+            gl::vertex_attrib_pointer_float(location + i, size, false, stride, i * 4);
+            gl::vertex_attrib_divisor(location + i, divisor);
+            gl::disable_vertex_attrib(location + i);
+
+            self.array_buffers[location + i] = Some(buffer.clone());
+            self.divisors[location + i] = divisor;
+        }
         self.unbind();
         buffer.unbind();
-
-        self.array_buffers[location] = Some(buffer.clone());
     }
 
     pub fn enable_attrib_arrays(&self) {
@@ -77,6 +128,89 @@ impl VertexArray {
         }
     }
 
+    /// The number of complete vertices in the array buffers. Buffers with
+    /// fewer elements limit how many vertices can be drawn.
+    pub fn vertex_count(&self) -> usize {
+        self.array_buffers
+            .iter()
+            .zip(self.divisors.iter())
+            .filter(|(_, &divisor)| divisor == 0)
+            .filter_map(|(buffer, _)| buffer.as_ref())
+            .map(|buffer| buffer.len() / buffer.size())
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// The number of instances the instance buffers have data for, or None
+    /// if there are no instance buffers.
+    pub fn instance_count(&self) -> Option<usize> {
+        self.array_buffers
+            .iter()
+            .zip(self.divisors.iter())
+            .filter(|(_, &divisor)| divisor > 0)
+            .filter_map(|(buffer, &divisor)| {
+                buffer
+                    .as_ref()
+                    .map(|buffer| buffer.len() / buffer.size() * divisor as usize)
+            })
+            .min()
+    }
+
+    /// Draws the whole vertex array. With an indices buffer every index is
+    /// drawn, otherwise every vertex in order.
+    ///
+    /// The shader program to draw with must be bound.
+    pub fn draw(&self, mode: PrimitiveMode) {
+        self.bind();
+        self.enable_attrib_arrays();
+
+        match &self.indices_buffer {
+            Some(indices) => {
+                let index_type = indices
+                    .index_type()
+                    .expect("indices buffer without an index type");
+                gl::draw_elements(mode, indices.len(), index_type, 0);
+            }
+            None => gl::draw_arrays(mode, 0, self.vertex_count()),
+        }
+
+        self.disable_attrib_arrays();
+        self.unbind();
+    }
+
+    /// Draws the whole vertex array `instance_count` times in a single draw
+    /// call, stepping through the instance buffers once per instance.
+    ///
+    /// The shader program to draw with must be bound.
+    ///
+    /// Panics if the instance buffers have data for fewer instances.
+    pub fn draw_instanced(&self, mode: PrimitiveMode, instance_count: usize) {
+        if let Some(count) = self.instance_count() {
+            assert!(
+                instance_count <= count,
+                "drawing {} instances but the instance buffers hold {}",
+                instance_count,
+                count,
+            );
+        }
+
+        self.bind();
+        self.enable_attrib_arrays();
+
+        match &self.indices_buffer {
+            Some(indices) => {
+                let index_type = indices
+                    .index_type()
+                    .expect("indices buffer without an index type");
+                gl::draw_elements_instanced(mode, indices.len(), index_type, 0, instance_count);
+            }
+            None => gl::draw_arrays_instanced(mode, 0, self.vertex_count(), instance_count),
+        }
+
+        self.disable_attrib_arrays();
+        self.unbind();
+    }
+
     pub fn bind(&self) {
         gl::bind_vertex_array(&self.vertex_array_id);
     }
@@ -1,64 +1,96 @@
-use crate::graphics::gl;
-use glfw::{Context, WindowEvent, WindowMode};
+#[cfg(feature = "png")]
+use crate::graphics::ImageError;
+use crate::graphics::{
+    gl, ContextId, CursorMode, Image, MonitorSelection, RenderState, StandardCursor, WindowBackend,
+    WindowBuilder, WindowError,
+};
+use crate::maths::Vector2;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FullscreenMode {
+    Windowed,
+    /// Takes exclusive control of the monitor at its current video mode.
+    Exclusive(MonitorSelection),
+    /// An undecorated window covering the whole monitor, which is faster to
+    /// switch in and out of than exclusive fullscreen.
+    Borderless(MonitorSelection),
+}
 
 pub struct Window {
-    width: u32,
-    height: u32,
+    size: Rc<Cell<(u32, u32)>>,
     title: String,
 
-    glfw: glfw::Glfw,
-    glfw_window: glfw::Window,
+    fullscreen: FullscreenMode,
+
+    context: ContextId,
+    backend: Box<dyn WindowBackend>,
 }
 
 impl Window {
-    pub fn new<T>(width: u32, height: u32, title: T) -> (Self, Receiver<(f64, WindowEvent)>)
+    /// Creates a window with the default `WindowBuilder` settings.
+    pub fn new<T>(width: u32, height: u32, title: T) -> Result<(Self, EventLoop), WindowError>
     where
         T: Into<String>,
     {
-        let title = title.into();
-
-        let nop: Option<glfw::ErrorCallback<()>> = None;
-        let mut glfw = glfw::init(nop).unwrap();
-
-        use glfw::WindowHint;
-        glfw.window_hint(WindowHint::ContextVersion(3, 3));
-        glfw.window_hint(WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-        glfw.window_hint(WindowHint::OpenGlForwardCompat(true));
-
-        let (mut glfw_window, events) = glfw
-            .create_window(width, height, &title, WindowMode::Windowed)
-            .expect("Failed to create GLFW window.");
-
-        glfw_window.make_current();
-        gl::load_with(|s| glfw_window.get_proc_address(s));
+        WindowBuilder::new(width, height, title).build()
+    }
 
-        glfw_window.set_key_polling(true);
-        glfw_window.set_framebuffer_size_polling(true);
+    pub(crate) fn from_backend(
+        mut backend: Box<dyn WindowBackend>,
+        title: String,
+        fullscreen: FullscreenMode,
+        swap_interval: u32,
+        debug_context: bool,
+    ) -> Self {
+        let context = ContextId::next();
+        context.make_current(&mut *backend);
+        gl::load_with(|s| backend.get_proc_address(s));
+        if debug_context && !gl::enable_debug_output() {
+            log::warn!("the OpenGL context does not support debug output");
+        }
+        backend.set_swap_interval(swap_interval);
+        RenderState::default().apply();
 
+        let (width, height) = backend.framebuffer_size();
         let mut window = Window {
-            width,
-            height,
+            size: Rc::new(Cell::new((0, 0))),
             title,
-            glfw,
-            glfw_window,
+            fullscreen,
+            context,
+            backend,
         };
         window.resize(width, height);
 
-        (window, events)
+        window
     }
 
+    /// Presents the frame and clears the framebuffer for the next one. The
+    /// window's context is made current first.
     pub fn update(&mut self) {
-        self.glfw_window.swap_buffers();
+        self.make_current();
+        self.backend.swap_buffers();
+        #[cfg(feature = "gl-trace")]
+        gl::trace::end_frame();
 
+        // The write masks and scissor limit clears too, so lift them for it.
+        let state = RenderState::current();
+        state.unwrap_or_default().unmasked().apply();
         use crate::graphics::gl::ClearFlag;
         gl::clear(&[ClearFlag::COLOR_BUFFER, ClearFlag::DEPTH_BUFFER]);
+        if let Some(state) = state {
+            state.apply();
+        }
     }
 
+    /// Updates the viewport to the new framebuffer size, making the window's
+    /// context current. Window sized `Framebuffer`s follow the new size the
+    /// next time they are bound.
     #[inline]
     pub fn resize(&mut self, width: u32, height: u32) {
-        self.width = width;
-        self.height = height;
-        gl::set_view_port(0, 0, width, height);
+        self.size.set((width, height));
+        self.bind();
     }
 
     #[inline]
@@ -68,9 +100,61 @@ impl Window {
         self.resize(width as u32, height as u32);
     }
 
+    /// Makes the window's context current and binds its default framebuffer,
+    /// with the viewport covering the whole window. Used to render to the
+    /// window again after rendering into a `Framebuffer`.
+    pub fn bind(&mut self) {
+        self.make_current();
+        gl::unbind_framebuffer(gl::FramebufferTarget::Framebuffer);
+        let (width, height) = self.size.get();
+        gl::set_view_port(0, 0, width, height);
+    }
+
+    #[inline]
+    pub fn context_id(&self) -> ContextId {
+        self.context
+    }
+
+    /// Makes the window's OpenGL context current on the calling thread, so
+    /// that following OpenGL calls render to it. Does nothing if it already
+    /// is.
+    #[inline]
+    pub fn make_current(&mut self) {
+        self.context.make_current(&mut *self.backend);
+    }
+
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        self.context.is_current()
+    }
+
+    #[inline]
+    pub(crate) fn backend(&self) -> &dyn WindowBackend {
+        &*self.backend
+    }
+
+    /// The size of the framebuffer in pixels.
     #[inline]
     pub fn size(&self) -> (u32, u32) {
-        (self.width, self.height)
+        self.size.get()
+    }
+
+    /// The framebuffer size, shared with the framebuffers that follow it.
+    pub(crate) fn size_cell(&self) -> Rc<Cell<(u32, u32)>> {
+        self.size.clone()
+    }
+
+    /// The size of the content area in screen coordinates. This differs from
+    /// `size()` on high DPI displays where one screen coordinate spans
+    /// several pixels.
+    pub fn logical_size(&self) -> (u32, u32) {
+        self.backend.logical_size()
+    }
+
+    /// The ratio between the window's DPI and the platform's default DPI,
+    /// for scaling UI and text.
+    pub fn content_scale(&self) -> Vector2 {
+        self.backend.content_scale()
     }
 
     #[inline]
@@ -78,40 +162,264 @@ impl Window {
         &self.title
     }
 
+    pub fn set_title<T>(&mut self, title: T)
+    where
+        T: Into<String>,
+    {
+        self.title = title.into();
+        self.backend.set_title(&self.title);
+    }
+
+    #[inline]
+    pub fn fullscreen(&self) -> FullscreenMode {
+        self.fullscreen
+    }
+
+    /// Switches between windowed, exclusive fullscreen and borderless
+    /// fullscreen. Leaving fullscreen restores the window's previous position
+    /// and size.
+    ///
+    /// Returns false, leaving the window unchanged, if the selected monitor
+    /// is not connected.
+    pub fn set_fullscreen(&mut self, mode: FullscreenMode) -> bool {
+        let applied = self.backend.set_fullscreen(mode);
+        if applied {
+            self.fullscreen = mode;
+        }
+        applied
+    }
+
+    #[inline]
+    pub fn minimize(&mut self) {
+        self.backend.minimize();
+    }
+
+    #[inline]
+    pub fn maximize(&mut self) {
+        self.backend.maximize();
+    }
+
+    /// Restores a minimized or maximized window to its previous size.
+    #[inline]
+    pub fn restore(&mut self) {
+        self.backend.restore();
+    }
+
+    #[inline]
+    pub fn is_minimized(&self) -> bool {
+        self.backend.is_minimized()
+    }
+
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        self.backend.is_maximized()
+    }
+
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.backend.cursor_mode()
+    }
+
+    /// Captured cursors use raw, unaccelerated mouse motion where the
+    /// platform supports it.
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) {
+        self.backend.set_cursor_mode(mode);
+    }
+
+    pub fn set_standard_cursor(&mut self, cursor: StandardCursor) {
+        self.backend.set_standard_cursor(cursor);
+    }
+
+    /// Uses `image` as the cursor while it is over the window. The hotspot is
+    /// the pixel of the image that points at the cursor position.
+    pub fn set_cursor_image(&mut self, image: &Image, hotspot_x: u32, hotspot_y: u32) {
+        self.backend.set_cursor_image(image, hotspot_x, hotspot_y);
+    }
+
+    /// Goes back to the platform's default arrow cursor.
+    pub fn reset_cursor(&mut self) {
+        self.backend.reset_cursor();
+    }
+
+    /// Sets the window icon. The platform picks the image closest to the size
+    /// it needs, so several sizes (16x16, 32x32, 48x48) can be given.
+    pub fn set_icon(&mut self, images: &[Image]) {
+        self.backend.set_icon(images);
+    }
+
+    /// Returns None if the clipboard is empty or doesn't hold text.
+    pub fn clipboard(&self) -> Option<String> {
+        self.backend.clipboard()
+    }
+
+    pub fn set_clipboard(&mut self, text: &str) {
+        self.backend.set_clipboard(text);
+    }
+
     #[inline]
     pub fn set_clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
         gl::set_clear_color(red, green, blue, alpha);
     }
 
+    /// Reads back what has been rendered to the window this frame. Call it
+    /// after rendering and before `update`, which presents and clears the
+    /// frame.
+    pub fn read_pixels(&mut self) -> Image {
+        self.make_current();
+        gl::unbind_framebuffer(gl::FramebufferTarget::Read);
+        let (width, height) = self.size();
+        Image::read_framebuffer(width, height)
+    }
+
+    /// Saves what has been rendered to the window this frame as a PNG file,
+    /// see `read_pixels`.
+    #[cfg(feature = "png")]
+    pub fn screenshot(&mut self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        self.read_pixels().save_png(path)
+    }
+
     #[inline]
     pub fn should_close(&self) -> bool {
-        self.glfw_window.should_close()
+        self.backend.should_close()
+    }
+
+    #[inline]
+    pub fn set_should_close(&mut self, should_close: bool) {
+        self.backend.set_should_close(should_close);
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        self.context.release();
     }
 }
 
-use std::sync::mpsc::Receiver;
+use crate::graphics::Event;
+use crate::input::{ButtonAction, GamepadError, GamepadSource, Input};
+use std::path::Path;
 
 pub struct EventLoop {
-    events: Receiver<(f64, WindowEvent)>,
+    events: Vec<Event>,
+    input: Input,
+
+    gamepad_source: Option<Box<dyn GamepadSource>>,
 }
 
 impl EventLoop {
-    pub fn new(events: Receiver<(f64, WindowEvent)>) -> Self {
-        EventLoop { events }
-    }
-
-    pub fn process_events(&self, window: &mut Window) {
-        window.glfw.poll_events();
-        for (_, event) in glfw::flush_messages(&self.events) {
-            match event {
-                WindowEvent::Key(glfw::Key::Escape, _, glfw::Action::Press, _) => {
-                    println!("test");
-                }
-                WindowEvent::FramebufferSize(w, h) => {
-                    window.resize_i(w, h);
-                }
-                _ => {}
-            }
+    pub fn new() -> Self {
+        EventLoop {
+            events: Vec::new(),
+            input: Input::new(),
+            gamepad_source: None,
+        }
+    }
+
+    /// Replaces where gamepad state is polled from. Defaults to the gamepad
+    /// support of the window's backend, if it has any.
+    pub fn set_gamepad_source<S>(&mut self, source: S)
+    where
+        S: GamepadSource + 'static,
+    {
+        self.gamepad_source = Some(Box::new(source));
+    }
+
+    fn gamepad_source<'a>(
+        source: &'a mut Option<Box<dyn GamepadSource>>,
+        window: &Window,
+    ) -> Option<&'a mut dyn GamepadSource> {
+        if source.is_none() {
+            *source = window.backend.gamepad_source();
+        }
+        match source {
+            Some(source) => Some(&mut **source),
+            None => None,
+        }
+    }
+
+    /// Adds gamepad mappings in the SDL_GameControllerDB format, one per line.
+    pub fn load_gamepad_mappings(
+        &mut self,
+        window: &Window,
+        mappings: &str,
+    ) -> Result<(), GamepadError> {
+        EventLoop::gamepad_source(&mut self.gamepad_source, window)
+            .ok_or(GamepadError::Unsupported)?
+            .update_mappings(mappings)
+    }
+
+    /// Reads a `gamecontrollerdb.txt` file and adds all of its mappings.
+    pub fn load_gamepad_mappings_file<P>(
+        &mut self,
+        window: &Window,
+        path: P,
+    ) -> Result<(), GamepadError>
+    where
+        P: AsRef<Path>,
+    {
+        let mappings = std::fs::read_to_string(path)?;
+        self.load_gamepad_mappings(window, &mappings)
+    }
+
+    /// Polls the platform for new events and records them for `events()`.
+    ///
+    /// The window's size and viewport and the `Input` state are updated
+    /// before the events are handed out, so they are always in sync.
+    pub fn process_events(&mut self, window: &mut Window) {
+        self.events.clear();
+        self.input.begin_frame();
+
+        window.backend.poll_events(&mut self.events);
+
+        if let Some(source) = EventLoop::gamepad_source(&mut self.gamepad_source, window) {
+            let gamepad_events = self.input.update_gamepads(source);
+            self.events
+                .extend(gamepad_events.into_iter().map(Event::from));
+        }
+
+        for event in &self.events {
+            EventLoop::handle_event(window, &mut self.input, event);
         }
     }
+
+    fn handle_event(window: &mut Window, input: &mut Input, event: &Event) {
+        match *event {
+            Event::Key { key, action, .. } => match action {
+                ButtonAction::Press => input.press_key(key),
+                ButtonAction::Release => input.release_key(key),
+                ButtonAction::Repeat => {}
+            },
+            Event::MouseButton { button, action, .. } => match action {
+                ButtonAction::Press => input.press_mouse_button(button),
+                ButtonAction::Release => input.release_mouse_button(button),
+                ButtonAction::Repeat => {}
+            },
+            Event::CursorMoved(position) => input.set_mouse_position(position),
+            Event::Scroll(offset) => input.scroll(offset),
+            Event::Resized { width, height } => window.resize(width, height),
+            _ => {}
+        }
+    }
+
+    /// The events received during the last call to `process_events`, in the
+    /// order they happened.
+    #[inline]
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    #[inline]
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    #[inline]
+    pub fn input_mut(&mut self) -> &mut Input {
+        &mut self.input
+    }
+}
+
+impl Default for EventLoop {
+    fn default() -> Self {
+        EventLoop::new()
+    }
 }
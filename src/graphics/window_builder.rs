@@ -0,0 +1,363 @@
+#[cfg(feature = "egl")]
+use crate::graphics::EglContext;
+use crate::graphics::{Backend, EventLoop, HeadlessContext, Platform, Window};
+use std::ffi::OsString;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WindowError {
+    #[error("failed to initialize the windowing system: {0}")]
+    Init(String),
+    #[error("failed to create a window and OpenGL context: {0}")]
+    ContextCreation(String),
+    #[error("OpenGL {major}.{minor} is not supported: {description}")]
+    UnsupportedGlVersion {
+        major: u32,
+        minor: u32,
+        description: String,
+    },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GlProfile {
+    Core,
+    Compatibility,
+    Any,
+}
+
+impl GlProfile {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn to_glfw(self) -> glfw::OpenGlProfileHint {
+        match self {
+            GlProfile::Core => glfw::OpenGlProfileHint::Core,
+            GlProfile::Compatibility => glfw::OpenGlProfileHint::Compat,
+            GlProfile::Any => glfw::OpenGlProfileHint::Any,
+        }
+    }
+}
+
+/// The API used to create the OpenGL context.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContextApi {
+    /// WGL, GLX or NSGL depending on the platform.
+    Native,
+    Egl,
+    /// Mesa's off-screen software renderer. Only available if GLFW was built
+    /// with OSMesa support.
+    OsMesa,
+}
+
+impl ContextApi {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn to_glfw(self) -> glfw::ContextCreationApi {
+        match self {
+            ContextApi::Native => glfw::ContextCreationApi::Native,
+            ContextApi::Egl => glfw::ContextCreationApi::Egl,
+            ContextApi::OsMesa => glfw::ContextCreationApi::OsMesa,
+        }
+    }
+}
+
+/// Which monitor a fullscreen window is shown on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MonitorSelection {
+    Primary,
+    /// An index into the list of connected monitors, in the order the
+    /// platform reports them.
+    Index(usize),
+}
+
+/// Configures the window and OpenGL context created by `build()`.
+///
+/// Defaults to a resizable, decorated window with an OpenGL 3.3 core,
+/// forward compatible context, a 24 bit depth buffer, an 8 bit stencil buffer
+/// and vsync.
+///
+/// ```ignore
+/// let (window, event_loop) = WindowBuilder::new(1280, 720, "Game")
+///     .gl_version(4, 1)
+///     .samples(4)
+///     .vsync(false)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowBuilder {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) title: String,
+    pub(crate) backend: Backend,
+
+    pub(crate) gl_version: (u32, u32),
+    pub(crate) gl_profile: GlProfile,
+    pub(crate) forward_compatible: bool,
+    pub(crate) debug_context: bool,
+    pub(crate) context_api: ContextApi,
+    pub(crate) software_rendering: bool,
+
+    pub(crate) samples: Option<u32>,
+    pub(crate) depth_bits: Option<u32>,
+    pub(crate) stencil_bits: Option<u32>,
+    pub(crate) srgb: bool,
+    pub(crate) swap_interval: u32,
+
+    pub(crate) resizable: bool,
+    pub(crate) decorated: bool,
+    pub(crate) transparent: bool,
+    pub(crate) visible: bool,
+    pub(crate) fullscreen: Option<MonitorSelection>,
+    pub(crate) position: Option<(i32, i32)>,
+}
+
+impl WindowBuilder {
+    pub fn new<T>(width: u32, height: u32, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        WindowBuilder {
+            width,
+            height,
+            title: title.into(),
+            backend: Backend::default(),
+            gl_version: (3, 3),
+            gl_profile: GlProfile::Core,
+            forward_compatible: true,
+            debug_context: cfg!(feature = "gl-debug"),
+            context_api: ContextApi::Native,
+            software_rendering: false,
+            samples: None,
+            depth_bits: Some(24),
+            stencil_bits: Some(8),
+            srgb: false,
+            swap_interval: 1,
+            resizable: true,
+            decorated: true,
+            transparent: false,
+            visible: true,
+            fullscreen: None,
+            position: None,
+        }
+    }
+
+    /// The windowing library used by `build()` and `build_headless()`.
+    /// Windows created through a `Platform` use the platform's backend.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn gl_version(mut self, major: u32, minor: u32) -> Self {
+        self.gl_version = (major, minor);
+        self
+    }
+
+    pub fn gl_profile(mut self, profile: GlProfile) -> Self {
+        self.gl_profile = profile;
+        self
+    }
+
+    /// Removes deprecated functionality from the context. Required for core
+    /// profiles on macOS.
+    pub fn forward_compatible(mut self, forward_compatible: bool) -> Self {
+        self.forward_compatible = forward_compatible;
+        self
+    }
+
+    /// Asks the driver for extra validation and installs a handler for its
+    /// debug messages, see `gl::enable_debug_output`. Enabled by default with
+    /// the `gl-debug` feature.
+    pub fn debug_context(mut self, debug_context: bool) -> Self {
+        self.debug_context = debug_context;
+        self
+    }
+
+    pub fn context_api(mut self, api: ContextApi) -> Self {
+        self.context_api = api;
+        self
+    }
+
+    /// Asks Mesa to use its llvmpipe software rasterizer instead of the GPU
+    /// driver, by setting `LIBGL_ALWAYS_SOFTWARE` while the context is
+    /// created, and restoring its previous value afterwards. Has no effect on
+    /// other OpenGL implementations, or once Mesa has loaded a driver for an
+    /// existing `Platform`; set the variable before `Platform::new` instead.
+    ///
+    /// Contexts are created one at a time across the process, so the
+    /// variable never changes while another context is created. Code outside
+    /// this crate reading the environment from C on another thread at the
+    /// same time can still race with it.
+    pub fn software_rendering(mut self, software_rendering: bool) -> Self {
+        self.software_rendering = software_rendering;
+        self
+    }
+
+    /// The number of samples used for multisample anti-aliasing. 0 disables
+    /// MSAA.
+    pub fn samples(mut self, samples: u32) -> Self {
+        self.samples = if samples == 0 { None } else { Some(samples) };
+        self
+    }
+
+    pub fn depth_bits(mut self, bits: u32) -> Self {
+        self.depth_bits = Some(bits);
+        self
+    }
+
+    pub fn stencil_bits(mut self, bits: u32) -> Self {
+        self.stencil_bits = Some(bits);
+        self
+    }
+
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    /// The number of screen refreshes to wait for before swapping buffers. 0
+    /// disables vsync.
+    pub fn swap_interval(mut self, interval: u32) -> Self {
+        self.swap_interval = interval;
+        self
+    }
+
+    pub fn vsync(self, vsync: bool) -> Self {
+        self.swap_interval(if vsync { 1 } else { 0 })
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn decorated(mut self, decorated: bool) -> Self {
+        self.decorated = decorated;
+        self
+    }
+
+    /// Lets the alpha channel of the framebuffer blend with whatever is
+    /// behind the window, if the platform supports it.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn fullscreen(mut self, monitor: MonitorSelection) -> Self {
+        self.fullscreen = Some(monitor);
+        self
+    }
+
+    pub fn windowed(mut self) -> Self {
+        self.fullscreen = None;
+        self
+    }
+
+    /// The initial position of the window's content area, in screen
+    /// coordinates. Ignored for fullscreen windows.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Creates the window, makes its context current and loads the OpenGL
+    /// functions.
+    ///
+    /// Returns Err if the windowing system cannot be initialized, for
+    /// example without a display, or if no context matching the requested
+    /// options can be created.
+    pub fn build(self) -> Result<(Window, EventLoop), WindowError> {
+        self.platform()?.create_window(self)
+    }
+
+    /// Creates an OpenGL context without showing a window, for rendering in
+    /// tests, tools and servers. Window specific options such as fullscreen
+    /// and vsync are ignored.
+    ///
+    /// Returns Err under the same conditions as `build()`.
+    pub fn build_headless(self) -> Result<HeadlessContext, WindowError> {
+        self.platform()?.create_headless(self, None)
+    }
+
+    /// Creates an OpenGL context on Mesa's surfaceless EGL platform, which
+    /// renders into an offscreen pbuffer and needs neither a windowing system
+    /// nor a display connection. The backend is ignored, along with the
+    /// window specific options ignored by `build_headless()`.
+    ///
+    /// Returns Err if libEGL cannot be loaded, if it lacks the surfaceless
+    /// platform, or if no context matching the requested options can be
+    /// created.
+    #[cfg(feature = "egl")]
+    pub fn build_offscreen(self) -> Result<HeadlessContext, WindowError> {
+        let backend = {
+            let _creation = self.begin_context_creation();
+            EglContext::new(&self)?
+        };
+        Ok(HeadlessContext::from_backend(
+            Box::new(backend),
+            self.debug_context,
+        ))
+    }
+
+    fn platform(&self) -> Result<Platform, WindowError> {
+        Platform::with_backend(self.backend)
+    }
+
+    /// Waits for any other thread to finish creating a context, then sets
+    /// up the environment for creating one with these options until the
+    /// returned guard is dropped.
+    pub(crate) fn begin_context_creation(&self) -> ContextCreation {
+        let lock = CONTEXT_CREATION
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let software = if self.software_rendering {
+            Some(EnvOverride::set("LIBGL_ALWAYS_SOFTWARE", "1"))
+        } else {
+            None
+        };
+        ContextCreation {
+            _software: software,
+            _lock: lock,
+        }
+    }
+}
+
+/// Serializes context creation across threads. Drivers read the environment
+/// while creating a context, and changing it while another thread reads it
+/// is undefined behaviour.
+static CONTEXT_CREATION: Mutex<()> = Mutex::new(());
+
+/// Held while a context is created, see `WindowBuilder::begin_context_creation`.
+pub(crate) struct ContextCreation {
+    // Dropped first, so the environment is restored before the lock is
+    // released.
+    _software: Option<EnvOverride>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+/// Sets an environment variable until dropped, then restores its previous
+/// value.
+struct EnvOverride {
+    name: &'static str,
+    previous: Option<OsString>,
+}
+
+impl EnvOverride {
+    fn set(name: &'static str, value: &str) -> Self {
+        let previous = std::env::var_os(name);
+        std::env::set_var(name, value);
+        EnvOverride { name, previous }
+    }
+}
+
+impl Drop for EnvOverride {
+    fn drop(&mut self) {
+        match &self.previous {
+            Some(previous) => std::env::set_var(self.name, previous),
+            None => std::env::remove_var(self.name),
+        }
+    }
+}
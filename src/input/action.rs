@@ -0,0 +1,601 @@
+use crate::input::{GamepadAxis, GamepadButton, Input, Key, MouseButton};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BindingError {
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("unknown input '{0}'")]
+    UnknownInput(String),
+    /// Names must not be empty, start or end with whitespace, or contain `=`,
+    /// `,`, `#` or line breaks, so that they read back from a bindings file.
+    #[error("'{0}' can not be used as an action or axis name")]
+    InvalidName(String),
+    #[error("failed to read or write the bindings file")]
+    Io(#[from] std::io::Error),
+}
+
+/// A single digital input. Gamepad buttons match on any connected gamepad.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Button {
+    Key(Key),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Button {
+    /// Returns whether the button is down now and whether it was down the
+    /// frame before.
+    fn state(&self, input: &Input) -> (bool, bool) {
+        let was_down = |down: bool, pressed: bool, released: bool| (down && !pressed) || released;
+
+        match *self {
+            Button::Key(key) => {
+                let down = input.is_key_down(key);
+                let previous = was_down(
+                    down,
+                    input.was_key_pressed(key),
+                    input.was_key_released(key),
+                );
+                (down, previous)
+            }
+            Button::Mouse(button) => {
+                let down = input.is_mouse_button_down(button);
+                let previous = was_down(
+                    down,
+                    input.was_mouse_button_pressed(button),
+                    input.was_mouse_button_released(button),
+                );
+                (down, previous)
+            }
+            Button::Gamepad(button) => input.gamepads().iter().fold(
+                (false, false),
+                |(any_down, any_previous), (_, gamepad)| {
+                    let down = gamepad.is_button_down(button);
+                    let previous = was_down(
+                        down,
+                        gamepad.was_button_pressed(button),
+                        gamepad.was_button_released(button),
+                    );
+                    (any_down || down, any_previous || previous)
+                },
+            ),
+        }
+    }
+
+    /// Returns the first key, mouse button or gamepad button pressed during
+    /// the last frame. Used to capture a new binding when rebinding at
+    /// runtime.
+    pub fn first_pressed(input: &Input) -> Option<Button> {
+        if let Some(key) = input.pressed_keys().next() {
+            return Some(Button::Key(key));
+        }
+        if let Some(button) = input.pressed_mouse_buttons().next() {
+            return Some(Button::Mouse(button));
+        }
+
+        input.gamepads().iter().find_map(|(_, gamepad)| {
+            GamepadButton::ALL
+                .iter()
+                .copied()
+                .find(|button| gamepad.was_button_pressed(*button))
+                .map(Button::Gamepad)
+        })
+    }
+}
+
+impl fmt::Display for Button {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Button::Key(key) => write!(f, "key:{}", key.name()),
+            Button::Mouse(button) => write!(f, "mouse:{}", button.name()),
+            Button::Gamepad(button) => write!(f, "gamepad:{}", button.name()),
+        }
+    }
+}
+
+impl FromStr for Button {
+    type Err = BindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || BindingError::UnknownInput(s.to_owned());
+
+        let (device, name) = split_device(s).ok_or_else(unknown)?;
+        match device {
+            "key" => Key::from_name(name).map(Button::Key),
+            "mouse" => MouseButton::from_name(name).map(Button::Mouse),
+            "gamepad" => GamepadButton::from_name(name).map(Button::Gamepad),
+            _ => None,
+        }
+        .ok_or_else(unknown)
+    }
+}
+
+fn split_device(s: &str) -> Option<(&str, &str)> {
+    let separator = s.find(':')?;
+    Some((s[..separator].trim(), s[separator + 1..].trim()))
+}
+
+/// One or more buttons that must all be held for the binding to be active,
+/// e.g. `key:LeftControl+key:S`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chord(Vec<Button>);
+
+impl Chord {
+    /// Panics if `buttons` is empty, as a chord of no buttons would always be
+    /// held.
+    pub fn new(buttons: Vec<Button>) -> Self {
+        assert!(!buttons.is_empty(), "a chord needs at least one button");
+        Chord(buttons)
+    }
+
+    #[inline]
+    pub fn buttons(&self) -> &[Button] {
+        &self.0
+    }
+
+    fn state(&self, input: &Input) -> (bool, bool) {
+        self.0
+            .iter()
+            .fold((true, true), |(all_down, all_previous), button| {
+                let (down, previous) = button.state(input);
+                (all_down && down, all_previous && previous)
+            })
+    }
+}
+
+impl From<Button> for Chord {
+    fn from(button: Button) -> Self {
+        Chord(vec![button])
+    }
+}
+
+impl From<Key> for Chord {
+    fn from(key: Key) -> Self {
+        Chord::from(Button::Key(key))
+    }
+}
+
+impl From<MouseButton> for Chord {
+    fn from(button: MouseButton) -> Self {
+        Chord::from(Button::Mouse(button))
+    }
+}
+
+impl From<GamepadButton> for Chord {
+    fn from(button: GamepadButton) -> Self {
+        Chord::from(Button::Gamepad(button))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let buttons: Vec<String> = self.0.iter().map(Button::to_string).collect();
+        write!(f, "{}", buttons.join("+"))
+    }
+}
+
+impl FromStr for Chord {
+    type Err = BindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let buttons = s
+            .split('+')
+            .map(str::parse)
+            .collect::<Result<Vec<Button>, BindingError>>()?;
+        Ok(Chord(buttons))
+    }
+}
+
+/// A source of an analog value in [-1, 1].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AxisBinding {
+    /// -1 while `negative` is held, 1 while `positive` is held.
+    Buttons {
+        negative: Chord,
+        positive: Chord,
+    },
+    /// The deadzoned axis of any connected gamepad, whichever is furthest
+    /// from rest.
+    Gamepad(GamepadAxis),
+    ScrollX,
+    ScrollY,
+}
+
+impl AxisBinding {
+    fn value(&self, input: &Input) -> f32 {
+        match self {
+            AxisBinding::Buttons { negative, positive } => {
+                let mut value = 0.0;
+                if negative.state(input).0 {
+                    value -= 1.0;
+                }
+                if positive.state(input).0 {
+                    value += 1.0;
+                }
+                value
+            }
+            AxisBinding::Gamepad(axis) => input
+                .gamepads()
+                .iter()
+                .map(|(_, gamepad)| gamepad.axis(*axis))
+                .fold(0.0, furthest_from_rest),
+            AxisBinding::ScrollX => input.scroll_delta().x,
+            AxisBinding::ScrollY => input.scroll_delta().y,
+        }
+    }
+}
+
+fn furthest_from_rest(a: f32, b: f32) -> f32 {
+    if b.abs() > a.abs() {
+        b
+    } else {
+        a
+    }
+}
+
+impl fmt::Display for AxisBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxisBinding::Buttons { negative, positive } => write!(f, "{}/{}", negative, positive),
+            AxisBinding::Gamepad(axis) => write!(f, "gamepad_axis:{}", axis.name()),
+            AxisBinding::ScrollX => write!(f, "scroll:x"),
+            AxisBinding::ScrollY => write!(f, "scroll:y"),
+        }
+    }
+}
+
+impl FromStr for AxisBinding {
+    type Err = BindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(separator) = s.find('/') {
+            return Ok(AxisBinding::Buttons {
+                negative: s[..separator].parse()?,
+                positive: s[separator + 1..].parse()?,
+            });
+        }
+
+        let unknown = || BindingError::UnknownInput(s.to_owned());
+        match split_device(s).ok_or_else(unknown)? {
+            ("gamepad_axis", name) => GamepadAxis::from_name(name).map(AxisBinding::Gamepad),
+            ("scroll", "x") => Some(AxisBinding::ScrollX),
+            ("scroll", "y") => Some(AxisBinding::ScrollY),
+            _ => None,
+        }
+        .ok_or_else(unknown)
+    }
+}
+
+/// Maps named actions and axes to physical inputs.
+///
+/// Each action or axis may have several bindings; an action is down while any
+/// of its chords is held. Bindings can be saved to and loaded from a text
+/// file of the form:
+///
+/// ```text
+/// [actions]
+/// fire = mouse:Left, key:Space
+/// save = key:LeftControl+key:S
+///
+/// [axes]
+/// move_x = key:A/key:D, gamepad_axis:LeftX
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<Chord>>,
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        ActionMap::default()
+    }
+
+    /// Adds a binding to `action`, keeping any existing bindings.
+    ///
+    /// Returns `BindingError::InvalidName` if `action` couldn't be read back
+    /// from a bindings file.
+    pub fn bind<C>(&mut self, action: &str, chord: C) -> Result<&mut Self, BindingError>
+    where
+        C: Into<Chord>,
+    {
+        check_name(action)?;
+        let chords = self.actions.entry(action.to_owned()).or_default();
+        let chord = chord.into();
+        if !chords.contains(&chord) {
+            chords.push(chord);
+        }
+        Ok(self)
+    }
+
+    /// Replaces every binding of `action` with `chord`.
+    pub fn rebind<C>(&mut self, action: &str, chord: C) -> Result<&mut Self, BindingError>
+    where
+        C: Into<Chord>,
+    {
+        check_name(action)?;
+        self.actions.insert(action.to_owned(), vec![chord.into()]);
+        Ok(self)
+    }
+
+    /// Removes one binding from `action`. Returns false if it wasn't bound.
+    pub fn unbind(&mut self, action: &str, chord: &Chord) -> bool {
+        match self.actions.get_mut(action) {
+            Some(chords) => {
+                let len = chords.len();
+                chords.retain(|bound| bound != chord);
+                chords.len() != len
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear_action(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Chord] {
+        self.actions.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn bind_axis(
+        &mut self,
+        axis: &str,
+        binding: AxisBinding,
+    ) -> Result<&mut Self, BindingError> {
+        check_name(axis)?;
+        let bindings = self.axes.entry(axis.to_owned()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        Ok(self)
+    }
+
+    pub fn rebind_axis(
+        &mut self,
+        axis: &str,
+        binding: AxisBinding,
+    ) -> Result<&mut Self, BindingError> {
+        check_name(axis)?;
+        self.axes.insert(axis.to_owned(), vec![binding]);
+        Ok(self)
+    }
+
+    pub fn clear_axis(&mut self, axis: &str) {
+        self.axes.remove(axis);
+    }
+
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], Vec::as_slice)
+    }
+
+    fn action_state(&self, input: &Input, action: &str) -> (bool, bool) {
+        self.bindings(action)
+            .iter()
+            .fold((false, false), |(any_down, any_previous), chord| {
+                let (down, previous) = chord.state(input);
+                (any_down || down, any_previous || previous)
+            })
+    }
+
+    pub fn is_action_down(&self, input: &Input, action: &str) -> bool {
+        self.action_state(input, action).0
+    }
+
+    /// Returns true on the frame any binding of `action` became active.
+    pub fn was_action_pressed(&self, input: &Input, action: &str) -> bool {
+        let (down, previous) = self.action_state(input, action);
+        down && !previous
+    }
+
+    /// Returns true on the frame the last active binding of `action` was let
+    /// go of.
+    pub fn was_action_released(&self, input: &Input, action: &str) -> bool {
+        let (down, previous) = self.action_state(input, action);
+        !down && previous
+    }
+
+    /// The value of `axis` from whichever binding is furthest from rest,
+    /// clamped to [-1, 1].
+    pub fn axis(&self, input: &Input, axis: &str) -> f32 {
+        self.axis_bindings(axis)
+            .iter()
+            .map(|binding| binding.value(input))
+            .fold(0.0, furthest_from_rest)
+            .clamp(-1.0, 1.0)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BindingError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BindingError> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+fn check_name(name: &str) -> Result<(), BindingError> {
+    let invalid =
+        name.is_empty() || name.trim() != name || name.contains(['=', ',', '#', '\n', '\r']);
+    if invalid {
+        Err(BindingError::InvalidName(name.to_owned()))
+    } else {
+        Ok(())
+    }
+}
+
+impl fmt::Display for ActionMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: ToString>(bindings: &[T]) -> String {
+            let bindings: Vec<String> = bindings.iter().map(T::to_string).collect();
+            bindings.join(", ")
+        }
+
+        writeln!(f, "[actions]")?;
+        for (action, chords) in &self.actions {
+            writeln!(f, "{} = {}", action, join(chords))?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "[axes]")?;
+        for (axis, bindings) in &self.axes {
+            writeln!(f, "{} = {}", axis, join(bindings))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for ActionMap {
+    type Err = BindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        enum Section {
+            None,
+            Actions,
+            Axes,
+        }
+
+        let mut map = ActionMap::new();
+        let mut section = Section::None;
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: String| BindingError::Parse {
+                line: line_number,
+                message,
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line {
+                "[actions]" => {
+                    section = Section::Actions;
+                    continue;
+                }
+                "[axes]" => {
+                    section = Section::Axes;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let separator = line
+                .find('=')
+                .ok_or_else(|| error("expected 'name = bindings'".to_owned()))?;
+            let name = line[..separator].trim();
+            let bindings = line[separator + 1..]
+                .split(',')
+                .map(str::trim)
+                .filter(|binding| !binding.is_empty());
+
+            if name.is_empty() {
+                return Err(error("missing action or axis name".to_owned()));
+            }
+            check_name(name).map_err(|e| error(format!("{}", e)))?;
+
+            match section {
+                Section::Actions => {
+                    map.actions.entry(name.to_owned()).or_default();
+                    for binding in bindings {
+                        let chord: Chord = binding.parse().map_err(|e| error(format!("{}", e)))?;
+                        map.bind(name, chord)?;
+                    }
+                }
+                Section::Axes => {
+                    map.axes.entry(name.to_owned()).or_default();
+                    for binding in bindings {
+                        let binding = binding.parse().map_err(|e| error(format!("{}", e)))?;
+                        map.bind_axis(name, binding)?;
+                    }
+                }
+                Section::None => {
+                    return Err(error(
+                        "binding outside of an [actions] or [axes] section".to_owned(),
+                    ))
+                }
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_survive_display_and_parse() {
+        let mut map = ActionMap::new();
+        map.bind("fire", MouseButton::Left)
+            .unwrap()
+            .bind("fire", Key::Space)
+            .unwrap()
+            .bind(
+                "save",
+                Chord::new(vec![Button::Key(Key::LeftControl), Button::Key(Key::S)]),
+            )
+            .unwrap()
+            .bind("jump", GamepadButton::A)
+            .unwrap();
+        map.bind_axis(
+            "move x",
+            AxisBinding::Buttons {
+                negative: Key::A.into(),
+                positive: Key::D.into(),
+            },
+        )
+        .unwrap()
+        .bind_axis("move x", AxisBinding::Gamepad(GamepadAxis::LeftX))
+        .unwrap()
+        .bind_axis("zoom", AxisBinding::ScrollY)
+        .unwrap();
+        map.rebind("unbound", Key::Escape).unwrap();
+        map.clear_action("unbound");
+
+        let parsed: ActionMap = map.to_string().parse().unwrap();
+        assert_eq!(parsed, map);
+    }
+
+    #[test]
+    fn names_that_would_not_read_back_are_rejected() {
+        let mut map = ActionMap::new();
+        for name in ["", " fire", "a=b", "a,b", "a#b", "a\nb"] {
+            assert!(
+                matches!(
+                    map.bind(name, Key::Space),
+                    Err(BindingError::InvalidName(_))
+                ),
+                "{:?} was accepted",
+                name
+            );
+            assert!(map.bind_axis(name, AxisBinding::ScrollX).is_err());
+        }
+        assert_eq!(map, ActionMap::new());
+    }
+
+    #[test]
+    fn parse_errors_report_the_line() {
+        let error = "[actions]\nfire = key:Nope\n"
+            .parse::<ActionMap>()
+            .unwrap_err();
+        assert!(matches!(error, BindingError::Parse { line: 2, .. }));
+        assert!("fire = key:Space".parse::<ActionMap>().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one button")]
+    fn empty_chords_panic() {
+        Chord::new(Vec::new());
+    }
+}
@@ -0,0 +1,523 @@
+use crate::maths::Vector2;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GamepadError {
+    #[error("failed to parse the gamepad mapping database")]
+    InvalidMappings,
+    #[error("failed to read the gamepad mapping database")]
+    Io(#[from] std::io::Error),
+    #[error("gamepads are not supported by the windowing backend")]
+    Unsupported,
+}
+
+/// Identifies one of the joystick slots. Slots are assigned by the platform
+/// when a device is connected and stay the same until it is disconnected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GamepadId(u8);
+
+impl GamepadId {
+    pub const COUNT: usize = 16;
+
+    pub fn new(index: usize) -> Option<Self> {
+        if index < GamepadId::COUNT {
+            Some(GamepadId(index as u8))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn all() -> impl Iterator<Item = GamepadId> {
+        (0..GamepadId::COUNT as u8).map(GamepadId)
+    }
+}
+
+/// Buttons of a gamepad using the Xbox controller layout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+}
+
+impl GamepadButton {
+    pub const COUNT: usize = 15;
+
+    pub const ALL: [GamepadButton; GamepadButton::COUNT] = [
+        GamepadButton::A,
+        GamepadButton::B,
+        GamepadButton::X,
+        GamepadButton::Y,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::Back,
+        GamepadButton::Start,
+        GamepadButton::Guide,
+        GamepadButton::LeftThumb,
+        GamepadButton::RightThumb,
+        GamepadButton::DpadUp,
+        GamepadButton::DpadRight,
+        GamepadButton::DpadDown,
+        GamepadButton::DpadLeft,
+    ];
+
+    #[inline]
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GamepadButton::A => "A",
+            GamepadButton::B => "B",
+            GamepadButton::X => "X",
+            GamepadButton::Y => "Y",
+            GamepadButton::LeftBumper => "LeftBumper",
+            GamepadButton::RightBumper => "RightBumper",
+            GamepadButton::Back => "Back",
+            GamepadButton::Start => "Start",
+            GamepadButton::Guide => "Guide",
+            GamepadButton::LeftThumb => "LeftThumb",
+            GamepadButton::RightThumb => "RightThumb",
+            GamepadButton::DpadUp => "DpadUp",
+            GamepadButton::DpadRight => "DpadRight",
+            GamepadButton::DpadDown => "DpadDown",
+            GamepadButton::DpadLeft => "DpadLeft",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<GamepadButton> {
+        GamepadButton::ALL
+            .iter()
+            .copied()
+            .find(|button| button.name() == name)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    pub const COUNT: usize = 6;
+
+    pub const ALL: [GamepadAxis; GamepadAxis::COUNT] = [
+        GamepadAxis::LeftX,
+        GamepadAxis::LeftY,
+        GamepadAxis::RightX,
+        GamepadAxis::RightY,
+        GamepadAxis::LeftTrigger,
+        GamepadAxis::RightTrigger,
+    ];
+
+    #[inline]
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    #[inline]
+    pub fn is_trigger(&self) -> bool {
+        matches!(self, GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GamepadAxis::LeftX => "LeftX",
+            GamepadAxis::LeftY => "LeftY",
+            GamepadAxis::RightX => "RightX",
+            GamepadAxis::RightY => "RightY",
+            GamepadAxis::LeftTrigger => "LeftTrigger",
+            GamepadAxis::RightTrigger => "RightTrigger",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<GamepadAxis> {
+        GamepadAxis::ALL
+            .iter()
+            .copied()
+            .find(|axis| axis.name() == name)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GamepadEvent {
+    Connected(GamepadId),
+    Disconnected(GamepadId),
+}
+
+/// The raw state of a gamepad at one point in time, as reported by a
+/// `GamepadSource`. Sticks are in [-1, 1] with +y pointing down, triggers are
+/// in [-1, 1] with -1 at rest.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GamepadSnapshot {
+    pub buttons: [bool; GamepadButton::COUNT],
+    pub axes: [f32; GamepadAxis::COUNT],
+}
+
+impl Default for GamepadSnapshot {
+    fn default() -> Self {
+        let mut axes = [0.0; GamepadAxis::COUNT];
+        axes[GamepadAxis::LeftTrigger.index()] = -1.0;
+        axes[GamepadAxis::RightTrigger.index()] = -1.0;
+
+        GamepadSnapshot {
+            buttons: [false; GamepadButton::COUNT],
+            axes,
+        }
+    }
+}
+
+/// Where gamepad state is polled from. The event loop uses the window
+/// backend's gamepad support by default, such as GLFW's joystick API; tests
+/// and replays can supply their own implementation with
+/// `EventLoop::set_gamepad_source`.
+pub trait GamepadSource {
+    /// Returns None if no gamepad is connected to the slot `id`, or if the
+    /// connected joystick has no gamepad mapping.
+    fn poll(&mut self, id: GamepadId) -> Option<GamepadSnapshot>;
+
+    fn name(&self, id: GamepadId) -> Option<String>;
+
+    /// Adds SDL_GameControllerDB style mappings, one per line.
+    fn update_mappings(&mut self, mappings: &str) -> Result<(), GamepadError>;
+}
+
+#[cfg(feature = "glfw")]
+pub struct GlfwGamepadSource {
+    glfw: glfw::Glfw,
+}
+
+#[cfg(feature = "glfw")]
+impl GlfwGamepadSource {
+    pub fn new(glfw: glfw::Glfw) -> Self {
+        GlfwGamepadSource { glfw }
+    }
+
+    /// Returns None for slots past GLFW's last joystick.
+    fn joystick(&self, id: GamepadId) -> Option<glfw::Joystick> {
+        match glfw::JoystickId::from_i32(id.index() as i32) {
+            Some(joystick_id) => Some(self.glfw.get_joystick(joystick_id)),
+            None => {
+                log::warn!(
+                    "gamepad slot {} is out of GLFW's joystick range",
+                    id.index()
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(feature = "glfw")]
+impl GamepadSource for GlfwGamepadSource {
+    fn poll(&mut self, id: GamepadId) -> Option<GamepadSnapshot> {
+        let joystick = self.joystick(id)?;
+        if !joystick.is_present() || !joystick.is_gamepad() {
+            return None;
+        }
+
+        let state = joystick.get_gamepad_state()?;
+        let mut snapshot = GamepadSnapshot::default();
+        for button in GamepadButton::ALL.iter() {
+            let glfw_button = glfw::GamepadButton::from_i32(button.index() as i32)?;
+            snapshot.buttons[button.index()] =
+                state.get_button_state(glfw_button) != glfw::Action::Release;
+        }
+        for axis in GamepadAxis::ALL.iter() {
+            let glfw_axis = glfw::GamepadAxis::from_i32(axis.index() as i32)?;
+            snapshot.axes[axis.index()] = state.get_axis(glfw_axis);
+        }
+
+        Some(snapshot)
+    }
+
+    fn name(&self, id: GamepadId) -> Option<String> {
+        self.joystick(id)?.get_gamepad_name()
+    }
+
+    fn update_mappings(&mut self, mappings: &str) -> Result<(), GamepadError> {
+        if self.glfw.update_gamepad_mappings(mappings) {
+            Ok(())
+        } else {
+            Err(GamepadError::InvalidMappings)
+        }
+    }
+}
+
+/// A connected gamepad. Button transitions compare the latest snapshot with
+/// the one from the previous frame.
+#[derive(Debug, Clone)]
+pub struct Gamepad {
+    name: String,
+    current: GamepadSnapshot,
+    previous: GamepadSnapshot,
+    deadzone: f32,
+}
+
+impl Gamepad {
+    fn new(name: String, snapshot: GamepadSnapshot, deadzone: f32) -> Self {
+        Gamepad {
+            name,
+            current: snapshot,
+            previous: GamepadSnapshot::default(),
+            deadzone,
+        }
+    }
+
+    fn update(&mut self, snapshot: GamepadSnapshot) {
+        self.previous = self.current;
+        self.current = snapshot;
+    }
+
+    #[inline]
+    pub fn name(&self) -> &'_ str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn is_button_down(&self, button: GamepadButton) -> bool {
+        self.current.buttons[button.index()]
+    }
+
+    #[inline]
+    pub fn was_button_pressed(&self, button: GamepadButton) -> bool {
+        self.current.buttons[button.index()] && !self.previous.buttons[button.index()]
+    }
+
+    #[inline]
+    pub fn was_button_released(&self, button: GamepadButton) -> bool {
+        !self.current.buttons[button.index()] && self.previous.buttons[button.index()]
+    }
+
+    /// The value of `axis` with the deadzone applied. Sticks are in [-1, 1]
+    /// and triggers are remapped to [0, 1].
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        let value = self.raw_axis(axis);
+        if axis.is_trigger() {
+            apply_deadzone((value + 1.0) / 2.0, self.deadzone)
+        } else {
+            apply_deadzone(value, self.deadzone)
+        }
+    }
+
+    /// The value of `axis` exactly as reported by the device.
+    #[inline]
+    pub fn raw_axis(&self, axis: GamepadAxis) -> f32 {
+        self.current.axes[axis.index()]
+    }
+
+    pub fn left_stick(&self) -> Vector2 {
+        Vector2::new(self.axis(GamepadAxis::LeftX), self.axis(GamepadAxis::LeftY))
+    }
+
+    pub fn right_stick(&self) -> Vector2 {
+        Vector2::new(
+            self.axis(GamepadAxis::RightX),
+            self.axis(GamepadAxis::RightY),
+        )
+    }
+
+    #[inline]
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone
+    }
+}
+
+/// Zeroes values within `deadzone` of rest and rescales the remainder so the
+/// output still covers the full range.
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= deadzone {
+        0.0
+    } else {
+        let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+        scaled.copysign(value)
+    }
+}
+
+/// The gamepads connected to each joystick slot.
+#[derive(Debug, Clone)]
+pub struct Gamepads {
+    slots: Vec<Option<Gamepad>>,
+    deadzone: f32,
+}
+
+impl Gamepads {
+    pub const DEFAULT_DEADZONE: f32 = 0.15;
+
+    pub fn new() -> Self {
+        Gamepads {
+            slots: vec![None; GamepadId::COUNT],
+            deadzone: Gamepads::DEFAULT_DEADZONE,
+        }
+    }
+
+    /// Polls every slot of `source`, returning the gamepads that were
+    /// connected or disconnected since the last update.
+    pub fn update(&mut self, source: &mut dyn GamepadSource) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+
+        for id in GamepadId::all() {
+            let slot = &mut self.slots[id.index()];
+            match (source.poll(id), slot.as_mut()) {
+                (Some(snapshot), Some(gamepad)) => gamepad.update(snapshot),
+                (Some(snapshot), None) => {
+                    let name = source.name(id).unwrap_or_default();
+                    *slot = Some(Gamepad::new(name, snapshot, self.deadzone));
+                    events.push(GamepadEvent::Connected(id));
+                }
+                (None, Some(_)) => {
+                    *slot = None;
+                    events.push(GamepadEvent::Disconnected(id));
+                }
+                (None, None) => {}
+            }
+        }
+
+        events
+    }
+
+    #[inline]
+    pub fn get(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.slots[id.index()].as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
+        GamepadId::all().filter_map(move |id| self.get(id).map(|gamepad| (id, gamepad)))
+    }
+
+    /// Sets the stick and trigger deadzone, in [0, 1), of every current and
+    /// future gamepad.
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        debug_assert!((0.0..1.0).contains(&deadzone));
+        self.deadzone = deadzone;
+        for gamepad in self.slots.iter_mut().flatten() {
+            gamepad.deadzone = deadzone;
+        }
+    }
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Gamepads::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reports whatever snapshots the test puts into its slots.
+    #[derive(Default)]
+    struct FakeSource {
+        slots: [Option<GamepadSnapshot>; GamepadId::COUNT],
+    }
+
+    impl GamepadSource for FakeSource {
+        fn poll(&mut self, id: GamepadId) -> Option<GamepadSnapshot> {
+            self.slots[id.index()]
+        }
+
+        fn name(&self, id: GamepadId) -> Option<String> {
+            Some(format!("Fake {}", id.index()))
+        }
+
+        fn update_mappings(&mut self, _mappings: &str) -> Result<(), GamepadError> {
+            Err(GamepadError::Unsupported)
+        }
+    }
+
+    #[test]
+    fn connecting_and_disconnecting_emits_events() {
+        let mut source = FakeSource::default();
+        let mut gamepads = Gamepads::new();
+        let id = GamepadId::new(3).unwrap();
+        assert!(gamepads.update(&mut source).is_empty());
+
+        source.slots[3] = Some(GamepadSnapshot::default());
+        assert_eq!(gamepads.update(&mut source), [GamepadEvent::Connected(id)]);
+        assert_eq!(gamepads.get(id).unwrap().name(), "Fake 3");
+        assert!(gamepads.update(&mut source).is_empty());
+
+        source.slots[3] = None;
+        assert_eq!(
+            gamepads.update(&mut source),
+            [GamepadEvent::Disconnected(id)]
+        );
+        assert!(gamepads.get(id).is_none());
+    }
+
+    #[test]
+    fn button_transitions_compare_with_the_previous_update() {
+        let mut source = FakeSource::default();
+        let mut gamepads = Gamepads::new();
+        let id = GamepadId::new(0).unwrap();
+        let mut snapshot = GamepadSnapshot::default();
+        source.slots[0] = Some(snapshot);
+        gamepads.update(&mut source);
+
+        snapshot.buttons[GamepadButton::A.index()] = true;
+        source.slots[0] = Some(snapshot);
+        gamepads.update(&mut source);
+        assert!(gamepads
+            .get(id)
+            .unwrap()
+            .was_button_pressed(GamepadButton::A));
+
+        gamepads.update(&mut source);
+        let gamepad = gamepads.get(id).unwrap();
+        assert!(gamepad.is_button_down(GamepadButton::A));
+        assert!(!gamepad.was_button_pressed(GamepadButton::A));
+    }
+
+    #[test]
+    fn deadzone_zeroes_small_values_and_rescales_the_rest() {
+        assert_eq!(apply_deadzone(0.1, 0.2), 0.0);
+        assert_eq!(apply_deadzone(-0.2, 0.2), 0.0);
+        assert!((apply_deadzone(0.6, 0.2) - 0.5).abs() < 1e-6);
+        assert!((apply_deadzone(-0.6, 0.2) + 0.5).abs() < 1e-6);
+        assert_eq!(apply_deadzone(1.0, 0.2), 1.0);
+        assert_eq!(apply_deadzone(-1.0, 0.2), -1.0);
+    }
+
+    #[test]
+    fn triggers_are_remapped_before_the_deadzone() {
+        let mut source = FakeSource::default();
+        let mut gamepads = Gamepads::new();
+        gamepads.set_deadzone(0.2);
+        let mut snapshot = GamepadSnapshot::default();
+        snapshot.axes[GamepadAxis::LeftX.index()] = 0.1;
+        snapshot.axes[GamepadAxis::LeftY.index()] = -0.6;
+        source.slots[0] = Some(snapshot);
+        gamepads.update(&mut source);
+
+        let gamepad = gamepads.get(GamepadId::new(0).unwrap()).unwrap();
+        assert_eq!(gamepad.axis(GamepadAxis::LeftTrigger), 0.0);
+        assert_eq!(gamepad.left_stick().x, 0.0);
+        assert!((gamepad.left_stick().y + 0.5).abs() < 1e-6);
+        assert_eq!(gamepad.raw_axis(GamepadAxis::LeftX), 0.1);
+    }
+}
@@ -0,0 +1,50 @@
+macro_rules! keys {
+    ($($key:ident),* $(,)?) => {
+        /// A keyboard key, named after its position on a US keyboard layout.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum Key {
+            $($key),*
+        }
+
+        impl Key {
+            #[cfg(feature = "glfw")]
+            pub(crate) fn from_glfw(key: glfw::Key) -> Key {
+                match key {
+                    $(glfw::Key::$key => Key::$key),*
+                }
+            }
+
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    $(Key::$key => stringify!($key)),*
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<Key> {
+                match name {
+                    $(stringify!($key) => Some(Key::$key),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    Space, Apostrophe, Comma, Minus, Period, Slash,
+    Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9,
+    Semicolon, Equal,
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    LeftBracket, Backslash, RightBracket, GraveAccent, World1, World2,
+    Escape, Enter, Tab, Backspace, Insert, Delete,
+    Right, Left, Down, Up, PageUp, PageDown, Home, End,
+    CapsLock, ScrollLock, NumLock, PrintScreen, Pause,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13,
+    F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24, F25,
+    Kp0, Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9,
+    KpDecimal, KpDivide, KpMultiply, KpSubtract, KpAdd, KpEnter, KpEqual,
+    LeftShift, LeftControl, LeftAlt, LeftSuper,
+    RightShift, RightControl, RightAlt, RightSuper,
+    Menu, Unknown,
+}
@@ -0,0 +1,17 @@
+mod action;
+pub use self::action::*;
+
+mod gamepad;
+pub use self::gamepad::*;
+
+mod key;
+pub use self::key::*;
+
+mod modifiers;
+pub use self::modifiers::*;
+
+mod mouse;
+pub use self::mouse::*;
+
+mod state;
+pub use self::state::*;
@@ -0,0 +1,56 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// The modifier keys held when a key or mouse button event happened.
+    pub struct Modifiers: u32 {
+        const SHIFT = 1 << 0;
+        const CONTROL = 1 << 1;
+        const ALT = 1 << 2;
+        const SUPER = 1 << 3;
+        const CAPS_LOCK = 1 << 4;
+        const NUM_LOCK = 1 << 5;
+    }
+}
+
+impl Modifiers {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn from_glfw(modifiers: glfw::Modifiers) -> Modifiers {
+        let mut result = Modifiers::empty();
+        result.set(Modifiers::SHIFT, modifiers.contains(glfw::Modifiers::Shift));
+        result.set(
+            Modifiers::CONTROL,
+            modifiers.contains(glfw::Modifiers::Control),
+        );
+        result.set(Modifiers::ALT, modifiers.contains(glfw::Modifiers::Alt));
+        result.set(Modifiers::SUPER, modifiers.contains(glfw::Modifiers::Super));
+        result.set(
+            Modifiers::CAPS_LOCK,
+            modifiers.contains(glfw::Modifiers::CapsLock),
+        );
+        result.set(
+            Modifiers::NUM_LOCK,
+            modifiers.contains(glfw::Modifiers::NumLock),
+        );
+        result
+    }
+}
+
+/// What happened to a key or mouse button. `Repeat` is only reported for keys
+/// held long enough to trigger the platform's key repeat.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ButtonAction {
+    Press,
+    Release,
+    Repeat,
+}
+
+impl ButtonAction {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn from_glfw(action: glfw::Action) -> ButtonAction {
+        match action {
+            glfw::Action::Press => ButtonAction::Press,
+            glfw::Action::Release => ButtonAction::Release,
+            glfw::Action::Repeat => ButtonAction::Repeat,
+        }
+    }
+}
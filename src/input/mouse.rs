@@ -0,0 +1,54 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Button4,
+    Button5,
+    Button6,
+    Button7,
+    Button8,
+}
+
+impl MouseButton {
+    #[cfg(feature = "glfw")]
+    pub(crate) fn from_glfw(button: glfw::MouseButton) -> MouseButton {
+        match button {
+            glfw::MouseButton::Button1 => MouseButton::Left,
+            glfw::MouseButton::Button2 => MouseButton::Right,
+            glfw::MouseButton::Button3 => MouseButton::Middle,
+            glfw::MouseButton::Button4 => MouseButton::Button4,
+            glfw::MouseButton::Button5 => MouseButton::Button5,
+            glfw::MouseButton::Button6 => MouseButton::Button6,
+            glfw::MouseButton::Button7 => MouseButton::Button7,
+            glfw::MouseButton::Button8 => MouseButton::Button8,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            MouseButton::Left => "Left",
+            MouseButton::Right => "Right",
+            MouseButton::Middle => "Middle",
+            MouseButton::Button4 => "Button4",
+            MouseButton::Button5 => "Button5",
+            MouseButton::Button6 => "Button6",
+            MouseButton::Button7 => "Button7",
+            MouseButton::Button8 => "Button8",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<MouseButton> {
+        match name {
+            "Left" => Some(MouseButton::Left),
+            "Right" => Some(MouseButton::Right),
+            "Middle" => Some(MouseButton::Middle),
+            "Button4" => Some(MouseButton::Button4),
+            "Button5" => Some(MouseButton::Button5),
+            "Button6" => Some(MouseButton::Button6),
+            "Button7" => Some(MouseButton::Button7),
+            "Button8" => Some(MouseButton::Button8),
+            _ => None,
+        }
+    }
+}
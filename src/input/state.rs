@@ -0,0 +1,173 @@
+use crate::input::{Gamepad, GamepadEvent, GamepadId, GamepadSource, Gamepads, Key, MouseButton};
+use crate::maths::{Vector, Vector2};
+use std::collections::HashSet;
+
+/// Keyboard, mouse, scroll and gamepad state, updated once per frame by the
+/// `EventLoop`.
+///
+/// "Pressed" and "released" only hold for the frame the transition happened
+/// in, while "down" holds for as long as the key or button is held.
+#[derive(Debug, Clone)]
+pub struct Input {
+    keys_down: HashSet<Key>,
+    keys_pressed: HashSet<Key>,
+    keys_released: HashSet<Key>,
+
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed: HashSet<MouseButton>,
+    mouse_buttons_released: HashSet<MouseButton>,
+
+    mouse_position: Option<Vector2>,
+    mouse_delta: Vector2,
+    scroll_delta: Vector2,
+
+    gamepads: Gamepads,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Input {
+            keys_down: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            keys_released: HashSet::new(),
+            mouse_buttons_down: HashSet::new(),
+            mouse_buttons_pressed: HashSet::new(),
+            mouse_buttons_released: HashSet::new(),
+            mouse_position: None,
+            mouse_delta: Vector2::zero(),
+            scroll_delta: Vector2::zero(),
+            gamepads: Gamepads::new(),
+        }
+    }
+
+    /// Clears the per-frame transitions and deltas. Called by the event loop
+    /// before it processes a new batch of events.
+    pub fn begin_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.mouse_buttons_pressed.clear();
+        self.mouse_buttons_released.clear();
+        self.mouse_delta = Vector2::zero();
+        self.scroll_delta = Vector2::zero();
+    }
+
+    pub fn press_key(&mut self, key: Key) {
+        if self.keys_down.insert(key) {
+            self.keys_pressed.insert(key);
+        }
+    }
+
+    pub fn release_key(&mut self, key: Key) {
+        if self.keys_down.remove(&key) {
+            self.keys_released.insert(key);
+        }
+    }
+
+    pub fn press_mouse_button(&mut self, button: MouseButton) {
+        if self.mouse_buttons_down.insert(button) {
+            self.mouse_buttons_pressed.insert(button);
+        }
+    }
+
+    pub fn release_mouse_button(&mut self, button: MouseButton) {
+        if self.mouse_buttons_down.remove(&button) {
+            self.mouse_buttons_released.insert(button);
+        }
+    }
+
+    /// Moves the cursor to `position`, in screen coordinates relative to the
+    /// top-left corner of the window. The first position reported does not
+    /// produce a delta.
+    pub fn set_mouse_position(&mut self, position: Vector2) {
+        if let Some(previous) = self.mouse_position {
+            self.mouse_delta += position - previous;
+        }
+        self.mouse_position = Some(position);
+    }
+
+    pub fn scroll(&mut self, offset: Vector2) {
+        self.scroll_delta += offset;
+    }
+
+    /// Polls every gamepad slot of `source`, returning the gamepads that were
+    /// connected or disconnected since the last update.
+    pub fn update_gamepads(&mut self, source: &mut dyn GamepadSource) -> Vec<GamepadEvent> {
+        self.gamepads.update(source)
+    }
+
+    #[inline]
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    #[inline]
+    pub fn was_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    #[inline]
+    pub fn was_key_released(&self, key: Key) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    /// The keys pressed during the last frame, in no particular order.
+    pub fn pressed_keys(&self) -> impl Iterator<Item = Key> + '_ {
+        self.keys_pressed.iter().copied()
+    }
+
+    #[inline]
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&button)
+    }
+
+    #[inline]
+    pub fn was_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed.contains(&button)
+    }
+
+    #[inline]
+    pub fn was_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_released.contains(&button)
+    }
+
+    /// The mouse buttons pressed during the last frame, in no particular order.
+    pub fn pressed_mouse_buttons(&self) -> impl Iterator<Item = MouseButton> + '_ {
+        self.mouse_buttons_pressed.iter().copied()
+    }
+
+    #[inline]
+    pub fn mouse_position(&self) -> Vector2 {
+        self.mouse_position.unwrap_or_else(Vector2::zero)
+    }
+
+    #[inline]
+    pub fn mouse_delta(&self) -> Vector2 {
+        self.mouse_delta
+    }
+
+    #[inline]
+    pub fn scroll_delta(&self) -> Vector2 {
+        self.scroll_delta
+    }
+
+    #[inline]
+    pub fn gamepad(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.gamepads.get(id)
+    }
+
+    #[inline]
+    pub fn gamepads(&self) -> &Gamepads {
+        &self.gamepads
+    }
+
+    #[inline]
+    pub fn gamepads_mut(&mut self) -> &mut Gamepads {
+        &mut self.gamepads
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::new()
+    }
+}
@@ -1,2 +1,6 @@
+pub mod app;
+pub mod ecs;
 pub mod graphics;
+pub mod input;
 pub mod maths;
+pub mod time;
@@ -1,5 +1,8 @@
 mod matrix;
 pub use self::matrix::*;
 
+mod transform;
+pub use self::transform::*;
+
 mod vector;
 pub use self::vector::*;
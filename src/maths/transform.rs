@@ -0,0 +1,42 @@
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform {
+    pub translation: Vector3,
+    pub rotation: Vector3,
+    pub scale: Vector3,
+}
+
+impl Transform {
+    pub fn new(translation: Vector3, rotation: Vector3, scale: Vector3) -> Self {
+        Transform {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn from_translation(translation: Vector3) -> Self {
+        Transform {
+            translation,
+            ..Default::default()
+        }
+    }
+
+    /// Builds the transformation matrix uploaded to shaders, equivalent to
+    /// `Matrix4::transformation(&translation, &rotation, &scale)`.
+    #[inline]
+    pub fn matrix(&self) -> Matrix4 {
+        Matrix4::transformation(&self.translation, &self.rotation, &self.scale)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            translation: Vector3::zero(),
+            rotation: Vector3::zero(),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
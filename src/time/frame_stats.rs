@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Frame time statistics over a sliding window of recent frames.
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    frame_times: VecDeque<Duration>,
+    capacity: usize,
+    total: Duration,
+}
+
+impl FrameStats {
+    pub const DEFAULT_WINDOW: usize = 120;
+
+    /// Keeps statistics over the last `window` frames.
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0);
+        FrameStats {
+            frame_times: VecDeque::with_capacity(window),
+            capacity: window,
+            total: Duration::from_secs(0),
+        }
+    }
+
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == self.capacity {
+            if let Some(oldest) = self.frame_times.pop_front() {
+                self.total -= oldest;
+            }
+        }
+        self.frame_times.push_back(frame_time);
+        self.total += frame_time;
+    }
+
+    /// The number of frames currently in the window.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.frame_times.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.frame_times.is_empty()
+    }
+
+    pub fn average_frame_time(&self) -> Duration {
+        if self.frame_times.is_empty() {
+            Duration::from_secs(0)
+        } else {
+            self.total / self.frame_times.len() as u32
+        }
+    }
+
+    pub fn min_frame_time(&self) -> Duration {
+        self.frame_times.iter().copied().min().unwrap_or_default()
+    }
+
+    pub fn max_frame_time(&self) -> Duration {
+        self.frame_times.iter().copied().max().unwrap_or_default()
+    }
+
+    /// Frames per second, from the average frame time.
+    pub fn fps(&self) -> f32 {
+        let average = self.average_frame_time().as_secs_f32();
+        if average > 0.0 {
+            1.0 / average
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        FrameStats::new(FrameStats::DEFAULT_WINDOW)
+    }
+}
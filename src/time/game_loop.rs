@@ -0,0 +1,217 @@
+use crate::time::{Clock, FrameStats, SystemClock};
+use std::time::Duration;
+
+/// Timing for one frame, returned by `GameLoop::tick`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tick {
+    /// Time since the previous tick, after clamping to the loop's maximum
+    /// frame time.
+    pub delta: Duration,
+    /// How many fixed updates to run this frame.
+    pub fixed_steps: u32,
+    /// The duration each fixed update simulates.
+    pub fixed_delta: Duration,
+    /// How far the current time is between the last fixed update and the
+    /// next one, in [0, 1). Use it to interpolate rendered state.
+    pub alpha: f32,
+    /// The index of this frame, starting at 0.
+    pub frame: u64,
+}
+
+impl Tick {
+    #[inline]
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    #[inline]
+    pub fn fixed_delta_seconds(&self) -> f32 {
+        self.fixed_delta.as_secs_f32()
+    }
+}
+
+/// Decouples simulation from rendering: the simulation advances in fixed
+/// steps while frames are rendered as fast as they come.
+///
+/// ```ignore
+/// let mut game_loop = GameLoop::new(60);
+/// while !window.should_close() {
+///     let tick = game_loop.tick();
+///     for _ in 0..tick.fixed_steps {
+///         simulate(tick.fixed_delta_seconds());
+///     }
+///     render(tick.alpha);
+/// }
+/// ```
+///
+/// Frames longer than the maximum frame time are clamped, so a stall (a
+/// debugger break, a slow load) does not queue up more fixed updates than
+/// the game can catch up on.
+pub struct GameLoop<C: Clock = SystemClock> {
+    clock: C,
+    fixed_delta: Duration,
+    max_frame_time: Duration,
+
+    last_time: Option<Duration>,
+    accumulator: Duration,
+
+    frame: u64,
+    fixed_update_count: u64,
+    stats: FrameStats,
+}
+
+impl GameLoop<SystemClock> {
+    pub fn new(updates_per_second: u32) -> Self {
+        GameLoop::with_clock(SystemClock::new(), updates_per_second)
+    }
+}
+
+impl<C: Clock> GameLoop<C> {
+    pub const DEFAULT_MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+
+    pub fn with_clock(clock: C, updates_per_second: u32) -> Self {
+        assert!(updates_per_second > 0);
+        GameLoop {
+            clock,
+            fixed_delta: Duration::from_secs(1) / updates_per_second,
+            max_frame_time: Self::DEFAULT_MAX_FRAME_TIME,
+            last_time: None,
+            accumulator: Duration::from_secs(0),
+            frame: 0,
+            fixed_update_count: 0,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Sets the longest frame the loop will simulate. Longer frames are
+    /// clamped to this duration.
+    pub fn set_max_frame_time(&mut self, max_frame_time: Duration) {
+        self.max_frame_time = max_frame_time;
+    }
+
+    /// Advances the loop to the clock's current time. Call once per frame.
+    ///
+    /// The first tick has a zero delta and runs no fixed updates.
+    pub fn tick(&mut self) -> Tick {
+        let now = self.clock.now();
+        let elapsed = match self.last_time {
+            Some(last) => now.checked_sub(last).unwrap_or_default(),
+            None => Duration::from_secs(0),
+        };
+        self.last_time = Some(now);
+
+        let delta = elapsed.min(self.max_frame_time);
+        if self.frame > 0 {
+            self.stats.record(elapsed);
+        }
+
+        self.accumulator += delta;
+        let mut fixed_steps = 0;
+        while self.accumulator >= self.fixed_delta {
+            self.accumulator -= self.fixed_delta;
+            fixed_steps += 1;
+        }
+        self.fixed_update_count += fixed_steps as u64;
+
+        let tick = Tick {
+            delta,
+            fixed_steps,
+            fixed_delta: self.fixed_delta,
+            alpha: self.accumulator.as_secs_f32() / self.fixed_delta.as_secs_f32(),
+            frame: self.frame,
+        };
+        self.frame += 1;
+
+        tick
+    }
+
+    #[inline]
+    pub fn fixed_delta(&self) -> Duration {
+        self.fixed_delta
+    }
+
+    /// The number of frames ticked so far.
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.frame
+    }
+
+    /// The number of fixed updates handed out so far.
+    #[inline]
+    pub fn fixed_update_count(&self) -> u64 {
+        self.fixed_update_count
+    }
+
+    /// Statistics of the unclamped frame times of recent frames.
+    #[inline]
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    #[inline]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::ManualClock;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// A loop of 50 updates per second, so 20ms per fixed step, past its
+    /// first tick.
+    fn game_loop() -> (GameLoop<ManualClock>, ManualClock) {
+        let clock = ManualClock::new();
+        let mut game_loop = GameLoop::with_clock(clock.clone(), 50);
+        let first = game_loop.tick();
+        assert_eq!((first.delta, first.fixed_steps), (millis(0), 0));
+        (game_loop, clock)
+    }
+
+    #[test]
+    fn fixed_steps_accumulate_across_frames() {
+        let (mut game_loop, clock) = game_loop();
+
+        clock.advance(millis(30));
+        let tick = game_loop.tick();
+        assert_eq!((tick.delta, tick.fixed_steps), (millis(30), 1));
+
+        clock.advance(millis(30));
+        assert_eq!(game_loop.tick().fixed_steps, 2);
+
+        clock.advance(millis(10));
+        assert_eq!(game_loop.tick().fixed_steps, 0);
+        assert_eq!(game_loop.fixed_update_count(), 3);
+        assert_eq!(game_loop.frame_count(), 4);
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let (mut game_loop, clock) = game_loop();
+        game_loop.set_max_frame_time(millis(100));
+
+        clock.advance(Duration::from_secs(5));
+        let tick = game_loop.tick();
+        assert_eq!(tick.delta, millis(100));
+        assert_eq!(tick.fixed_steps, 5);
+        assert_eq!(game_loop.stats().max_frame_time(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn alpha_is_the_fraction_of_the_next_step() {
+        let (mut game_loop, clock) = game_loop();
+
+        clock.advance(millis(25));
+        let tick = game_loop.tick();
+        assert_eq!(tick.fixed_steps, 1);
+        assert!((tick.alpha - 0.25).abs() < 1e-6);
+
+        clock.advance(millis(10));
+        assert!((game_loop.tick().alpha - 0.75).abs() < 1e-6);
+    }
+}
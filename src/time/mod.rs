@@ -0,0 +1,8 @@
+mod clock;
+pub use self::clock::*;
+
+mod frame_stats;
+pub use self::frame_stats::*;
+
+mod game_loop;
+pub use self::game_loop::*;
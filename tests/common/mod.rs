@@ -0,0 +1,134 @@
+//! Golden image testing: rendered images are compared against reference PNGs
+//! stored in `tests/golden`.
+//!
+//! A missing reference fails the test. Running with `CREAN_BLESS=1` records
+//! every reference from the rendered images instead, review and commit the
+//! new PNGs. On a mismatch, the rendered image and a diff image marking the
+//! differing pixels in red are written next to the test binaries, in
+//! `target/tmp/golden`.
+//!
+//! Rendering tests run on Mesa's software rasterizer through its surfaceless
+//! EGL platform, which needs no display but does need Mesa's libEGL, so they
+//! are ignored by default. Run them with
+//! `cargo test --features egl,png -- --ignored`.
+
+#![allow(dead_code)]
+
+use crean::graphics::{HeadlessContext, Image};
+use std::path::PathBuf;
+
+/// The largest difference allowed in each of the red, green, blue and alpha
+/// channels of a pixel. Rasterization differs slightly between drivers, so a
+/// small tolerance keeps edges from failing tests.
+pub type Tolerance = [u8; 4];
+
+pub const EXACT: Tolerance = [0; 4];
+pub const DEFAULT_TOLERANCE: Tolerance = [2; 4];
+
+/// The pixels of two images that differ by more than the tolerance.
+#[derive(Debug)]
+pub struct Mismatch {
+    pub differing_pixels: usize,
+    /// The expected image in grey, with the differing pixels in red.
+    pub diff: Image,
+}
+
+/// Compares two images of the same size channel by channel.
+pub fn compare(actual: &Image, expected: &Image, tolerance: Tolerance) -> Result<(), Mismatch> {
+    assert_eq!(actual.size(), expected.size(), "images differ in size");
+
+    let (width, height) = expected.size();
+    let mut diff = Image::new(width, height);
+    let mut differing_pixels = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let a = actual.pixel(x, y);
+            let e = expected.pixel(x, y);
+            let differs = (0..4).any(|i| a[i].abs_diff(e[i]) > tolerance[i]);
+
+            if differs {
+                differing_pixels += 1;
+                diff.set_pixel(x, y, [255, 0, 0, 255]);
+            } else {
+                let grey = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 6) as u8;
+                diff.set_pixel(x, y, [grey, grey, grey, 255]);
+            }
+        }
+    }
+
+    if differing_pixels == 0 {
+        Ok(())
+    } else {
+        Err(Mismatch {
+            differing_pixels,
+            diff,
+        })
+    }
+}
+
+/// Compares `image` against the reference `tests/golden/<name>.png`, panicking
+/// on a mismatch.
+pub fn assert_golden(name: &str, image: &Image, tolerance: Tolerance) {
+    let reference = golden_dir().join(format!("{}.png", name));
+    if std::env::var_os("CREAN_BLESS").is_some() {
+        image.save_png(&reference).unwrap();
+        eprintln!("recorded golden image {}", reference.display());
+        return;
+    }
+    if !reference.exists() {
+        let actual = output_dir().join(format!("{}.actual.png", name));
+        image.save_png(&actual).unwrap();
+        panic!(
+            "there is no golden image {}, see {} and record it with CREAN_BLESS=1",
+            reference.display(),
+            actual.display(),
+        );
+    }
+
+    let expected = Image::load_png(&reference).unwrap();
+    if image.size() != expected.size() {
+        let actual = output_dir().join(format!("{}.actual.png", name));
+        image.save_png(&actual).unwrap();
+        panic!(
+            "{} is {:?} but the golden image is {:?}, see {}",
+            name,
+            image.size(),
+            expected.size(),
+            actual.display(),
+        );
+    }
+
+    if let Err(mismatch) = compare(image, &expected, tolerance) {
+        let actual = output_dir().join(format!("{}.actual.png", name));
+        let diff = output_dir().join(format!("{}.diff.png", name));
+        image.save_png(&actual).unwrap();
+        mismatch.diff.save_png(&diff).unwrap();
+        panic!(
+            "{} differs from the golden image in {} pixels, see {} and {}",
+            name,
+            mismatch.differing_pixels,
+            actual.display(),
+            diff.display(),
+        );
+    }
+}
+
+/// Creates a software rendered offscreen context to render golden images
+/// with.
+pub fn headless(width: u32, height: u32) -> HeadlessContext {
+    HeadlessContext::offscreen(width, height).expect("failed to create an offscreen OpenGL context")
+}
+
+fn golden_dir() -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn output_dir() -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,239 @@
+//! Tests of trace capture and replay, replaying against the recording OpenGL
+//! stand-in. Run with `cargo test --features gl-mock,gl-trace`.
+
+use crean::graphics::gl::{self, mock, trace};
+use crean::graphics::{gl::ClearFlag, Buffer, PrimitiveMode, VertexArray};
+
+fn draw_frame(vertex_array: &VertexArray) {
+    gl::clear(&[ClearFlag::COLOR_BUFFER]);
+    vertex_array.draw(PrimitiveMode::Triangles);
+}
+
+#[test]
+fn captures_setup_and_the_requested_frames() {
+    mock::reset();
+    trace::capture(1, 2);
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_array_buffer(0, Buffer::array(&[0.0, 1.0, 2.0], 3));
+
+    for _ in 0..4 {
+        draw_frame(&vertex_array);
+        trace::end_frame();
+    }
+
+    assert!(!trace::is_capturing());
+    let trace = trace::take_trace().unwrap();
+    assert_eq!(trace.frames.len(), 2);
+
+    let functions = trace.calls.iter().map(|call| call.function);
+    assert_eq!(functions.clone().filter(|&f| f == "DrawArrays").count(), 2);
+    assert_eq!(functions.clone().filter(|&f| f == "GenBuffers").count(), 1);
+
+    let upload = trace
+        .calls
+        .iter()
+        .find(|call| call.function == "BufferData")
+        .unwrap();
+    let expected: Vec<u8> = [0.0f32, 1.0, 2.0]
+        .iter()
+        .flat_map(|value| value.to_ne_bytes())
+        .collect();
+    assert_eq!(upload.args[2], trace::Value::Bytes(expected));
+}
+
+#[test]
+fn traces_survive_saving_and_loading() {
+    mock::reset();
+    trace::capture(0, 1);
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_array_buffer(0, Buffer::array(&[0.5; 6], 3));
+    gl::set_clear_color(0.1, 0.2, 0.3, 1.0);
+    draw_frame(&vertex_array);
+    trace::end_frame();
+    let captured = trace::take_trace().unwrap();
+
+    let mut text = Vec::new();
+    captured.write(&mut text).unwrap();
+    let loaded = trace::Trace::read(&text[..]).unwrap();
+    assert_eq!(loaded, captured);
+
+    assert!(trace::Trace::read(&b"not a trace\n"[..]).is_err());
+}
+
+#[test]
+fn replay_remaps_objects_and_reports_draws() {
+    mock::reset();
+    trace::capture(0, 1);
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_array_buffer(0, Buffer::array(&[0.0; 9], 3));
+    draw_frame(&vertex_array);
+    draw_frame(&vertex_array);
+    trace::end_frame();
+    let captured = trace::take_trace().unwrap();
+    let traced_buffer = mock::calls_to("GenBuffers")[0].ids(1)[0];
+
+    mock::reset();
+    // Take some names first so the replayed ones differ from the traced.
+    let _other = Buffer::array(&[0.0; 3], 3);
+    mock::take_calls();
+
+    let mut draws = Vec::new();
+    captured
+        .replay(|draw| draws.push((draw.frame, draw.draw, draw.function)))
+        .unwrap();
+    assert_eq!(draws, [(0, 0, "DrawArrays"), (0, 1, "DrawArrays")]);
+
+    let replayed_buffer = mock::calls_to("GenBuffers")[0].ids(1)[0];
+    assert_ne!(replayed_buffer, traced_buffer);
+    assert!(mock::calls_to("BindBuffer")
+        .iter()
+        .filter(|call| call.int(1) != 0)
+        .all(|call| call.int(1) == replayed_buffer as i64));
+}
+
+#[test]
+fn writes_to_mapped_buffers_are_replayed() {
+    mock::reset();
+    trace::capture(0, 1);
+    let buffer = Buffer::empty_array(3, 3, gl::BufferUsage::Dynamic);
+    buffer
+        .map::<f32>(0..3, gl::MapAccess::WRITE)
+        .unwrap()
+        .copy_from_slice(&[1.0, 2.0, 3.0]);
+    trace::end_frame();
+    let captured = trace::take_trace().unwrap();
+
+    mock::reset();
+    captured.replay(|_| {}).unwrap();
+    let expected: Vec<u8> = [1.0f32, 2.0, 3.0]
+        .iter()
+        .flat_map(|value| value.to_ne_bytes())
+        .collect();
+    assert_eq!(mock::calls_to("UnmapBuffer")[0].bytes(1), expected);
+}
+
+#[test]
+fn writes_to_buffers_mapped_at_once_stay_apart() {
+    mock::reset();
+    trace::capture(0, 1);
+    let a = Buffer::empty_array(1, 1, gl::BufferUsage::Dynamic);
+    let b = Buffer::empty_array(4, 1, gl::BufferUsage::Dynamic);
+    {
+        let mut a_mapping = a.map::<f32>(0..1, gl::MapAccess::WRITE).unwrap();
+        let mut b_mapping = b.map::<f32>(0..4, gl::MapAccess::WRITE).unwrap();
+        a_mapping.copy_from_slice(&[1.0]);
+        b_mapping.copy_from_slice(&[2.0; 4]);
+    }
+    trace::end_frame();
+    let captured = trace::take_trace().unwrap();
+
+    mock::reset();
+    captured.replay(|_| {}).unwrap();
+    let unmaps = mock::calls_to("UnmapBuffer");
+    let written = |floats: &[f32]| -> Vec<u8> {
+        floats
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect()
+    };
+    assert_eq!(unmaps[0].bytes(1), written(&[2.0; 4]));
+    assert_eq!(unmaps[1].bytes(1), written(&[1.0]));
+}
+
+#[test]
+fn capturing_no_frames_finishes_at_once() {
+    mock::reset();
+    trace::capture(0, 0);
+    assert!(!trace::is_capturing());
+    assert_eq!(trace::take_trace(), Some(trace::Trace::default()));
+}
+
+fn trace_of(calls: Vec<trace::TraceCall>) -> trace::Trace {
+    trace::Trace {
+        calls,
+        frames: Vec::new(),
+    }
+}
+
+fn call(function: &'static str, args: Vec<trace::Value>) -> trace::TraceCall {
+    trace::TraceCall { function, args }
+}
+
+#[test]
+fn replay_rejects_data_shorter_than_its_size() {
+    use trace::Value::{Bytes, Int};
+
+    mock::reset();
+    let truncated = trace_of(vec![call(
+        "BufferData",
+        vec![Int(0x8892), Int(16), Bytes(vec![0; 8]), Int(0x88E4)],
+    )]);
+    assert!(matches!(
+        truncated.replay(|_| {}),
+        Err(trace::TraceError::InvalidArgument {
+            function: "BufferData",
+            index: 2,
+        })
+    ));
+
+    // 2 by 2 RGBA bytes with the default unpack alignment of 4.
+    let texture = |data: Vec<u8>| {
+        trace_of(vec![call(
+            "TexImage2D",
+            vec![
+                Int(0x0DE1),
+                Int(0),
+                Int(0x8058),
+                Int(2),
+                Int(2),
+                Int(0),
+                Int(0x1908),
+                Int(0x1401),
+                Bytes(data),
+            ],
+        )])
+    };
+    assert!(texture(vec![0; 15]).replay(|_| {}).is_err());
+    assert!(texture(vec![0; 16]).replay(|_| {}).is_ok());
+
+    let matrices = trace_of(vec![call(
+        "UniformMatrix4fv",
+        vec![Int(0), Int(2), Int(0), Bytes(vec![0; 64])],
+    )]);
+    assert!(matrices.replay(|_| {}).is_err());
+    assert!(mock::calls_to("UniformMatrix4fv").is_empty());
+}
+
+#[test]
+fn replay_rejects_writes_past_a_mapped_range() {
+    use trace::Value::{Bytes, Ids, Int};
+
+    mock::reset();
+    let overflowing = trace_of(vec![
+        call("GenBuffers", vec![Int(1), Ids(vec![1])]),
+        call("BindBuffer", vec![Int(0x8892), Int(1)]),
+        call(
+            "MapBufferRange",
+            vec![Int(0x8892), Int(0), Int(4), Int(0x2)],
+        ),
+        call("UnmapBuffer", vec![Int(0x8892), Bytes(vec![1; 8])]),
+    ]);
+    assert!(matches!(
+        overflowing.replay(|_| {}),
+        Err(trace::TraceError::InvalidArgument {
+            function: "UnmapBuffer",
+            index: 1,
+        })
+    ));
+    assert_eq!(mock::calls_to("UnmapBuffer").len(), 1);
+}
+
+#[test]
+fn replay_rejects_missing_generated_names() {
+    mock::reset();
+    let empty = trace_of(vec![call(
+        "CreateProgram",
+        vec![trace::Value::Ids(Vec::new())],
+    )]);
+    assert!(empty.replay(|_| {}).is_err());
+}
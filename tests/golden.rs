@@ -0,0 +1,144 @@
+mod common;
+
+use common::{assert_golden, compare, headless, DEFAULT_TOLERANCE, EXACT};
+use crean::graphics::gl::ClearFlag;
+use crean::graphics::{
+    gl, Attachment, Buffer, FramebufferBuilder, Image, PrimitiveMode, ShaderProgram, TextureFormat,
+    VertexArray,
+};
+use crean::maths::{Matrix4, Vector, Vector3};
+use std::rc::Rc;
+
+const WIDTH: u32 = 256;
+const HEIGHT: u32 = 256;
+
+/// The scene of the hexagon example, frozen at a fixed rotation.
+#[test]
+#[ignore = "needs Mesa's libEGL, see tests/common/mod.rs"]
+fn hexagon() {
+    let _context = headless(WIDTH, HEIGHT);
+
+    let vertices: [f32; 21] = [
+        0.0, 0.0, 0.0, // center
+        0.0, -0.5, 0.0, // bottom
+        0.5, -0.25, 0.0, // bottom right
+        0.5, 0.25, 0.0, // top right
+        0.0, 0.5, 0.0, // top
+        -0.5, 0.25, 0.0, // top left
+        -0.5, -0.25, 0.0, // bottom left
+    ];
+    let vertices1: [f32; 21] = [
+        0.0, 0.0, 0.0, // center
+        0.0, -0.1, 0.0, // bottom
+        0.1, -0.05, 0.0, // bottom right
+        0.1, 0.05, 0.0, // top right
+        0.0, 0.1, 0.0, // top
+        -0.1, 0.05, 0.0, // top left
+        -0.1, -0.05, 0.0, // bottom left
+    ];
+    let colors: [f32; 21] = [
+        1.0, 1.0, 1.0, // center
+        1.0, 0.0, 0.0, // bottom
+        0.0, 1.0, 0.0, // bottom right
+        0.0, 0.0, 1.0, // top right
+        1.0, 0.0, 0.0, // top
+        0.0, 1.0, 0.0, // top left
+        0.0, 0.0, 1.0, // bottom left
+    ];
+    let indices = [0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 5, 0, 5, 6, 0, 6, 1];
+
+    let colors_buffer = Rc::new(Buffer::array(&colors, 3));
+    let indices_buffer = Rc::new(Buffer::indices(&indices));
+
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_indices_buffer_ref(&indices_buffer);
+    vertex_array.put_array_buffer(0, Buffer::array(&vertices, 3));
+    vertex_array.put_array_buffer_ref(1, &colors_buffer);
+
+    let mut vertex_array1 = VertexArray::new();
+    vertex_array1.put_indices_buffer_ref(&indices_buffer);
+    vertex_array1.put_array_buffer(0, Buffer::array(&vertices1, 3));
+    vertex_array1.put_array_buffer_ref(1, &colors_buffer);
+
+    let mut shader_program =
+        ShaderProgram::from_multi_shader(include_str!("../examples/assets/hexagon.glsl")).unwrap();
+    shader_program.bind();
+    shader_program.define_uniform("uProjection").unwrap();
+    shader_program.define_uniform("uTransformation").unwrap();
+    shader_program
+        .upload_uniform(
+            "uProjection",
+            &Matrix4::perspective(70.0, WIDTH, HEIGHT, 0.1, 1000.0),
+        )
+        .unwrap();
+
+    let mut framebuffer = FramebufferBuilder::new(WIDTH, HEIGHT)
+        .color(Attachment::Texture(TextureFormat::Rgba8))
+        .depth_stencil(Attachment::Renderbuffer(TextureFormat::Depth24Stencil8))
+        .build()
+        .unwrap();
+    framebuffer.bind().unwrap();
+    gl::set_clear_color(0.4, 0.5, 0.7, 1.0);
+    gl::clear(&[ClearFlag::COLOR_BUFFER, ClearFlag::DEPTH_BUFFER]);
+
+    let rotation = Vector3::new(0.0, 0.0, 30.0);
+    shader_program
+        .upload_uniform(
+            "uTransformation",
+            &Matrix4::transformation(
+                &Vector3::new(1.0, 0.0, 0.0),
+                &rotation,
+                &Vector3::new(0.5, 0.5, 0.5),
+            ),
+        )
+        .unwrap();
+    vertex_array.draw(PrimitiveMode::Triangles);
+
+    shader_program
+        .upload_uniform(
+            "uTransformation",
+            &Matrix4::transformation(&Vector3::zero(), &-rotation, &Vector3::new(1.0, 1.0, 1.0)),
+        )
+        .unwrap();
+    vertex_array1.draw(PrimitiveMode::Triangles);
+
+    shader_program.unbind();
+    framebuffer.unbind();
+    gl::check_error().unwrap();
+
+    assert_golden(
+        "hexagon",
+        &framebuffer.read_pixels(0).unwrap(),
+        DEFAULT_TOLERANCE,
+    );
+}
+
+fn filled(width: u32, height: u32, rgba: [u8; 4]) -> Image {
+    let mut image = Image::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            image.set_pixel(x, y, rgba);
+        }
+    }
+    image
+}
+
+#[test]
+fn compare_accepts_identical_images() {
+    let image = filled(4, 4, [10, 20, 30, 255]);
+    assert!(compare(&image, &image, EXACT).is_ok());
+}
+
+#[test]
+fn compare_respects_per_channel_tolerance() {
+    let expected = filled(4, 4, [10, 20, 30, 255]);
+    let mut actual = expected.clone();
+    actual.set_pixel(1, 2, [12, 20, 30, 255]);
+
+    assert!(compare(&actual, &expected, [2, 0, 0, 0]).is_ok());
+
+    let mismatch = compare(&actual, &expected, [1, 255, 255, 255]).unwrap_err();
+    assert_eq!(mismatch.differing_pixels, 1);
+    assert_eq!(mismatch.diff.pixel(1, 2), [255, 0, 0, 255]);
+    assert_ne!(mismatch.diff.pixel(0, 0), [255, 0, 0, 255]);
+}
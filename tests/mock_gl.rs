@@ -0,0 +1,460 @@
+//! Unit tests of the `graphics` types against the recording OpenGL stand-in.
+//! Run with `cargo test --features gl-mock`.
+
+use crean::graphics::gl::mock::{self, Arg, ObjectKind};
+use crean::graphics::{
+    gl, Attachment, BlendState, Buffer, BufferError, BufferUsage, CompareFunc, FramebufferBuilder,
+    IndexType, PrimitiveMode, RenderState, ShaderProgram, Texture2D, TextureError, TextureFormat,
+    VertexArray,
+};
+
+const SHADER_SOURCE: &str = "#type vertex
+void main() {}
+#type fragment
+void main() {}
+";
+
+#[test]
+fn dropping_a_buffer_deletes_it() {
+    mock::reset();
+    let buffer = Buffer::array(&[0.0; 6], 3);
+    let id = mock::calls_to("GenBuffers")[0].ids(1)[0];
+    assert!(mock::is_live(ObjectKind::Buffer, id));
+
+    drop(buffer);
+    assert!(!mock::is_live(ObjectKind::Buffer, id));
+    assert_eq!(mock::calls_to("DeleteBuffers")[0].ids(1), &[id]);
+}
+
+fn bytes(floats: &[f32]) -> Vec<u8> {
+    floats
+        .iter()
+        .flat_map(|float| float.to_ne_bytes())
+        .collect()
+}
+
+#[test]
+fn buffer_updates_upload_only_the_given_range() {
+    mock::reset();
+    let buffer = Buffer::empty_array(12, 3, BufferUsage::Dynamic);
+    let allocation = &mock::calls_to("BufferData")[0];
+    assert_eq!(allocation.int(1), 48);
+    assert_eq!(allocation.args[2], Arg::Pointer(0));
+    assert_eq!(allocation.int(3), BufferUsage::Dynamic.value() as i64);
+    assert_eq!(buffer.len(), 0);
+
+    buffer.update(3, &[1.0f32, 2.0, 3.0]).unwrap();
+    let update = &mock::calls_to("BufferSubData")[0];
+    assert_eq!(update.int(1), 12);
+    assert_eq!(update.int(2), 12);
+    assert_eq!(update.bytes(3), bytes(&[1.0, 2.0, 3.0]));
+    assert_eq!(buffer.len(), 6);
+    assert_eq!(buffer.capacity(), 12);
+
+    assert_eq!(
+        buffer.update(10, &[0.0f32; 3]),
+        Err(BufferError::OutOfBounds {
+            start: 10,
+            end: 13,
+            capacity: 12
+        })
+    );
+}
+
+#[test]
+fn set_data_orphans_the_buffer_while_the_data_fits() {
+    mock::reset();
+    let buffer = Buffer::array_with_usage(&[0.0; 6], 3, BufferUsage::Stream);
+    mock::take_calls();
+
+    buffer.set_data(&[1.0f32; 3]).unwrap();
+    let allocation = &mock::calls_to("BufferData")[0];
+    assert_eq!(allocation.int(1), 24);
+    assert_eq!(allocation.args[2], Arg::Pointer(0));
+    assert_eq!(
+        mock::calls_to("BufferSubData")[0].bytes(3),
+        bytes(&[1.0; 3])
+    );
+    assert_eq!((buffer.len(), buffer.capacity()), (3, 6));
+    mock::take_calls();
+
+    buffer.set_data(&[2.0f32; 9]).unwrap();
+    assert_eq!(mock::calls_to("BufferData")[0].int(1), 36);
+    assert!(!mock::called("BufferSubData"));
+    assert_eq!((buffer.len(), buffer.capacity()), (9, 9));
+}
+
+#[test]
+fn mapped_buffers_are_unmapped_on_drop() {
+    mock::reset();
+    let buffer = Buffer::empty_array(4, 2, BufferUsage::Dynamic);
+    {
+        let mut mapping = buffer.map::<f32>(2..4, gl::MapAccess::WRITE).unwrap();
+        assert_eq!(mapping.len(), 2);
+        mapping.copy_from_slice(&[5.0, 6.0]);
+        assert_eq!(buffer.update(0, &[0.0f32]), Err(BufferError::Mapped));
+        assert!(!mock::called("UnmapBuffer"));
+    }
+
+    let map = &mock::calls_to("MapBufferRange")[0];
+    assert_eq!((map.int(1), map.int(2)), (8, 8));
+    assert_eq!(
+        mock::calls_to("UnmapBuffer")[0].bytes(1),
+        bytes(&[5.0, 6.0])
+    );
+    assert_eq!(buffer.len(), 4);
+    assert!(buffer.update(0, &[0.0f32]).is_ok());
+}
+
+#[test]
+fn buffers_mapped_at_once_get_their_own_memory() {
+    mock::reset();
+    let a = Buffer::empty_array(1, 1, BufferUsage::Dynamic);
+    let b = Buffer::empty_array(64, 1, BufferUsage::Dynamic);
+    {
+        let mut a_mapping = a.map::<f32>(0..1, gl::MapAccess::WRITE).unwrap();
+        let mut b_mapping = b.map::<f32>(0..64, gl::MapAccess::WRITE).unwrap();
+        a_mapping.copy_from_slice(&[1.0]);
+        b_mapping.copy_from_slice(&[2.0; 64]);
+    }
+
+    let unmaps = mock::calls_to("UnmapBuffer");
+    assert_eq!(unmaps.len(), 2);
+    assert_eq!(unmaps[0].bytes(1), bytes(&[2.0; 64]));
+    assert_eq!(unmaps[1].bytes(1), bytes(&[1.0]));
+
+    a.bind();
+    let target = gl::BufferType::ArrayBuffer;
+    let first = unsafe { gl::map_buffer_range(target, 0, 4, gl::MapAccess::WRITE) };
+    let second = unsafe { gl::map_buffer_range(target, 0, 4, gl::MapAccess::WRITE) };
+    assert!(!first.is_null());
+    assert!(second.is_null());
+    assert!(gl::check_error().is_err());
+    assert!(gl::unmap_buffer(target));
+}
+
+#[test]
+#[should_panic(expected = "wrong element type")]
+fn mapping_with_the_wrong_element_size_panics() {
+    mock::reset();
+    let buffer = Buffer::empty_array(4, 2, BufferUsage::Dynamic);
+    let _ = buffer.map::<u8>(0..4, gl::MapAccess::READ);
+}
+
+#[test]
+fn updates_past_the_end_of_memory_are_out_of_bounds() {
+    mock::reset();
+    let buffer = Buffer::empty_array(4, 2, BufferUsage::Dynamic);
+    assert!(matches!(
+        buffer.update(usize::MAX, &[0.0f32; 2]),
+        Err(BufferError::OutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn put_array_buffer_sets_the_attrib_pointer() {
+    mock::reset();
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_array_buffer(2, Buffer::array(&[0.0; 6], 3));
+
+    let pointers = mock::calls_to("VertexAttribPointer");
+    assert_eq!(pointers.len(), 1);
+    assert_eq!(pointers[0].int(0), 2);
+    assert_eq!(pointers[0].int(1), 3);
+    assert_eq!(mock::calls_to("VertexAttribDivisor")[0].int(1), 0);
+}
+
+#[test]
+fn instance_buffers_span_locations() {
+    mock::reset();
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_instance_buffer(1, Buffer::array(&[0.0; 32], 16));
+
+    let pointers = mock::calls_to("VertexAttribPointer");
+    let locations = pointers.iter().map(|call| call.int(0)).collect::<Vec<_>>();
+    assert_eq!(locations, [1, 2, 3, 4]);
+    assert!(mock::calls_to("VertexAttribDivisor")
+        .iter()
+        .all(|call| call.int(1) == 1));
+    assert_eq!(vertex_array.instance_count(), Some(2));
+}
+
+#[test]
+#[should_panic(expected = "instance buffers hold 2")]
+fn drawing_more_instances_than_buffered_panics() {
+    mock::reset();
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_array_buffer(0, Buffer::array(&[0.0; 9], 3));
+    vertex_array.put_instance_buffer(1, Buffer::array(&[0.0; 8], 4));
+    vertex_array.draw_instanced(PrimitiveMode::Triangles, 3);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn instance_buffers_past_the_last_location_panic() {
+    mock::reset();
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_instance_buffer(14, Buffer::array(&[0.0; 16], 16));
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn blitting_past_i32_max_panics() {
+    mock::reset();
+    gl::blit_framebuffer(
+        (i32::MAX as u32, 0, 1, 1),
+        (0, 0, 1, 1),
+        &[gl::ClearFlag::COLOR_BUFFER],
+        gl::TextureFilter::Nearest,
+    );
+}
+
+#[test]
+fn draw_uses_the_index_buffer() {
+    mock::reset();
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_array_buffer(0, Buffer::array(&[0.0; 9], 3));
+    vertex_array.put_indices_buffer(Buffer::indices_u16(&[0, 1, 2, 2, 1, 0]));
+    vertex_array.draw(PrimitiveMode::Triangles);
+
+    let draws = mock::calls_to("DrawElements");
+    assert_eq!(draws.len(), 1);
+    assert_eq!(draws[0].int(0), PrimitiveMode::Triangles.value() as i64);
+    assert_eq!(draws[0].int(1), 6);
+    assert_eq!(draws[0].int(2), IndexType::UnsignedShort.value() as i64);
+}
+
+#[test]
+fn draw_without_indices_draws_every_vertex() {
+    mock::reset();
+    let mut vertex_array = VertexArray::new();
+    vertex_array.put_array_buffer(0, Buffer::array(&[0.0; 12], 3));
+    vertex_array.put_array_buffer(1, Buffer::array(&[0.0; 8], 2));
+    vertex_array.draw(PrimitiveMode::Points);
+
+    let draws = mock::calls_to("DrawArrays");
+    assert_eq!(draws.len(), 1);
+    assert_eq!(draws[0].int(2), 4);
+}
+
+#[test]
+fn shader_program_deletes_its_shaders_after_linking() {
+    mock::reset();
+    let program = ShaderProgram::from_multi_shader(SHADER_SOURCE).unwrap();
+
+    assert_eq!(mock::calls_to("CreateShader").len(), 2);
+    assert!(mock::called("LinkProgram"));
+    assert!(mock::live_objects(ObjectKind::Shader).is_empty());
+    assert_eq!(mock::live_objects(ObjectKind::Program).len(), 1);
+
+    drop(program);
+    assert!(mock::live_objects(ObjectKind::Program).is_empty());
+}
+
+#[test]
+fn uniforms_are_uploaded_to_their_location() {
+    mock::reset();
+    let mut program = ShaderProgram::from_multi_shader(SHADER_SOURCE).unwrap();
+    program.define_uniform("uFirst").unwrap();
+    program.define_uniform("uSecond").unwrap();
+    program.upload_uniform("uSecond", &2.5f32).unwrap();
+
+    let uploads = mock::calls_to("Uniform1f");
+    assert_eq!(uploads.len(), 1);
+    assert_eq!(uploads[0].int(0), 1);
+    assert_eq!(uploads[0].float(1), 2.5);
+}
+
+#[test]
+fn check_error_reports_the_oldest_error() {
+    mock::reset();
+    assert!(gl::check_error().is_ok());
+
+    // GL_INVALID_VALUE, then GL_INVALID_OPERATION.
+    mock::push_error(0x0501);
+    mock::push_error(0x0502);
+    assert!(matches!(gl::check_error(), Err(gl::GLError::InvalidValue)));
+    assert!(gl::check_error().is_ok());
+}
+
+#[test]
+fn framebuffer_attachments_are_created_and_deleted() {
+    mock::reset();
+    let framebuffer = FramebufferBuilder::new(64, 32)
+        .color(Attachment::Texture(TextureFormat::Rgba8))
+        .color(Attachment::Texture(TextureFormat::R32F))
+        .depth_stencil(Attachment::Renderbuffer(TextureFormat::Depth24Stencil8))
+        .build()
+        .unwrap();
+
+    assert_eq!(mock::calls_to("FramebufferTexture2D").len(), 2);
+    assert_eq!(mock::calls_to("FramebufferRenderbuffer").len(), 1);
+    assert_eq!(mock::calls_to("DrawBuffers")[0].ids(1).len(), 2);
+    assert_eq!(mock::live_objects(ObjectKind::Texture).len(), 2);
+
+    drop(framebuffer);
+    assert!(mock::live_objects(ObjectKind::Texture).is_empty());
+    assert!(mock::live_objects(ObjectKind::Renderbuffer).is_empty());
+    assert!(mock::live_objects(ObjectKind::Framebuffer).is_empty());
+}
+
+/// Starts from a known render state with an empty log.
+fn reset_render_state() {
+    RenderState::invalidate();
+    RenderState::INITIAL.apply();
+    mock::reset();
+}
+
+#[test]
+fn applying_a_render_state_again_changes_nothing() {
+    reset_render_state();
+    RenderState::default().apply();
+    assert_eq!(
+        mock::calls_to("Enable")[0].int(0),
+        gl::Capability::DepthTest.value() as i64
+    );
+    assert_eq!(
+        mock::calls_to("DepthFunc")[0].int(0),
+        CompareFunc::Less.value() as i64
+    );
+    assert_eq!(mock::take_calls().len(), 2);
+
+    RenderState::default().apply();
+    assert!(mock::calls().is_empty());
+}
+
+#[test]
+fn only_the_changed_render_state_is_set() {
+    reset_render_state();
+    let state = RenderState {
+        blend: Some(BlendState::ALPHA),
+        depth_write: false,
+        ..RenderState::INITIAL
+    };
+    state.apply();
+    assert!(mock::called("BlendFuncSeparate"));
+    assert_eq!(mock::calls_to("DepthMask")[0].int(0), 0);
+    assert!(!mock::called("DepthFunc"));
+    assert!(!mock::called("CullFace"));
+    mock::take_calls();
+
+    RenderState {
+        blend: Some(BlendState::ADDITIVE),
+        ..state
+    }
+    .apply();
+    assert!(mock::called("BlendFuncSeparate"));
+    assert!(!mock::called("Enable"));
+    assert!(!mock::called("DepthMask"));
+}
+
+#[test]
+fn invalidated_render_state_is_set_in_full() {
+    reset_render_state();
+    RenderState::invalidate();
+    assert_eq!(RenderState::current(), None);
+
+    RenderState::INITIAL.apply();
+    for function in [
+        "DepthMask",
+        "FrontFace",
+        "StencilMask",
+        "PolygonMode",
+        "ColorMask",
+    ] {
+        assert!(mock::called(function), "{} wasn't called", function);
+    }
+    assert_eq!(mock::calls_to("Disable").len(), 5);
+    assert_eq!(RenderState::current(), Some(RenderState::INITIAL));
+}
+
+#[test]
+fn binding_what_is_bound_is_skipped() {
+    mock::reset();
+    gl::reset_bind_stats();
+    let buffer = Buffer::array(&[0.0; 3], 3);
+    buffer.bind();
+    assert_eq!(mock::calls_to("BindBuffer").len(), 1);
+    assert_eq!(
+        gl::bind_stats(),
+        gl::BindStats {
+            issued: 1,
+            skipped: 1
+        }
+    );
+
+    let program = ShaderProgram::from_multi_shader(SHADER_SOURCE).unwrap();
+    program.bind();
+    program.bind();
+    assert_eq!(mock::calls_to("UseProgram").len(), 1);
+}
+
+#[test]
+fn vertex_arrays_keep_their_own_element_array_buffer() {
+    mock::reset();
+    let indices = std::rc::Rc::new(Buffer::indices(&[0, 1, 2]));
+    let mut first = VertexArray::new();
+    let mut second = VertexArray::new();
+    mock::take_calls();
+
+    first.put_indices_buffer_ref(&indices);
+    second.put_indices_buffer_ref(&indices);
+    let index_binds = mock::calls_to("BindBuffer")
+        .into_iter()
+        .filter(|call| call.int(1) != 0)
+        .count();
+    assert_eq!(index_binds, 2);
+}
+
+#[test]
+#[should_panic(expected = "pixel components")]
+fn uploading_too_few_pixels_panics() {
+    mock::reset();
+    gl::texture_image_2d(TextureFormat::Rgba8, 4, 4, &[0u8; 15]);
+}
+
+#[test]
+#[should_panic(expected = "pixel components")]
+fn reading_into_too_small_a_buffer_panics() {
+    mock::reset();
+    let mut pixels = [0u8; 4 * 4 * 4 - 1];
+    gl::read_pixels(0, 0, 4, 4, TextureFormat::Rgba8, &mut pixels);
+}
+
+#[test]
+fn regions_past_the_texture_are_rejected() {
+    mock::reset();
+    let mut texture = Texture2D::new(4, 4, TextureFormat::R8);
+    let overflowing = texture.upload_region(u32::MAX, 0, 2, 1, &[0u8; 2]);
+    assert!(matches!(overflowing, Err(TextureError::OutOfBounds)));
+    assert!(texture.upload_region(2, 2, 2, 2, &[0u8; 4]).is_ok());
+}
+
+#[test]
+fn textures_are_tracked_per_unit() {
+    mock::reset();
+    let texture = Texture2D::new(4, 4, TextureFormat::Rgba8);
+    texture.bind(0);
+    texture.bind(1);
+    mock::take_calls();
+
+    texture.bind(0);
+    texture.bind(1);
+    assert!(!mock::called("BindTexture"));
+    // Only switching to unit 1 and back, as `bind` leaves unit 0 active.
+    assert_eq!(mock::calls_to("ActiveTexture").len(), 2);
+}
+
+#[test]
+fn deleted_objects_are_bound_again() {
+    mock::reset();
+    let program = ShaderProgram::from_multi_shader(SHADER_SOURCE).unwrap();
+    program.bind();
+    drop(program);
+
+    // The mock hands out new names, so pretend the name is reused.
+    mock::reset();
+    let program = ShaderProgram::from_multi_shader(SHADER_SOURCE).unwrap();
+    program.bind();
+    assert_eq!(mock::calls_to("UseProgram").len(), 1);
+}